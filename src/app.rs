@@ -1,28 +1,44 @@
 use leptos::prelude::*;
+use std::collections::BTreeMap;
 
 use crate::definition::{觸鍵方式, 鍵組};
 use crate::engine::{微觀引擎, 微觀引擎輸出信號, 視圖輸入信號};
 use crate::gear::{
+    adaptive::{加練標題, 加練機關輸出信號},
     assignment::{作業, 作業機關輸出信號},
     caption::字幕機關輸出信號,
     chord::{並擊機關輸出信號, 並擊狀態},
+    curriculum::{解鎖標題, 解鎖機關輸出信號, 達標正確率, 達標速度},
+    ghost::影子機關輸出信號,
+    heatmap::熱圖機關輸出信號,
+    history::練習紀錄機關輸出信號,
+    host_layout::系統佈局機關輸出信號,
     key_press::連擊機關輸出信號,
     layout::{
         佈局機關輸出信號,
         功能鍵::{回車鍵, 製表鍵, 退出鍵, 退格鍵},
     },
     mode::{工作模式, 工作模式機關輸出信號},
+    replay::錄放機關輸出信號,
+    retry::重練機關輸出信號,
+    statistics::統計機關輸出信號,
     theory::輸入方案機關輸出信號,
 };
-use crate::key_code::KeyCode;
+use crate::key_code::{鍵值字符轉換, KeyCode};
+use crate::rime_schema::導出並擊方案;
 use crate::view::{
     caption::Rime字幕屏,
+    curriculum::Rime解鎖進度,
     exercise_menu::Rime練習題選單,
+    heatmap::Rime最差片段表,
+    history::Rime練習紀錄,
     input_code::{
         回顯區佈局, 編碼欄顯示選項, Rime反查輸入欄, Rime編碼回顯區, Rime編碼欄
     },
     keyboard::{鍵面動態着色法, Rime鍵圖, Rime鍵盤圖},
     layout_menu::Rime配列選單,
+    replay::Rime錄放面板,
+    statistics::Rime練習小結,
     status_bar::Rime狀態欄,
     theory_menu::Rime方案選單,
 };
@@ -31,6 +47,7 @@ use crate::view::{
 struct 並擊對標動態 {
     目標並擊: Signal<Option<鍵組>>,
     實況並擊: Signal<並擊狀態>,
+    鍵位熱度: Signal<BTreeMap<KeyCode, f64>>,
 }
 
 impl 鍵面動態着色法 for 並擊對標動態 {
@@ -48,6 +65,10 @@ impl 鍵面動態着色法 for 並擊對標動態 {
     fn 是否擊中(&self, 鍵: KeyCode) -> bool {
         self.實況並擊.read().累計擊鍵.0.contains(&鍵)
     }
+
+    fn 熱度(&self, 鍵: KeyCode) -> Option<f64> {
+        self.鍵位熱度.read().get(&鍵).copied()
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -80,10 +101,19 @@ pub fn Rime打字機應用() -> impl IntoView {
         方案,
         模式,
         佈局,
+        系統,
         作業,
         字幕,
         連擊,
         並擊,
+        統計,
+        紀錄,
+        熱圖,
+        加練,
+        解鎖,
+        重練,
+        錄放,
+        影子,
         視圖,
     } = 微觀引擎();
     let 輸入方案機關輸出信號 {
@@ -93,11 +123,21 @@ pub fn Rime打字機應用() -> impl IntoView {
         指法,
         ..
     } = 方案;
+    let 系統佈局機關輸出信號 {
+        系統佈局,
+        切換系統佈局,
+        ..
+    } = 系統;
     let 佈局機關輸出信號 {
         實際配列,
         選用配列,
+        配列幾何,
+        導入配列,
+        缺失鍵位,
+        替代缺鍵,
+        替代鍵位,
+        切換替代缺鍵,
         當選盤面,
-        ..
     } = 佈局;
     let 工作模式機關輸出信號 {
         現行工作模式,
@@ -113,6 +153,9 @@ pub fn Rime打字機應用() -> impl IntoView {
         佈置作業,
         目標作業內容,
         目標輸入碼片段,
+        一題打完,
+        推進方式,
+        切換推進方式,
         ..
     } = 作業;
     let 字幕機關輸出信號 { .. } = 字幕;
@@ -131,8 +174,55 @@ pub fn Rime打字機應用() -> impl IntoView {
         並擊所得拼音,
         並擊完成,
         並擊成功,
+        並擊判定,
+        切換並擊判定,
         ..
     } = 並擊;
+    let 統計機關輸出信號 {
+        練習統計, 統計摘要,
+    ..
+    } = 統計;
+    let 練習紀錄機關輸出信號 {
+        方案紀錄, 清除紀錄
+    } = 紀錄;
+    let 熱圖機關輸出信號 {
+        熱圖選項,
+        切換熱圖,
+        鍵位熱度,
+        最差片段,
+    } = 熱圖;
+    let 加練機關輸出信號 { 佈置加練 } = 加練;
+    let 解鎖機關輸出信號 {
+        現行課程,
+        課程評定,
+        佈置解鎖練習,
+    } = 解鎖;
+    let 重練機關輸出信號 {
+        待重練片段,
+        提議重練,
+        佈置重練,
+        ..
+    } = 重練;
+    let 錄放機關輸出信號 {
+        錄製中,
+        放映中,
+        錄像,
+        重放倍速,
+        開始錄製,
+        停止錄製,
+        開始重放,
+        停止重放,
+        切換重放倍速,
+        導入錄像,
+        ..
+    } = 錄放;
+    let 影子機關輸出信號 {
+        影子來源,
+        影子字幕,
+        影子時差,
+        切換影子來源,
+        ..
+    } = 影子;
     let 視圖輸入信號 {
         視圖落鍵動作,
         視圖抬鍵動作,
@@ -200,6 +290,15 @@ pub fn Rime打字機應用() -> impl IntoView {
         佈置作業(作業::自訂(現行方案(), 反查碼));
     };
     let 當選題號 = Signal::derive(move || 當前作業.read().題號);
+    let 當選生成題 = Signal::derive(move || 當前作業.read().生成題.as_ref().map(|題| 題.標題));
+    let 選中生成題動作 = move |標題| {
+        match 標題 {
+            加練標題 => 佈置加練(),
+            解鎖標題 => 佈置解鎖練習(),
+            _ => {}
+        }
+        關閉輸入欄();
+    };
     let 選中題號動作 = move |題號| {
         佈置作業(作業::練習題(現行方案(), 題號));
         關閉輸入欄();
@@ -212,8 +311,113 @@ pub fn Rime打字機應用() -> impl IntoView {
         選用配列(Some(選中項));
         關閉輸入欄();
     };
+    let 導入配列動作 = move |文本| {
+        let 結果 = 導入配列(文本);
+        if 結果.is_ok() {
+            關閉輸入欄();
+        }
+        結果
+    };
     let 方案配套練習題 = Signal::derive(move || 現行方案().配套練習題().unwrap_or(&[]));
     let 方案指定佈局 = Signal::derive(move || *方案定義.read().佈局);
+    let 導出方案文件 = Signal::derive(move || {
+        (指法() == 觸鍵方式::並擊)
+            .then(|| 導出並擊方案(&方案定義.read(), 現行方案.read().slug()))
+    });
+
+    let 顯示練習小結 = move || 一題打完() && 統計摘要.read().片段數 > 0;
+    let 衆片段紀錄 = Signal::derive(move || 練習統計.read().衆片段().to_vec());
+    let 片段單位 = Signal::derive(move || match 指法() {
+        觸鍵方式::並擊 => "音節",
+        觸鍵方式::連擊 => "字",
+    });
+
+    let (顯示練習紀錄, 設置顯示練習紀錄) = signal(false);
+    let 紀錄條數 = Signal::derive(move || 方案紀錄.read().len());
+    let 並擊判定名稱 =
+        Signal::derive(move || (指法() == 觸鍵方式::並擊).then(|| 並擊判定.read().to_string()));
+    let 熱圖名稱 = Signal::derive(move || {
+        熱圖選項().map_or_else(
+            || String::from("關"),
+            |選項| format!("{}{}", 選項.來源, 選項.指標),
+        )
+    });
+
+    let 已解鎖鍵數 =
+        Signal::derive(move || 現行課程.read().as_ref().map_or(0, |課程| 課程.已解鎖.len()));
+    let 全部鍵數 = Signal::derive(move || 現行課程.read().as_ref().map_or(0, |課程| 課程.全部鍵數));
+    let 新鍵字根 = Signal::derive(move || {
+        現行課程
+            .read()
+            .as_ref()
+            .and_then(|課程| 課程.新鍵)
+            .map(|鍵| 方案定義.read().寫成字根碼(&鍵))
+    });
+    let 課程評定表示 = Signal::derive(move || {
+        課程評定
+            .read()
+            .as_ref()
+            .map(|評定| match (評定.達標, &評定.解鎖字根) {
+                (true, Some(字根)) => format!("達標, 解鎖新鍵 {字根}"),
+                (true, None) => String::from("達標, 已解鎖全部鍵位"),
+                (false, _) => format!(
+                    "未達標: 須首擊正確 {:.0}%、每分鐘 {:.0} {}",
+                    達標正確率 * 100.0,
+                    達標速度(指法()),
+                    片段單位()
+                ),
+            })
+    });
+
+    let (顯示錄放面板, 設置顯示錄放面板) = signal(false);
+    let 錄像狀態 = Signal::derive(move || {
+        if 錄製中() {
+            "錄製中"
+        } else if 放映中() {
+            "重放中"
+        } else {
+            ""
+        }
+    });
+    let 錄像摘要 = Signal::derive(move || {
+        錄像
+            .read()
+            .as_ref()
+            .map(|錄像| format!("{} 擊, {:.1} 秒", 錄像.落鍵數(), 錄像.時長() / 1000.0))
+    });
+    let 導出錄像 = Signal::derive(move || {
+        錄像
+            .read()
+            .as_ref()
+            .and_then(|錄像| serde_json::to_string(錄像).ok())
+    });
+
+    // 如 `/`, 已由 `.` 鍵代替則作 `/→.`
+    let 缺鍵提示 = Signal::derive(move || {
+        let 鍵名 = |鍵碼: KeyCode| {
+            鍵值字符轉換(鍵碼).map_or_else(|| format!("{鍵碼:?}"), |字符| 字符.to_string())
+        };
+        let 替代鍵位 = 替代鍵位.read();
+        let 衆缺鍵 = 缺失鍵位
+            .read()
+            .iter()
+            .map(
+                |&缺鍵| match 替代鍵位.0.iter().find(|&&(_, 代替)| 代替 == 缺鍵) {
+                    Some(&(鍵碼, _)) => format!("{}→{}", 鍵名(缺鍵), 鍵名(鍵碼)),
+                    None => 鍵名(缺鍵),
+                },
+            )
+            .collect::<Vec<_>>();
+        if 衆缺鍵.is_empty() {
+            return None;
+        }
+        let 提示 = 衆缺鍵.join(" ");
+        Some(if 替代缺鍵() && 替代鍵位.0.is_empty() {
+            format!("{提示} (無閒置鍵)")
+        } else {
+            提示
+        })
+    });
 
     let 標註功能鍵 = |功能鍵| Signal::derive(move || 功能鍵);
 
@@ -225,12 +429,31 @@ pub fn Rime打字機應用() -> impl IntoView {
     let 並擊動態 = 並擊對標動態 {
         目標並擊: 目標鍵位表示,
         實況並擊: 並擊狀態流.into(),
+        鍵位熱度: 鍵位熱度.into(),
     };
 
     let 開關狀態 = 功能鍵開關狀態 { 現行工作模式 };
 
     view! {
-        <Rime字幕屏 是否顯示光標={是否顯示光標} 按進度顯示字幕={字幕.段落表示}/>
+        <Rime字幕屏 是否顯示光標={是否顯示光標} 按進度顯示字幕={字幕.段落表示} 影子={影子字幕}/>
+        <Show when={顯示練習小結}>
+            <Rime練習小結
+                摘要={統計摘要.into()}
+                衆片段={衆片段紀錄}
+                片段單位={片段單位}
+                影子時差={影子時差.into()}
+                提議重練={Signal::derive(move || 提議重練().then(|| 待重練片段.read().len()))}
+                點擊重練={佈置重練}
+            />
+        </Show>
+        <Show when={move || 現行課程.read().is_some()}>
+            <Rime解鎖進度
+                已解鎖鍵數={已解鎖鍵數}
+                全部鍵數={全部鍵數}
+                新鍵字根={新鍵字根}
+                評定={課程評定表示}
+            />
+        </Show>
         <div class="echo-bar">
             <div title="重新錄入／選練習題">
                 <Rime鍵圖
@@ -267,6 +490,8 @@ pub fn Rime打字機應用() -> impl IntoView {
                             預設練習題={方案配套練習題}
                             當選題號={當選題號}
                             選中題號={選中題號動作}
+                            當選生成題={當選生成題}
+                            選中生成題={選中生成題動作}
                         />
                     }.into_any(),
                     工作模式::選擇輸入方案 => view! {
@@ -279,6 +504,7 @@ pub fn Rime打字機應用() -> impl IntoView {
                         <Rime配列選單
                             已選配列={實際配列}
                             選中配列={選中配列動作}
+                            導入配列={導入配列動作}
                         />
                     }.into_any(),
                 }
@@ -299,8 +525,10 @@ pub fn Rime打字機應用() -> impl IntoView {
         </div>
         <Rime鍵盤圖
             鍵盤佈局={方案指定佈局}
-            目標盤面={當選盤面}
-            配列={實際配列}
+            目標盤面={當選盤面.into()}
+            配列幾何={配列幾何.into()}
+            系統佈局={系統佈局.into()}
+            替代鍵位={替代鍵位.into()}
             着色法={並擊動態}
             落鍵動作={視圖落鍵動作}
             抬鍵動作={視圖抬鍵動作}
@@ -309,8 +537,58 @@ pub fn Rime打字機應用() -> impl IntoView {
         <Rime狀態欄
             現行方案={現行方案}
             已選配列={實際配列}
+            系統佈局名稱={Signal::derive(move || 系統佈局.read().to_string())}
+            缺鍵提示={缺鍵提示}
+            導出方案文件={導出方案文件}
+            紀錄條數={紀錄條數}
+            熱圖名稱={熱圖名稱}
+            並擊判定名稱={並擊判定名稱}
+            推進方式名稱={Signal::derive(move || 推進方式.read().to_string())}
+            重練片段數={Signal::derive(move || 待重練片段.read().len())}
             點擊方案={move || 開啓方案選單()}
             點擊配列={move || 開啓配列選單()}
+            點擊系統佈局={切換系統佈局}
+            點擊缺鍵={切換替代缺鍵}
+            點擊紀錄={move || 設置顯示練習紀錄.update(|顯示| *顯示 = !*顯示)}
+            點擊熱圖={切換熱圖}
+            點擊並擊判定={切換並擊判定}
+            點擊推進方式={切換推進方式}
+            點擊重練={佈置重練}
+            錄像狀態={錄像狀態}
+            點擊錄像={move || 設置顯示錄放面板.update(|顯示| *顯示 = !*顯示)}
+            影子名稱={Signal::derive(move || 影子來源.read().to_string())}
+            點擊影子={切換影子來源}
         />
+        {move || 熱圖選項().map(|選項| view! {
+            <Rime最差片段表
+                選項={Signal::derive(move || 選項)}
+                衆片段={最差片段.into()}
+                片段單位={片段單位}
+            />
+        })}
+        <Show when={顯示練習紀錄}>
+            <Rime練習紀錄
+                衆成績={方案紀錄.into()}
+                片段單位={片段單位}
+                清除紀錄={清除紀錄}
+                關閉={move || 設置顯示練習紀錄(false)}
+            />
+        </Show>
+        <Show when={顯示錄放面板}>
+            <Rime錄放面板
+                錄製中={錄製中.into()}
+                放映中={放映中.into()}
+                錄像摘要={錄像摘要}
+                導出文本={導出錄像}
+                重放倍速={重放倍速.into()}
+                開始錄製={開始錄製}
+                停止錄製={停止錄製}
+                開始重放={開始重放}
+                停止重放={停止重放}
+                切換重放倍速={切換重放倍速}
+                導入錄像={導入錄像}
+                關閉={move || 設置顯示錄放面板(false)}
+            />
+        </Show>
     }
 }
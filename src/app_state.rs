@@ -16,16 +16,18 @@ pub struct AppParams {
     pub theory: String,
 }
 
-// 查詢參數: ?drill=...&layout=...
+// 查詢參數: ?drill=...&layout=...&scheme=...&kle=...
 #[derive(Params, PartialEq, Clone, Debug)]
 pub struct AppQuery {
     pub drill: Option<String>,
     pub layout: Option<String>,
+    pub scheme: Option<String>,
+    pub kle: Option<String>,
 }
 
 impl 方案選項 {
     // 生成 URL
-    fn slug(&self) -> &str {
+    pub fn slug(&self) -> &str {
         match self {
             方案選項::宮保拼音 => "combo_pinyin",
             方案選項::拉丁字母 => "alphabet",
@@ -41,54 +43,64 @@ impl 方案選項 {
             方案選項::宮保注音 => "combo_zhuyin",
             方案選項::倉頡 => "cangjie",
             方案選項::倉頡七曜並擊 => "cangjie_luminary_chords",
+            方案選項::自訂方案 => "custom",
         }
     }
     // 解析 URL
-    fn from_slug(s: &str) -> Result<Self, ()> {
+    pub fn from_slug(s: &str) -> Option<Self> {
         match s {
-            "combo_pinyin" => Ok(方案選項::宮保拼音),
-            "alphabet" => Ok(方案選項::拉丁字母),
-            "old_chinese" => Ok(方案選項::上古漢語),
-            "early_middle_chinese" => Ok(方案選項::早期中古漢語),
-            "late_middle_chinese" => Ok(方案選項::晚期中古漢語),
-            "old_mandarin" => Ok(方案選項::近古漢語),
-            "modern_chinese" => Ok(方案選項::現代漢語),
-            "cantonese" => Ok(方案選項::粵語),
-            "combo_jyutping" => Ok(方案選項::宮保粵拼),
-            "zhuyin" => Ok(方案選項::注音),
-            "detenele" => Ok(方案選項::動態能力注音),
-            "combo_zhuyin" => Ok(方案選項::宮保注音),
-            "cangjie" => Ok(方案選項::倉頡),
-            "cangjie_luminary_chords" => Ok(方案選項::倉頡七曜並擊),
-            _ => Err(()), // 解析失敗
+            "combo_pinyin" => Some(方案選項::宮保拼音),
+            "alphabet" => Some(方案選項::拉丁字母),
+            "old_chinese" => Some(方案選項::上古漢語),
+            "early_middle_chinese" => Some(方案選項::早期中古漢語),
+            "late_middle_chinese" => Some(方案選項::晚期中古漢語),
+            "old_mandarin" => Some(方案選項::近古漢語),
+            "modern_chinese" => Some(方案選項::現代漢語),
+            "cantonese" => Some(方案選項::粵語),
+            "combo_jyutping" => Some(方案選項::宮保粵拼),
+            "zhuyin" => Some(方案選項::注音),
+            "detenele" => Some(方案選項::動態能力注音),
+            "combo_zhuyin" => Some(方案選項::宮保注音),
+            "cangjie" => Some(方案選項::倉頡),
+            "cangjie_luminary_chords" => Some(方案選項::倉頡七曜並擊),
+            "custom" => Some(方案選項::自訂方案),
+            _ => None, // 解析失敗
         }
     }
 }
 
 impl 配列 {
     // 生成 URL
-    fn slug(&self) -> &str {
+    pub fn slug(&self) -> &str {
         match self {
             配列::主鍵盤區 => "qwerty",
+            配列::歐規主鍵盤區 => "iso",
+            配列::日規主鍵盤區 => "jis",
             配列::字母鍵盤 => "alphabet",
             配列::正交直列 => "ortho",
             配列::直列分體 => "ortho_split",
             配列::正交直列帶數字行 => "ortho_with_number_row",
             配列::直列分體帶數字行 => "ortho_split_with_number_row",
             配列::縱向錯列分體 => "columnar_split",
+            配列::拇指簇分體 => "thumb_cluster_split",
+            配列::自訂 => "custom",
         }
     }
     // 解析 URL
-    fn from_slug(s: &str) -> Result<Self, ()> {
+    pub fn from_slug(s: &str) -> Option<Self> {
         match s {
-            "qwerty" => Ok(配列::主鍵盤區),
-            "alphabet" => Ok(配列::字母鍵盤),
-            "ortho" => Ok(配列::正交直列),
-            "ortho_split" => Ok(配列::直列分體),
-            "ortho_with_number_row" => Ok(配列::正交直列帶數字行),
-            "ortho_split_with_number_row" => Ok(配列::直列分體帶數字行),
-            "columnar_split" => Ok(配列::縱向錯列分體),
-            _ => Err(()), // 解析失敗
+            "qwerty" => Some(配列::主鍵盤區),
+            "iso" => Some(配列::歐規主鍵盤區),
+            "jis" => Some(配列::日規主鍵盤區),
+            "alphabet" => Some(配列::字母鍵盤),
+            "ortho" => Some(配列::正交直列),
+            "ortho_split" => Some(配列::直列分體),
+            "ortho_with_number_row" => Some(配列::正交直列帶數字行),
+            "ortho_split_with_number_row" => Some(配列::直列分體帶數字行),
+            "columnar_split" => Some(配列::縱向錯列分體),
+            "thumb_cluster_split" => Some(配列::拇指簇分體),
+            "custom" => Some(配列::自訂),
+            _ => None, // 解析失敗
         }
     }
 }
@@ -102,6 +114,8 @@ pub struct AppState {
     pub theory: Signal<方案選項>,
     pub drill: Signal<Option<usize>>,
     pub layout: Signal<Option<配列>>,
+    pub scheme: Signal<Option<String>>,
+    pub kle: Signal<Option<String>>,
     pub set_theory: 選用方案動作,
     pub set_drill: 選用練習題動作,
     pub set_layout: 選用配列動作,
@@ -119,7 +133,7 @@ pub fn use_app_state() -> AppState {
             .as_ref()
             .ok()
             .map(|x| x.theory.clone())
-            .and_then(|s| 方案選項::from_slug(&s).ok())
+            .and_then(|s| 方案選項::from_slug(&s))
             .unwrap_or_default()
     });
 
@@ -139,14 +153,30 @@ pub fn use_app_state() -> AppState {
             .as_ref()
             .ok()
             .and_then(|x| x.layout.clone())
-            .and_then(|s| 配列::from_slug(&s).ok())
+            .and_then(|s| 配列::from_slug(&s))
     });
 
+    // 從 query 獲取 scheme, 即自訂方案的數據文件網址
+    let scheme = Signal::derive(move || query.read().as_ref().ok().and_then(|x| x.scheme.clone()));
+
+    // 從 query 獲取 kle, 即配列文件網址. 載入後存於瀏覽器, 換頁不必保留
+    let kle = Signal::derive(move || query.read().as_ref().ok().and_then(|x| x.kle.clone()));
+
     let navigate =
         move |new_theory: 方案選項, new_drill: Option<usize>, new_layout: Option<配列>| {
+            // 自訂方案須保留數據文件網址
+            let new_scheme = (new_theory == 方案選項::自訂方案)
+                .then(|| scheme.get_untracked())
+                .flatten();
             let query_items = [
                 new_drill.map(|drill| format!("drill={}", drill)),
                 new_layout.map(|layout| format!("layout={}", layout.slug())),
+                new_scheme.map(|scheme| {
+                    format!(
+                        "scheme={}",
+                        String::from(js_sys::encode_uri_component(&scheme))
+                    )
+                }),
             ];
             let query_str = query_items
                 .into_iter()
@@ -187,6 +217,8 @@ pub fn use_app_state() -> AppState {
         theory,
         drill,
         layout,
+        scheme,
+        kle,
         set_theory,
         set_drill,
         set_layout,
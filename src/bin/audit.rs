@@ -0,0 +1,241 @@
+//! 並擊方案體檢
+//!
+//! 用法:
+//! - `typewriter-audit roundtrip [方案] [音節表文件...] [--assets=目錄]`
+//! - `typewriter-audit chords [方案] [--max=鍵數]`
+//!
+//! `roundtrip`: 逐個音節覈對「拆分—按鍵—轉寫」能否回到原拼式, 列出打不出或打出來走樣的音節.
+//! 有音節打不出時, 以狀態碼 1 退出.
+//! 音節表文件以空白分隔各拼式; 不給文件則取方案配套練習題中出現的音節.
+//! 網頁版從網址求取的練習題, 在資源目錄中讀取, 缺省爲工作目錄下的 `assets`.
+//!
+//! `chords`: 遍歷字根表諸鍵不多於若干鍵的組合 (默認 4 鍵), 列出各並擊轉寫所得的拼式,
+//! 以及重碼的拼式、不合拼式與被消除的並擊. 供設計、審閱並擊佈局之用.
+//!
+//! 方案爲網址中的方案代號, 如 `combo_pinyin`; 亦可爲方案數據文件的路徑.
+
+#![allow(confusable_idents)]
+#![allow(uncommon_codepoints)]
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    match 體檢::運行() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(錯誤) => {
+            eprintln!("{錯誤}");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// 命令行程序不參與網頁構建
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod 體檢 {
+    use std::collections::BTreeSet;
+    use std::path::{Path, PathBuf};
+
+    use typewriter::definition::輸入方案定義;
+    use typewriter::drill::{練習題, 題目來源};
+    use typewriter::gear::{
+        assignment::解析習題,
+        theory::{方案選項, 生成方案定義, 輸入方案環境},
+    };
+    use typewriter::key_code::{鍵值字符轉換, KeyCode};
+    use typewriter::theory_audit::{
+        並擊轉寫, 往返問題, 碼表中的音節, 覈對往返, 遍歷並擊空間
+    };
+    use typewriter::theory_file::載入方案文件;
+
+    const 用法: &str = "用法: typewriter-audit roundtrip [方案] [音節表文件...] [--assets=目錄]
+      typewriter-audit chords [方案] [--max=鍵數]";
+
+    const 默認鍵數上限: usize = 4;
+
+    fn 載入方案(
+        代號或路徑: &str,
+    ) -> Result<(輸入方案定義<'static>, &'static [練習題<'static>]), String> {
+        match 方案選項::from_slug(代號或路徑) {
+            Some(選項) => Ok((
+                生成方案定義(選項, 輸入方案環境::default())
+                    .ok_or_else(|| format!("未知方案: {代號或路徑}"))?,
+                選項.配套練習題().unwrap_or(&[]),
+            )),
+            None => {
+                let 方案文本 = std::fs::read_to_string(代號或路徑)
+                    .map_err(|錯誤| format!("無法讀取方案文件 {代號或路徑}: {錯誤}"))?;
+                let 方案 = 載入方案文件(&方案文本).map_err(|錯誤| 錯誤.to_string())?;
+                Ok((方案, &[][..]))
+            }
+        }
+    }
+
+    /// 網頁版從網址求取的練習題, 讀取資源目錄中的同名文件
+    fn 練習題中的音節(
+        方案: &輸入方案定義,
+        衆練習題: &[練習題],
+        資源目錄: &Path,
+    ) -> BTreeSet<String> {
+        衆練習題
+            .iter()
+            .flat_map(|題| match &題.題目 {
+                題目來源::內建 { 編碼, .. } => 碼表中的音節(方案, 編碼),
+                題目來源::求取 { 網址 } => 網址
+                    .strip_prefix("/typewriter/static/")
+                    .and_then(|文件| std::fs::read_to_string(資源目錄.join(文件)).ok())
+                    .map(|習題文本| 碼表中的音節(方案, &解析習題(&習題文本).碼表))
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn 讀取音節表(衆文件: &[String]) -> Result<BTreeSet<String>, String> {
+        let mut 音節表 = BTreeSet::new();
+        for 文件 in 衆文件 {
+            let 文本 = std::fs::read_to_string(文件)
+                .map_err(|錯誤| format!("無法讀取音節表 {文件}: {錯誤}"))?;
+            音節表.extend(文本.split_whitespace().map(str::to_owned));
+        }
+        Ok(音節表)
+    }
+
+    fn 覈對往返一致(
+        方案代號: &str,
+        衆文件: &[String],
+        資源目錄: &Path,
+    ) -> Result<bool, String> {
+        let (方案, 衆練習題) = 載入方案(方案代號)?;
+        let 音節表 = if 衆文件.is_empty() {
+            練習題中的音節(&方案, 衆練習題, 資源目錄)
+        } else {
+            讀取音節表(衆文件)?
+        };
+        let 衆問題 = 覈對往返(&方案, &音節表);
+        for 條目 in &衆問題 {
+            let 說明 = match &條目.問題 {
+                往返問題::不合拼式 => "不合拼式".to_owned(),
+                往返問題::無從拆分 => "無從拆分".to_owned(),
+                往返問題::缺少字根 { 字根碼, 缺少 } => {
+                    format!("缺少字根\t{字根碼}\t字根表未收: {缺少}")
+                }
+                往返問題::無從轉寫 {
+                    字根碼, 並擊碼
+                } => {
+                    format!("無從轉寫\t{字根碼}\t按出 {並擊碼}")
+                }
+                往返問題::拼式走樣 {
+                    字根碼,
+                    並擊碼,
+                    轉寫所得,
+                    仍可擊中,
+                } => format!(
+                    "拼式走樣\t{字根碼}\t按出 {並擊碼} → {轉寫所得}{}",
+                    if *仍可擊中 { " (仍可擊中)" } else { "" }
+                ),
+            };
+            println!("{}\t{說明}", 條目.拼式);
+        }
+        let 打不出 = 衆問題.iter().filter(|條目| !條目.問題.能否擊中()).count();
+        println!(
+            "覈對 {} 個音節, {} 個有問題, 其中 {打不出} 個打不出",
+            音節表.len(),
+            衆問題.len()
+        );
+        Ok(打不出 == 0)
+    }
+
+    fn 鍵位表示(鍵碼: KeyCode) -> String {
+        鍵值字符轉換(鍵碼)
+            .filter(char::is_ascii_graphic)
+            .map(|字符| 字符.to_ascii_uppercase().to_string())
+            .unwrap_or_else(|| format!("{鍵碼:?}"))
+    }
+
+    fn 並擊表示(條目: &並擊轉寫) -> String {
+        let 鍵位 = 條目
+            .鍵位
+            .0
+            .iter()
+            .map(|&鍵碼| 鍵位表示(鍵碼))
+            .collect::<Vec<_>>()
+            .join("+");
+        format!("{}\t{鍵位}", 條目.並擊碼)
+    }
+
+    fn 遍歷並擊(方案代號: &str, 鍵數上限: usize) -> Result<bool, String> {
+        let (方案, _) = 載入方案(方案代號)?;
+        let 報告 = 遍歷並擊空間(&方案, 鍵數上限);
+        println!("# 有效並擊");
+        for 條目 in &報告.有效並擊 {
+            println!("{}\t{}", 條目.拼式, 並擊表示(條目));
+        }
+        let 重碼 = 報告.重碼();
+        println!("# 重碼");
+        for (拼式, 衆並擊) in &重碼 {
+            let 衆並擊 = 衆並擊
+                .iter()
+                .map(|條目| 並擊表示(條目).replace('\t', " "))
+                .collect::<Vec<_>>()
+                .join("\t");
+            println!("{拼式}\t{衆並擊}");
+        }
+        println!("# 不合拼式");
+        for 條目 in &報告.不合拼式 {
+            println!("{}\t{}", 條目.拼式, 並擊表示(條目));
+        }
+        println!("# 被消除");
+        for 條目 in &報告.被消除 {
+            println!("{}", 並擊表示(條目));
+        }
+        println!(
+            "遍歷 {} 種並擊: 有效 {}, 不合拼式 {}, 被消除 {}; 重碼拼式 {} 個",
+            報告.並擊總數,
+            報告.有效並擊.len(),
+            報告.不合拼式.len(),
+            報告.被消除.len(),
+            重碼.len()
+        );
+        Ok(true)
+    }
+
+    /// 返回 `false` 表示查出打不出的音節
+    pub fn 運行() -> Result<bool, String> {
+        let 參數 = std::env::args().skip(1).collect::<Vec<_>>();
+        let 預設方案 = 方案選項::default().slug().to_owned();
+        match 參數.split_first() {
+            Some((命令, 其餘)) if 命令 == "roundtrip" => {
+                let mut 資源目錄 = PathBuf::from("assets");
+                let mut 位置參數 = vec![];
+                for 此項 in 其餘 {
+                    if let Some(目錄) = 此項.strip_prefix("--assets=") {
+                        資源目錄 = PathBuf::from(目錄);
+                    } else {
+                        位置參數.push(此項.to_owned());
+                    }
+                }
+                let (方案代號, 衆文件) = 位置參數
+                    .split_first()
+                    .map_or((預設方案.as_str(), &[][..]), |(方案代號, 衆文件)| {
+                        (方案代號.as_str(), 衆文件)
+                    });
+                覈對往返一致(方案代號, 衆文件, &資源目錄)
+            }
+            Some((命令, 其餘)) if 命令 == "chords" => {
+                let mut 方案代號 = 預設方案.as_str();
+                let mut 鍵數上限 = 默認鍵數上限;
+                for 此項 in 其餘 {
+                    if let Some(鍵數) = 此項.strip_prefix("--max=") {
+                        鍵數上限 = 鍵數.parse().map_err(|_| format!("鍵數有誤: {鍵數}"))?;
+                    } else {
+                        方案代號 = 此項;
+                    }
+                }
+                遍歷並擊(方案代號, 鍵數上限)
+            }
+            _ => Err(用法.to_owned()),
+        }
+    }
+}
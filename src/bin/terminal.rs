@@ -0,0 +1,560 @@
+//! 終端打字機
+//!
+//! 經 SSH 等遠程終端練習打字. 與網頁版共用輸入方案、練習題和擊鍵判定邏輯.
+//!
+//! 終端支持報告抬鍵事件時 (kitty 鍵盤協議), 並擊按實際的落鍵、抬鍵判定;
+//! 否則將相繼到達的落鍵視作同時按下, 停頓片刻後一併抬起.
+//!
+//! 用法: `typewriter-terminal [方案] [題號] [--layout=配列] [--assets=目錄]`
+//!
+//! 方案爲網址中的方案代號, 如 `combo_pinyin`; 亦可爲方案數據文件的路徑.
+//! 配列亦用網址中的代號, 如 `ortho`.
+//! 網頁版從網址求取的練習題, 在資源目錄中讀取, 缺省爲工作目錄下的 `assets`.
+
+#![allow(confusable_idents)]
+#![allow(uncommon_codepoints)]
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    if let Err(錯誤) = 終端::運行() {
+        eprintln!("{錯誤}");
+        std::process::exit(1);
+    }
+}
+
+/// 終端程序不參與網頁構建
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod 終端 {
+    use crossterm::{
+        cursor::{Hide, MoveTo, Show},
+        event::{
+            self, Event, KeyCode as 終端鍵碼, KeyEvent, KeyEventKind, KeyModifiers,
+            KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        },
+        execute, queue,
+        style::{
+            Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor,
+            SetForegroundColor,
+        },
+        terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+    use std::collections::BTreeMap;
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
+
+    use typewriter::definition::觸鍵方式;
+    use typewriter::drill::{練習題, 題目來源};
+    use typewriter::gear::{
+        assignment::{作業內容, 解析習題},
+        geometry::鍵位幾何,
+        layout::{功能鍵::衆功能鍵, 配列},
+        statistics::{作答位置, 擊鍵紀錄, 練習統計},
+        theory::{方案選項, 生成方案定義, 輸入方案環境},
+    };
+    use typewriter::headless::{引擎核心, 擊鍵反饋};
+    use typewriter::key_code::{字符鍵值轉換, KeyCode};
+    use typewriter::theory_file::載入方案文件;
+
+    /// 不能報告抬鍵時, 相繼落鍵的間隔在此之內, 視作同時按下
+    const 並擊間隔: Duration = Duration::from_millis(80);
+
+    struct 命令行參數 {
+        方案: String,
+        題號: usize,
+        配列: Option<配列>,
+        資源目錄: PathBuf,
+    }
+
+    fn 解析命令行參數() -> Result<命令行參數, String> {
+        let mut 參數 = 命令行參數 {
+            方案: 方案選項::default().slug().to_owned(),
+            題號: 0,
+            配列: None,
+            資源目錄: PathBuf::from("assets"),
+        };
+        let mut 位置參數 = 0;
+        for 此項 in std::env::args().skip(1) {
+            if let Some(代號) = 此項.strip_prefix("--layout=") {
+                參數.配列 = Some(配列::from_slug(代號).ok_or_else(|| format!("未知配列: {代號}"))?);
+            } else if let Some(目錄) = 此項.strip_prefix("--assets=") {
+                參數.資源目錄 = PathBuf::from(目錄);
+            } else if 位置參數 == 0 {
+                參數.方案 = 此項;
+                位置參數 += 1;
+            } else if 位置參數 == 1 {
+                參數.題號 = 此項.parse().map_err(|_| format!("題號有誤: {此項}"))?;
+                位置參數 += 1;
+            } else {
+                return Err(format!("多餘的參數: {此項}"));
+            }
+        }
+        Ok(參數)
+    }
+
+    /// 網頁版從網址求取的練習題, 在終端讀取資源目錄中的同名文件
+    fn 讀取練習題(
+        題: &練習題<'static>, 資源目錄: &Path
+    ) -> Option<作業內容<'static>> {
+        match 題.題目 {
+            題目來源::內建 {
+                ref 編碼, ref 字幕
+            } => Some(作業內容 {
+                碼表: 編碼.clone(),
+                字幕: 字幕.clone(),
+            }),
+            題目來源::求取 { 網址 } => {
+                let 文件 = 網址.strip_prefix("/typewriter/static/")?;
+                std::fs::read_to_string(資源目錄.join(文件))
+                    .ok()
+                    .map(|習題文本| 解析習題(&習題文本))
+            }
+        }
+    }
+
+    struct 終端打字機 {
+        核心: 引擎核心,
+        配列: 配列,
+        衆練習題: &'static [練習題<'static>],
+        題號: Option<usize>,
+        資源目錄: PathBuf,
+        抬鍵報告: bool,
+        待抬鍵: Vec<KeyCode>,
+        統計: 練習統計,
+        計時起點: Instant,
+        上次進度: usize,
+    }
+
+    impl 終端打字機 {
+        fn new(參數: 命令行參數, 抬鍵報告: bool) -> Result<Self, String> {
+            let 環境 = 輸入方案環境 {
+                已選配列: 參數.配列,
+            };
+            let (方案, 衆練習題) = match 方案選項::from_slug(&參數.方案) {
+                Some(選項) => (
+                    生成方案定義(選項, 環境).ok_or_else(|| format!("未知方案: {}", 參數.方案))?,
+                    選項.配套練習題().unwrap_or(&[]),
+                ),
+                None => {
+                    let 方案文本 = std::fs::read_to_string(&參數.方案)
+                        .map_err(|錯誤| format!("無法讀取方案文件 {}: {錯誤}", 參數.方案))?;
+                    let 方案 = 載入方案文件(&方案文本).map_err(|錯誤| 錯誤.to_string())?;
+                    (方案, &[][..])
+                }
+            };
+            let mut 打字機 = Self {
+                配列: 參數.配列.unwrap_or(方案.佈局.首選配列),
+                核心: 引擎核心::new(方案),
+                衆練習題,
+                題號: None,
+                資源目錄: 參數.資源目錄,
+                抬鍵報告,
+                待抬鍵: vec![],
+                統計: 練習統計::default(),
+                計時起點: Instant::now(),
+                上次進度: 0,
+            };
+            打字機.選題(參數.題號);
+            Ok(打字機)
+        }
+
+        fn 選題(&mut self, 題號: usize) {
+            let Some(題) = self.衆練習題.get(題號) else {
+                return;
+            };
+            self.題號 = Some(題號);
+            self.核心.佈置作業(讀取練習題(題, &self.資源目錄));
+        }
+
+        fn 換題(&mut self, 向後: bool) {
+            let 題數 = self.衆練習題.len();
+            if 題數 == 0 {
+                return;
+            }
+            let 題號 = match (self.題號, 向後) {
+                (None, _) => 0,
+                (Some(題號), true) => (題號 + 1) % 題數,
+                (Some(題號), false) => (題號 + 題數 - 1) % 題數,
+            };
+            self.選題(題號);
+        }
+
+        /// 返回 `false` 則退出
+        fn 處理按鍵(&mut self, 事件: KeyEvent) -> bool {
+            let 落鍵 = match 事件.kind {
+                KeyEventKind::Press => true,
+                KeyEventKind::Release => false,
+                KeyEventKind::Repeat => return true,
+            };
+            match 事件.code {
+                終端鍵碼::Char('c') if 事件.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return false;
+                }
+                終端鍵碼::Esc if 落鍵 => {
+                    if self.核心.作業進度() == 0 {
+                        return false;
+                    }
+                    self.核心.重置作業進度();
+                    self.核心.重置輸入狀態();
+                }
+                終端鍵碼::Tab if 落鍵 => {
+                    let _不看結果 = self.核心.跳轉段落(false);
+                }
+                終端鍵碼::BackTab if 落鍵 => {
+                    let _不看結果 = self.核心.跳轉段落(true);
+                }
+                終端鍵碼::Backspace if 落鍵 => self.核心.退格(),
+                終端鍵碼::PageDown if 落鍵 => self.換題(true),
+                終端鍵碼::PageUp if 落鍵 => self.換題(false),
+                終端鍵碼::Char(字符) => {
+                    let 鍵碼 = 字符鍵值轉換(字符.to_ascii_lowercase());
+                    if 鍵碼 == KeyCode::No {
+                        return true;
+                    }
+                    if 落鍵 {
+                        self.落鍵(鍵碼);
+                    } else {
+                        self.擊鍵(鍵碼, false);
+                    }
+                }
+                _ => {}
+            }
+            true
+        }
+
+        /// 驅動引擎並記入練習統計
+        fn 擊鍵(&mut self, 鍵碼: KeyCode, 落鍵: bool) -> 擊鍵反饋 {
+            let 作答 = 作答位置::取自(&self.核心);
+            let 反饋 = if 落鍵 {
+                self.核心.落鍵(鍵碼)
+            } else {
+                self.核心.抬鍵(鍵碼)
+            };
+            let 時刻 = self.計時起點.elapsed().as_secs_f64() * 1000.0;
+            self.統計.記錄(
+                時刻,
+                &擊鍵紀錄 {
+                    落鍵, 作答, 反饋
+                },
+            );
+            反饋
+        }
+
+        /// 從頭開始作業時, 重新統計. 打完一題而迴轉到開頭者, 由統計在下一輪落鍵時自行重置
+        fn 覈對進度(&mut self) {
+            let 進度 = self.核心.作業進度();
+            if 進度 != self.上次進度 {
+                if 進度 == 0 && !self.核心.一題打完() {
+                    self.統計.重置();
+                }
+                self.上次進度 = 進度;
+            }
+        }
+
+        fn 落鍵(&mut self, 鍵碼: KeyCode) {
+            if self.擊鍵(鍵碼, true).轉入自習 {
+                self.題號 = None;
+            }
+            if self.抬鍵報告 {
+                return;
+            }
+            match self.核心.指法() {
+                觸鍵方式::連擊 => {
+                    self.擊鍵(鍵碼, false);
+                }
+                觸鍵方式::並擊 => {
+                    if !self.待抬鍵.contains(&鍵碼) {
+                        self.待抬鍵.push(鍵碼);
+                    }
+                }
+            }
+        }
+
+        fn 一併抬鍵(&mut self) {
+            for 鍵碼 in std::mem::take(&mut self.待抬鍵) {
+                self.擊鍵(鍵碼, false);
+            }
+        }
+
+        fn 事件循環(&mut self, 輸出: &mut impl Write) -> io::Result<()> {
+            loop {
+                self.覈對進度();
+                self.繪製(輸出)?;
+                if !self.待抬鍵.is_empty() && !event::poll(並擊間隔)? {
+                    self.一併抬鍵();
+                    continue;
+                }
+                if let Event::Key(事件) = event::read()? {
+                    if !self.處理按鍵(事件) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        fn 繪製(&self, 輸出: &mut impl Write) -> io::Result<()> {
+            let 核心 = &self.核心;
+            queue!(輸出, Clear(ClearType::All), MoveTo(0, 0))?;
+
+            let 題目 = match self
+                .題號
+                .and_then(|題號| self.衆練習題.get(題號).map(|題| (題號, 題)))
+            {
+                Some((題號, 題)) => format!("{}/{} {}", 題號 + 1, self.衆練習題.len(), 題.標題),
+                None if 核心.有無作業() => "自訂".to_owned(),
+                None => "自習".to_owned(),
+            };
+            queue!(
+                輸出,
+                SetAttribute(Attribute::Bold),
+                Print(format!("{} · {} · {}", 核心.方案().名稱, 題目, self.配列)),
+                SetAttribute(Attribute::Reset),
+            )?;
+
+            queue!(輸出, MoveTo(2, 2))?;
+            if let Some(字幕) = 核心.段落表示() {
+                queue!(
+                    輸出,
+                    SetForegroundColor(Color::DarkGreen),
+                    Print(字幕.已完成),
+                    ResetColor,
+                    SetAttribute(Attribute::Reverse),
+                    Print(字幕.指標文字),
+                    SetAttribute(Attribute::Reset),
+                    Print(字幕.未完成),
+                )?;
+            }
+
+            let 摘要 = self.統計.摘要();
+            if 核心.一題打完() && 摘要.片段數 > 0 {
+                let 片段單位 = match 核心.指法() {
+                    觸鍵方式::並擊 => "音節",
+                    觸鍵方式::連擊 => "字",
+                };
+                queue!(
+                    輸出,
+                    MoveTo(2, 3),
+                    SetForegroundColor(Color::DarkCyan),
+                    Print(format!(
+                        "{:.0} {片段單位}/分 · {:.0} 擊/分 · 首擊正確 {:.0}% · 平均遲疑 {:.2} 秒",
+                        摘要.每分鐘片段數(),
+                        摘要.每分鐘擊數(),
+                        摘要.首擊正確率() * 100.0,
+                        摘要.平均遲疑 / 1000.0,
+                    )),
+                    ResetColor,
+                )?;
+            }
+
+            queue!(輸出, MoveTo(2, 4))?;
+            self.繪製編碼欄(輸出)?;
+
+            let 鍵盤起始行 = 6;
+            let 鍵盤行數 = self.繪製鍵盤(輸出, 鍵盤起始行)?;
+
+            queue!(
+                輸出,
+                MoveTo(0, 鍵盤起始行 + 鍵盤行數 + 1),
+                SetForegroundColor(Color::DarkGrey),
+                Print("Esc 重新錄入／退出 · Tab 下一段 · Backspace 回退 · PgUp/PgDn 換題 · Ctrl-C 退出"),
+                ResetColor,
+            )?;
+            if !self.抬鍵報告 && 核心.指法() == 觸鍵方式::並擊 {
+                queue!(
+                    輸出,
+                    MoveTo(0, 鍵盤起始行 + 鍵盤行數 + 2),
+                    SetForegroundColor(Color::DarkGrey),
+                    Print("終端不報告抬鍵, 相繼按下的鍵視作一次並擊"),
+                    ResetColor,
+                )?;
+            }
+            輸出.flush()
+        }
+
+        fn 繪製編碼欄(&self, 輸出: &mut impl Write) -> io::Result<()> {
+            let 核心 = &self.核心;
+            let 目標 = 核心.目標輸入碼片段();
+            let (輸入碼, 輸入正確) = match 核心.指法() {
+                觸鍵方式::連擊 => {
+                    let 輸入碼 = 核心.已錄入字根碼();
+                    let 提示 = 目標.and_then(|目標| 目標.顯示輸入碼()).unwrap_or_default();
+                    (format!("{提示}  {輸入碼}‸"), 核心.連擊片段完成())
+                }
+                觸鍵方式::並擊 => {
+                    let 並擊碼 = 核心.反查所得並擊碼().unwrap_or_else(|| 核心.實況並擊碼());
+                    let 轉寫碼 = 目標
+                        .and_then(|目標| 目標.轉寫碼原文.clone())
+                        .or_else(|| 核心.並擊所得拼音())
+                        .map(|轉寫| format!("⟨{轉寫}⟩"))
+                        .unwrap_or_default();
+                    let 實況 = if 核心.反查所得並擊碼().is_some() && !核心.實況並擊碼().is_empty()
+                    {
+                        format!("  {}", 核心.實況並擊碼())
+                    } else {
+                        String::new()
+                    };
+                    (
+                        format!("{並擊碼} {轉寫碼}{實況}"),
+                        核心.並擊完成() && 核心.並擊成功(),
+                    )
+                }
+            };
+            let 顏色 = if 輸入正確 {
+                Color::Green
+            } else {
+                Color::Reset
+            };
+            queue!(輸出, SetForegroundColor(顏色), Print(輸入碼), ResetColor)
+        }
+
+        /// 按配列幾何畫出鍵面, 縱座標相近的鍵畫在同一行, 返回所佔行數
+        fn 繪製鍵盤(&self, 輸出: &mut impl Write, 起始行: u16) -> io::Result<u16> {
+            let 核心 = &self.核心;
+            let 佈局 = 核心.方案().佈局;
+            let 盤面 = 核心.當選盤面();
+            let 幾何 = self.配列.幾何();
+            let 鍵面文字 = |鍵位: &鍵位幾何| {
+                let 刻印 = match 鍵位.刻印座標 {
+                    Some(座標) => 佈局.選擇盤面(盤面, 座標).map(|(_, 刻印)| 刻印),
+                    None => 衆功能鍵
+                        .iter()
+                        .find(|功能鍵| 功能鍵.鍵碼 == 鍵位.鍵碼)
+                        .map(|功能鍵| 功能鍵.刻印),
+                };
+                刻印
+                    .and_then(|刻印| {
+                        刻印
+                            .居中刻印文字()
+                            .or_else(|| 刻印.上方刻印文字())
+                            .or_else(|| 刻印.下方刻印文字())
+                    })
+                    .unwrap_or("")
+            };
+            let 鍵寬 = 幾何
+                .衆鍵位
+                .iter()
+                .map(|鍵位| 顯示寬度(鍵面文字(鍵位)))
+                .max()
+                .unwrap_or(0)
+                .max(3)
+                + 2;
+            // 每單位鍵距所佔的字符數, 含鍵間空格
+            let 鍵距 = (鍵寬 + 1) as f64;
+            let 目標鍵位 = match 核心.指法() {
+                觸鍵方式::並擊 => 核心.反查鍵位(),
+                觸鍵方式::連擊 => 核心.逐鍵提示(),
+            };
+            let 並擊狀態 = 核心.並擊狀態();
+
+            let mut 衆行 = BTreeMap::<i64, Vec<&鍵位幾何>>::new();
+            for 鍵位 in &幾何.衆鍵位 {
+                衆行
+                    .entry(鍵位.中心().1.floor() as i64)
+                    .or_default()
+                    .push(鍵位);
+            }
+            for (行數, 此行) in 衆行.values_mut().enumerate() {
+                此行.sort_by(|甲, 乙| 甲.左.total_cmp(&乙.左));
+                for 鍵位 in 此行.iter() {
+                    let 鍵 = 鍵位.鍵碼;
+                    let 寬 = ((鍵位.寬 * 鍵距).round() as usize).saturating_sub(1);
+                    let 文字 = match 鍵面文字(鍵位) {
+                        "" if 鍵 == KeyCode::Space => "␣",
+                        文字 => 文字,
+                    };
+                    let 背景 = if 並擊狀態.實時落鍵.0.contains(&鍵) {
+                        Color::DarkBlue
+                    } else if 並擊狀態.累計擊鍵.0.contains(&鍵) {
+                        Color::DarkCyan
+                    } else if 目標鍵位.as_ref().is_some_and(|鍵組| 鍵組.0.contains(&鍵)) {
+                        Color::DarkYellow
+                    } else {
+                        Color::DarkGrey
+                    };
+                    queue!(
+                        輸出,
+                        MoveTo(2 + (鍵位.左 * 鍵距).round() as u16, 起始行 + 行數 as u16),
+                        SetBackgroundColor(背景),
+                        Print(置中(文字, 寬)),
+                        ResetColor,
+                    )?;
+                }
+            }
+            Ok(衆行.len() as u16)
+        }
+    }
+
+    fn 顯示寬度(文字: &str) -> usize {
+        文字
+            .chars()
+            .map(|字符| match 字符 as u32 {
+                0x0300..=0x036F | 0x200B..=0x200F => 0,
+                0x1100..=0x115F
+                | 0x2E80..=0xA4CF
+                | 0xAC00..=0xD7A3
+                | 0xF900..=0xFAFF
+                | 0xFE30..=0xFE4F
+                | 0xFF00..=0xFF60
+                | 0xFFE0..=0xFFE6
+                | 0x20000..=0x3FFFD => 2,
+                _ => 1,
+            })
+            .sum()
+    }
+
+    fn 置中(文字: &str, 寬: usize) -> String {
+        let 餘白 = 寬.saturating_sub(顯示寬度(文字));
+        format!(
+            "{}{}{}",
+            " ".repeat(餘白 / 2),
+            文字,
+            " ".repeat(餘白 - 餘白 / 2)
+        )
+    }
+
+    /// 離開時恢復終端設定, 中途出錯亦然
+    struct 終端設定 {
+        抬鍵報告: bool,
+    }
+
+    impl 終端設定 {
+        fn 進入() -> io::Result<Self> {
+            terminal::enable_raw_mode()?;
+            let 抬鍵報告 = terminal::supports_keyboard_enhancement().unwrap_or(false);
+            let mut 輸出 = io::stdout();
+            execute!(輸出, EnterAlternateScreen, Hide)?;
+            if 抬鍵報告 {
+                execute!(
+                    輸出,
+                    PushKeyboardEnhancementFlags(
+                        KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                            | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                    )
+                )?;
+            }
+            Ok(Self { 抬鍵報告 })
+        }
+    }
+
+    impl Drop for 終端設定 {
+        fn drop(&mut self) {
+            let mut 輸出 = io::stdout();
+            if self.抬鍵報告 {
+                let _ = execute!(輸出, PopKeyboardEnhancementFlags);
+            }
+            let _ = execute!(輸出, Show, LeaveAlternateScreen);
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+
+    pub fn 運行() -> Result<(), String> {
+        let 參數 = 解析命令行參數()?;
+        let 設定 = 終端設定::進入().map_err(|錯誤| 錯誤.to_string())?;
+        let mut 打字機 = 終端打字機::new(參數, 設定.抬鍵報告)?;
+        let mut 輸出 = io::stdout();
+        打字機.事件循環(&mut 輸出).map_err(|錯誤| 錯誤.to_string())
+    }
+}
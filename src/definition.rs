@@ -1,10 +1,13 @@
 use lazy_regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::BTreeSet;
 
 use crate::gear::layout::{盤面選擇碼, 鍵盤佈局};
 use crate::key_code::KeyCode;
-use crate::spelling_algebra::{拼寫運算, 施展拼寫運算};
+use crate::spelling_algebra::{
+    分類拼式, 拼寫運算, 施展多值拼寫運算, 施展拼寫運算
+};
 
 #[derive(Clone)]
 pub struct 鍵位定義<'a> {
@@ -25,13 +28,13 @@ pub struct 輸入方案定義<'a> {
     pub 動態切換: &'a [動態切換規則<'a>],
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Deserialize, PartialEq)]
 pub enum 觸鍵方式 {
     連擊,
     並擊,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub enum 碼表格式 {
     逐鍵,
     連擊,
@@ -73,7 +76,7 @@ pub trait 判定鍵位 {
     fn 包含鍵位(&self, 鍵位: &鍵位定義) -> bool;
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct 鍵組(pub BTreeSet<KeyCode>);
 
 impl 鍵組 {
@@ -181,6 +184,16 @@ impl 轉寫法定義<'_> {
         施展拼寫運算(轉寫碼, self.字根拆分規則)
     }
 
+    /// 求得字根碼可轉寫成的所有拼式, 包括派生、模糊、縮寫所得, 各標明類別
+    pub fn 字根碼轉寫爲衆拼式(&self, 字根碼: &str) -> Vec<分類拼式> {
+        施展多值拼寫運算(字根碼, self.拼式轉寫規則)
+    }
+
+    /// 求得拼式可拆分成的所有字根碼, 正規字根碼居首, 各標明類別
+    pub fn 拼式拆分爲衆字根碼(&self, 轉寫碼: &str) -> Vec<分類拼式> {
+        施展多值拼寫運算(轉寫碼, self.字根拆分規則)
+    }
+
     pub fn 驗證拼式(&self, 待驗證拼式: &str) -> bool {
         self.拼式驗證規則.iter().any(|r| r.is_match(待驗證拼式))
     }
@@ -3,29 +3,46 @@ use leptos::prelude::*;
 
 use crate::action::動作;
 use crate::app_state::use_app_state;
-use crate::definition::觸鍵方式;
-use crate::gear::theory::輸入方案環境;
 use crate::gear::{
-    assignment::{作業, 作業機關, 作業機關輸出信號, 步進法},
-    caption::{字幕機關, 字幕機關輸出信號, 字幕段落},
+    adaptive::{加練機關, 加練機關輸出信號},
+    assignment::{作業, 作業機關, 作業機關輸出信號},
+    caption::{字幕機關, 字幕機關輸出信號},
     chord::{並擊機關, 並擊機關輸出信號},
-    input::{檔位, 焦點事件處理機關, 觸鍵消息, 輸入事件處理機關},
+    curriculum::{解鎖機關, 解鎖機關輸出信號},
+    ghost::{影子機關, 影子機關輸出信號},
+    heatmap::{熱圖機關, 熱圖機關輸出信號},
+    history::{練習紀錄機關, 練習紀錄機關輸出信號},
+    host_layout::{系統佈局機關, 系統佈局機關輸出信號},
+    input::{擊鍵事件, 擊鍵來源, 焦點事件處理機關, 輸入事件處理機關},
     key_press::{連擊機關, 連擊機關輸出信號},
     layout::{佈局機關, 佈局機關輸出信號},
     mode::{工作模式, 工作模式機關, 工作模式機關輸出信號},
+    replay::{錄放機關, 錄放機關輸出信號},
+    retry::{重練機關, 重練機關輸出信號},
+    statistics::{作答位置, 擊鍵紀錄, 統計機關, 統計機關輸出信號},
     theory::{輸入方案機關, 輸入方案機關輸出信號},
 };
-use crate::key_code::KeyCode;
+use crate::headless::{功能鍵反饋, 引擎核心};
+use crate::key_code::{鍵值字符轉換, KeyCode};
 
 #[derive(Clone)]
 pub struct 微觀引擎輸出信號 {
     pub 方案: 輸入方案機關輸出信號,
     pub 模式: 工作模式機關輸出信號,
     pub 佈局: 佈局機關輸出信號,
+    pub 系統: 系統佈局機關輸出信號,
     pub 作業: 作業機關輸出信號,
     pub 字幕: 字幕機關輸出信號,
     pub 連擊: 連擊機關輸出信號,
     pub 並擊: 並擊機關輸出信號,
+    pub 統計: 統計機關輸出信號,
+    pub 紀錄: 練習紀錄機關輸出信號,
+    pub 熱圖: 熱圖機關輸出信號,
+    pub 加練: 加練機關輸出信號,
+    pub 解鎖: 解鎖機關輸出信號,
+    pub 重練: 重練機關輸出信號,
+    pub 錄放: 錄放機關輸出信號,
+    pub 影子: 影子機關輸出信號,
     pub 視圖: 視圖輸入信號,
 }
 
@@ -35,28 +52,36 @@ pub fn 微觀引擎() -> 微觀引擎輸出信號 {
     let 選用方案 = state.set_theory;
     let 已選配列 = state.layout;
     let 選用配列 = state.set_layout;
-    let 環境 = 輸入方案環境 { 已選配列 };
-    let 方案 = 輸入方案機關(現行方案, 選用方案, 環境);
-    let 佈局 = 佈局機關(&方案, 已選配列, 選用配列);
-    let 作業 = 作業機關(&方案);
-    let 字幕 = 字幕機關(&方案, &作業);
-    let 連擊 = 連擊機關(&方案, &作業, &佈局);
-    let 並擊 = 並擊機關(&方案, &作業);
+    let 方案 = 輸入方案機關(現行方案, 選用方案, 已選配列, state.scheme);
 
-    let 輸入方案機關輸出信號 {
-        現行方案, 指法,
-    ..
-    } = 方案;
+    let 方案定義 = 方案.方案定義;
+    let 核心 = RwSignal::new(引擎核心::new(方案定義.get_untracked()));
+    let _ = Effect::watch(
+        方案定義,
+        move |&方案, _, _| {
+            核心.write().切換方案(方案);
+        },
+        false,
+    );
+
+    let 佈局 = 佈局機關(&方案, 核心, 已選配列, 選用配列, state.kle);
+    let 系統 = 系統佈局機關();
+    let 作業 = 作業機關(&方案, 核心);
+    let 字幕 = 字幕機關(核心);
+    let 連擊 = 連擊機關(核心);
+    let 並擊 = 並擊機關(核心);
+    let 統計 = 統計機關(核心);
+    let 記錄擊鍵 = 統計.記錄擊鍵;
+    let 紀錄 = 練習紀錄機關(&方案, &佈局, &作業, &統計);
+    let 熱圖 = 熱圖機關(&方案, &統計, &紀錄);
+    let 加練 = 加練機關(&方案, &作業, &紀錄);
+    let 解鎖 = 解鎖機關(&方案, &作業, &統計);
+    let 重練 = 重練機關(&方案, &作業, &統計, 核心);
+
+    let 輸入方案機關輸出信號 { 現行方案, .. } = 方案;
     let 作業機關輸出信號 {
-        當前作業,
-        佈置作業,
-        作業進度,
-        重置作業進度,
-        作業進度完成,
-        作業推進,
-        作業回退,
-        有無作業,
-        ..
+        當前作業, 佈置作業,
+    ..
     } = 作業;
 
     Effect::new(move || {
@@ -77,35 +102,17 @@ pub fn 微觀引擎() -> 微觀引擎輸出信號 {
         }
     });
 
-    let 字幕機關輸出信號 {
-        分段字幕,
-        當前段落,
-        前序段落,
-        ..
-    } = 字幕;
-    let 連擊機關輸出信號 {
-        連擊狀態變更,
-        連擊比對成功,
-        清空連擊輸入碼,
-        回退連擊輸入碼,
-        編輯連擊輸入碼,
-        自動切換盤面,
+    let 重練機關輸出信號 {
+        提議重練,
+        佈置重練,
+        婉拒重練,
         ..
-    } = 連擊;
-    let 並擊機關輸出信號 {
-        並擊狀態變更,
-        重置並擊狀態,
-        並擊完成,
-        並擊成功,
-        ..
-    } = 並擊;
+    } = 重練;
 
-    let 輸入 = 輸入動作機關(&方案, &連擊, &並擊);
+    let 輸入 = 輸入動作機關(核心);
 
     let 模式 = 工作模式機關(&方案, &作業, &輸入);
 
-    let 輸入動作 { 重置輸入狀態 } = 輸入;
-
     let 工作模式機關輸出信號 {
         現行工作模式,
         開啓反查輸入,
@@ -124,168 +131,159 @@ pub fn 微觀引擎() -> 微觀引擎輸出信號 {
         false,
     );
 
-    焦點事件處理機關(重置並擊狀態);
+    焦點事件處理機關(move || 核心.write().重置並擊狀態());
 
-    let 處理功能鍵 = move |觸鍵消息 { 鍵碼, 檔位 }| match 鍵碼 {
-        KeyCode::Escape => {
-            match 現行工作模式() {
-                工作模式::錄入 => {
-                    if 作業進度() != 0 {
-                        重置作業進度();
-                        重置輸入狀態();
-                    } else {
-                        開啓練習題選單();
-                    }
-                }
-                _ => 關閉輸入欄(),
+    let 處理功能鍵 = move |鍵碼, 上檔: bool| {
+        if 現行工作模式() == 工作模式::錄入 {
+            if 鍵碼 == KeyCode::Escape && 提議重練.get_untracked() {
+                婉拒重練();
+                return true;
             }
-            true
-        }
-        KeyCode::Tab => {
-            match 現行工作模式() {
-                工作模式::錄入 => {
-                    let 跳轉結果 = if 檔位.shift {
-                        let 目標 = 前序段落()
-                            .or_else(|| 分段字幕.with(|衆段落| 衆段落.last().cloned()))
-                            .map(|字幕段落(起, _, _)| 起);
-                        作業回退(步進法 {
-                            目標, 迴轉: true
-                        })
-                    } else {
-                        let 目標 = 當前段落().map(|字幕段落(_, 止, _)| 止);
-                        作業推進(步進法 {
-                            目標, 迴轉: true
-                        })
-                    };
-                    if 跳轉結果.is_ok() {
-                        重置輸入狀態();
-                    }
-                }
-                工作模式::選取練習題 => {
-                    開啓方案選單();
-                }
-                工作模式::選擇輸入方案 => {
-                    開啓配列選單();
-                }
-                工作模式::選擇配列 => {
-                    開啓練習題選單();
-                }
-                _ => {
-                    關閉輸入欄();
-                }
+            let 反饋 = 核心.write().功能鍵(鍵碼, 上檔);
+            match 反饋 {
+                功能鍵反饋::非功能鍵 => return false,
+                功能鍵反饋::已處理 => {}
+                功能鍵反饋::開啓練習題選單 => 開啓練習題選單(),
+                功能鍵反饋::開啓反查輸入 => 開啓反查輸入(),
+                功能鍵反饋::開啓方案選單 => 開啓方案選單(),
             }
-            true
+            return true;
         }
-        KeyCode::Backspace => {
-            if 現行工作模式() == 工作模式::錄入 {
-                match 指法() {
-                    觸鍵方式::連擊 => {
-                        if 回退連擊輸入碼().is_err() && 有無作業() {
-                            let _不看結果 = 作業回退(步進法::default());
-                            重置輸入狀態();
-                        }
+        match 鍵碼 {
+            KeyCode::Escape | KeyCode::Enter => {
+                關閉輸入欄();
+                true
+            }
+            KeyCode::Tab => {
+                match 現行工作模式() {
+                    工作模式::選取練習題 => {
+                        開啓方案選單();
                     }
-                    觸鍵方式::並擊 => {
-                        if 並擊完成() || 作業回退(步進法::default()).is_ok() {
-                            重置並擊狀態();
-                        }
+                    工作模式::選擇輸入方案 => {
+                        開啓配列選單();
+                    }
+                    工作模式::選擇配列 => {
+                        開啓練習題選單();
+                    }
+                    _ => {
+                        關閉輸入欄();
                     }
                 }
-                return true;
+                true
             }
-            false
-        }
-        KeyCode::Enter => {
-            if 現行工作模式() == 工作模式::錄入 {
-                開啓反查輸入();
-            } else {
-                關閉輸入欄();
-            }
-            true
-        }
-        KeyCode::Grave => {
-            match 現行工作模式() {
-                工作模式::選擇輸入方案 => 關閉輸入欄(),
-                _ => 開啓方案選單(),
+            KeyCode::Grave => {
+                match 現行工作模式() {
+                    工作模式::選擇輸入方案 => 關閉輸入欄(),
+                    _ => 開啓方案選單(),
+                }
+                true
             }
-            true
+            _ => false,
         }
-        _ => false,
     };
 
-    let 擊中目標 = move || match 指法() {
-        觸鍵方式::連擊 => 連擊比對成功(),
-        觸鍵方式::並擊 => 並擊完成() && 並擊成功(),
-    };
-    let 批閱作業 = move || {
-        // 擊中目標輸入碼後反查下一個輸入碼
-        let 分段落則迴轉 = 步進法 {
-            目標: None,
-            迴轉: 分段字幕.read().len() > 1,
-        };
-        擊中目標() && 作業推進(分段落則迴轉).is_ok()
-    };
-    let 另起一段 = move || {
-        當前段落
-            .read()
-            .as_ref()
-            .is_some_and(|字幕段落(段落起始, _, _)| 作業進度.read() == *段落起始)
-    };
-    let 既然落鍵 = move |鍵碼| {
-        // 繼續擊鍵時消除已完成的反查作業
-        if 作業進度完成() {
-            佈置作業(作業::自習(現行方案()));
+    let 既然落鍵 = move |鍵碼, 功能鍵: bool| {
+        let 錄入中 = 現行工作模式() == 工作模式::錄入;
+        // 提議重練時擊下字鍵, 即接受重練, 此擊不計
+        if 錄入中 && !功能鍵 && 鍵值字符轉換(鍵碼).is_some() && 提議重練.get_untracked()
+        {
+            佈置重練();
+            return;
         }
-        if 現行工作模式() == 工作模式::錄入 {
-            並擊狀態變更.write().落鍵(鍵碼);
-            if 指法() == 觸鍵方式::連擊 {
-                連擊狀態變更.write().擊發(鍵碼);
-                編輯連擊輸入碼(鍵碼);
-                if 批閱作業() && 另起一段() {
-                    清空連擊輸入碼();
-                }
-                自動切換盤面();
+        if 錄入中 {
+            let 作答 = 作答位置::取自(&核心.read());
+            let 反饋 = 核心.write().落鍵(鍵碼);
+            記錄擊鍵(擊鍵紀錄 {
+                落鍵: true,
+                作答,
+                反饋,
+            });
+            if 反饋.轉入自習 {
+                佈置作業(作業::自習(現行方案()));
             }
         }
+        // 繼續擊鍵時消除已完成的反查作業
+        else if 核心.read().作業進度完成() {
+            佈置作業(作業::自習(現行方案()));
+        }
     };
     let 既然抬鍵 = move |鍵碼| {
         if 現行工作模式() == 工作模式::錄入 {
-            並擊狀態變更.write().抬鍵(鍵碼);
+            let 作答 = 作答位置::取自(&核心.read());
+            let 反饋 = 核心.write().抬鍵(鍵碼);
+            記錄擊鍵(擊鍵紀錄 {
+                落鍵: false,
+                作答,
+                反饋,
+            });
+        }
+    };
+
+    let 受理擊鍵 = move |事件: 擊鍵事件| -> bool {
+        if !事件.落鍵 {
+            if 事件.鍵碼 != KeyCode::No {
+                既然抬鍵(事件.鍵碼);
+            }
+            return false;
         }
-        match 指法() {
-            觸鍵方式::連擊 => {
-                // 顯示並擊動態, 抬鍵後清除並擊結果
-                if 並擊完成() {
-                    重置並擊狀態();
+        match 事件.來源 {
+            擊鍵來源::實體鍵盤 => {
+                let 已處理 = 處理功能鍵(事件.鍵碼, 事件.上檔);
+                if 事件.鍵碼 != KeyCode::No {
+                    既然落鍵(事件.鍵碼, 已處理);
                 }
+                已處理
             }
-            觸鍵方式::並擊 => {
-                // 推進到下一題時, 清除上一題的並擊結果
-                // 但在最後一題完成後停下顯示結果
-                if 批閱作業() && !作業進度完成() {
-                    重置並擊狀態();
+            // 攔截功能鍵
+            擊鍵來源::屏幕鍵盤 => {
+                if !處理功能鍵(事件.鍵碼, 事件.上檔) && 事件.鍵碼 != KeyCode::No {
+                    既然落鍵(事件.鍵碼, false);
                 }
+                true
             }
         }
     };
 
-    輸入事件處理機關(處理功能鍵, 既然落鍵, 既然抬鍵);
+    let 錄放 = 錄放機關(&方案, &作業, 核心);
+    let 影子 = 影子機關(&方案, &作業, &錄放, 核心);
+    let 影子機關輸出信號 {
+        記錄本輪擊鍵, ..
+    } = 影子;
+    let 錄放機關輸出信號 {
+        放映中, 錄製擊鍵,
+    ..
+    } = 錄放;
+    // 重放時不理會現場擊鍵
+    let 現場擊鍵 = move |事件: 擊鍵事件| -> bool {
+        if 放映中.get_untracked() {
+            return false;
+        }
+        錄製擊鍵(事件);
+        記錄本輪擊鍵(事件);
+        受理擊鍵(事件)
+    };
+
+    輸入事件處理機關(系統.系統佈局, 佈局.替代鍵位.into(), 現場擊鍵);
 
     let 視圖落鍵動作 = Callback::new(move |鍵碼: KeyCode| {
         // 觸屏點擊時, 默認未按下修飾鍵
-        let 虛擬檔位 = 檔位::default();
-        // 攔截功能鍵
-        if 處理功能鍵(觸鍵消息 {
+        現場擊鍵(擊鍵事件 {
+            時刻: js_sys::Date::now(),
             鍵碼,
-            檔位: 虛擬檔位,
-        }) {
-            return;
-        }
-        既然落鍵(鍵碼);
+            落鍵: true,
+            來源: 擊鍵來源::屏幕鍵盤,
+            上檔: false,
+        });
     });
 
     let 視圖抬鍵動作 = Callback::new(move |鍵碼: KeyCode| {
-        既然抬鍵(鍵碼);
+        現場擊鍵(擊鍵事件 {
+            時刻: js_sys::Date::now(),
+            鍵碼,
+            落鍵: false,
+            來源: 擊鍵來源::屏幕鍵盤,
+            上檔: false,
+        });
     });
 
     let 視圖 = 視圖輸入信號 {
@@ -297,10 +295,19 @@ pub fn 微觀引擎() -> 微觀引擎輸出信號 {
         方案,
         模式,
         佈局,
+        系統,
         作業,
         字幕,
         連擊,
         並擊,
+        統計,
+        紀錄,
+        熱圖,
+        加練,
+        解鎖,
+        重練,
+        錄放,
+        影子,
         視圖,
     }
 }
@@ -313,22 +320,9 @@ pub struct 輸入動作 {
 }
 
 #[define_opaque(輸入重置動作)]
-fn 輸入動作機關(
-    方案: &輸入方案機關輸出信號,
-    連擊: &連擊機關輸出信號,
-    並擊: &並擊機關輸出信號,
-) -> 輸入動作 {
-    let 指法 = 方案.指法;
-    let 清空連擊輸入碼 = 連擊.清空連擊輸入碼;
-    let 重置並擊狀態 = 並擊.重置並擊狀態;
-    let 自動切換盤面 = 連擊.自動切換盤面;
-
+fn 輸入動作機關(核心: RwSignal<引擎核心>) -> 輸入動作 {
     let 重置輸入狀態 = move || {
-        match 指法() {
-            觸鍵方式::連擊 => 清空連擊輸入碼(),
-            觸鍵方式::並擊 => 重置並擊狀態(),
-        }
-        自動切換盤面();
+        核心.write().重置輸入狀態();
     };
 
     輸入動作 { 重置輸入狀態 }
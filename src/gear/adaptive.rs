@@ -0,0 +1,262 @@
+//! 弱項加練
+//!
+//! 從方案配套練習題中收集片段, 按近來練習紀錄中各片段及其所用鍵位的擊錯率、用時加權抽取,
+//! 即時生成一道練習題. 未練過的片段優先出現, 久未練習的片段權重漸增, 仿間隔重複.
+
+use leptos::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::action::動作;
+use crate::definition::{碼表格式, 輸入方案定義};
+use crate::drill::{練習題, 題目來源};
+use crate::gear::{
+    assignment::{
+        作業, 作業內容, 作業機關輸出信號, 對照輸入碼, 生成題, 碼表定義, 解析碼表
+    },
+    caption::字幕格式,
+    heatmap::{按鍵位彙總, 熱圖指標, 片段鍵位},
+    history::{片段成績, 練習成績, 練習紀錄機關輸出信號},
+    theory::輸入方案機關輸出信號,
+};
+
+pub const 加練標題: &str = "弱項加練";
+
+/// 每道加練題的片段數
+const 加練題量: usize = 30;
+
+/// 只參考最近若干次練習紀錄
+const 參考紀錄數: usize = 20;
+
+const 一日: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// 可供抽取的片段, 同屬一種碼表格式
+#[derive(Clone, Debug, PartialEq)]
+pub struct 片段庫 {
+    pub 格式: 碼表格式,
+    pub 衆片段: Vec<對照輸入碼>,
+}
+
+impl 片段庫 {
+    /// 收集內建練習題中的片段, 去重. 各題格式不一時, 取片段最多的一種格式.
+    pub fn 收集(練習題: &[練習題], 方案: &輸入方案定義) -> Option<Self> {
+        let mut 各格式片段 = Vec::<片段庫>::new();
+        for 題 in 練習題 {
+            let 題目來源::內建 { 編碼, .. } = &題.題目 else {
+                continue;
+            };
+            let 格式 = 編碼.碼表格式().unwrap_or(方案.編碼法);
+            let 庫 = match 各格式片段.iter_mut().find(|庫| 庫.格式 == 格式) {
+                Some(庫) => 庫,
+                None => {
+                    各格式片段.push(片段庫 {
+                        格式,
+                        衆片段: vec![],
+                    });
+                    各格式片段.last_mut().unwrap()
+                }
+            };
+            for 片段 in 解析碼表(編碼, 方案) {
+                let 有字 = 片段
+                    .顯示輸入碼()
+                    .is_some_and(|輸入碼| !輸入碼.trim().is_empty());
+                if 有字 && !庫.衆片段.contains(&片段) {
+                    庫.衆片段.push(片段);
+                }
+            }
+        }
+        各格式片段
+            .into_iter()
+            .filter(|庫| !庫.衆片段.is_empty())
+            .max_by_key(|庫| 庫.衆片段.len())
+    }
+
+    /// 按 `衆權重` 隨機抽取 `題量` 個片段, 權重與 `衆片段` 一一對應.
+    /// `隨機數` 每次給出 [0, 1) 間的數.
+    pub fn 按權重抽取(
+        &self,
+        衆權重: &[f64],
+        題量: usize,
+        mut 隨機數: impl FnMut() -> f64,
+    ) -> Vec<&對照輸入碼> {
+        let 總權重 = 衆權重.iter().sum::<f64>();
+        let mut 選出 = Vec::<&對照輸入碼>::with_capacity(題量);
+        while 選出.len() < 題量 && 總權重 > 0.0 {
+            let mut 餘數 = 隨機數() * 總權重;
+            let 序號 = 衆權重
+                .iter()
+                .position(|&權重| {
+                    餘數 -= 權重;
+                    餘數 < 0.0
+                })
+                .unwrap_or(衆權重.len() - 1);
+            let 片段 = &self.衆片段[序號];
+            // 避免同一片段接連出現
+            if self.衆片段.len() > 1 && 選出.last() == Some(&片段) {
+                continue;
+            }
+            選出.push(片段);
+        }
+        選出
+    }
+
+    /// 將選出的片段寫成該格式的碼表, 解析後得到同樣的片段
+    pub fn 寫成碼表(&self, 選出: &[&對照輸入碼]) -> 碼表定義<'static> {
+        let 寫出 = |片段: &&對照輸入碼| match (&片段.字根碼原文, &片段.轉寫碼原文) {
+            (Some(字根碼), Some(轉寫碼)) => format!("[{字根碼}]=<{轉寫碼}>"),
+            (Some(字根碼), None) => format!("[{字根碼}]"),
+            (None, Some(轉寫碼)) => format!("<{轉寫碼}>"),
+            (None, None) => String::new(),
+        };
+        let 碼表 = match self.格式 {
+            碼表格式::逐鍵 => 選出
+                .iter()
+                .flat_map(|片段| 片段.字根碼原文.as_ref().or(片段.轉寫碼原文.as_ref()))
+                .cloned()
+                .collect::<String>(),
+            碼表格式::連擊 => 選出
+                .iter()
+                .flat_map(|片段| 片段.字根碼原文.as_ref().or(片段.轉寫碼原文.as_ref()))
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(" "),
+            碼表格式::並擊 => 選出.iter().map(寫出).collect::<Vec<_>>().join(" "),
+        };
+        碼表定義::生成(self.格式, 碼表)
+    }
+}
+
+/// 近來練習中一個片段的表現
+struct 片段近況<'a> {
+    成績: Option<&'a 片段成績>,
+    /// 所用各鍵擊錯率的平均
+    鍵位擊錯率: f64,
+    /// 距最近一次練習的天數
+    間隔天數: Option<f64>,
+}
+
+fn 片段權重(近況: &片段近況, 全體平均用時: f64) -> f64 {
+    let 鍵位權重 = 2.0 * 近況.鍵位擊錯率;
+    let Some(成績) = 近況.成績 else {
+        // 未練過的片段優先出現
+        return 2.0 + 鍵位權重;
+    };
+    let 擊錯權重 = 3.0 * 熱圖指標::擊錯率.取值(成績);
+    let 遲緩權重 = if 全體平均用時 > 0.0 {
+        (熱圖指標::平均用時.取值(成績) / 全體平均用時 - 1.0).clamp(0.0, 2.0)
+    } else {
+        0.0
+    };
+    let 間隔權重 = 近況.間隔天數.map_or(0.0, |天數| (天數 / 7.0).min(1.0));
+    1.0 + 擊錯權重 + 遲緩權重 + 間隔權重 + 鍵位權重
+}
+
+/// 按近來的練習紀錄爲片段庫中各片段加權, 抽取若干片段生成練習題.
+/// `隨機數` 每次給出 [0, 1) 間的數.
+pub fn 生成加練(
+    庫: &片段庫,
+    方案: &輸入方案定義,
+    衆紀錄: &[練習成績],
+    此刻: f64,
+    題量: usize,
+    隨機數: impl FnMut() -> f64,
+) -> 碼表定義<'static> {
+    let 近來紀錄 = &衆紀錄[衆紀錄.len().saturating_sub(參考紀錄數)..];
+    let 近來成績 = 片段成績::合併(近來紀錄.iter().flat_map(|紀錄| &紀錄.衆片段));
+    let 成績表 = 近來成績
+        .iter()
+        .map(|成績| (成績.輸入碼.as_str(), 成績))
+        .collect::<BTreeMap<_, _>>();
+    let 末次練習 = 近來紀錄
+        .iter()
+        .flat_map(|紀錄| {
+            紀錄
+                .衆片段
+                .iter()
+                .map(|片段| (片段.輸入碼.as_str(), 紀錄.日期))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let 鍵位表 = 按鍵位彙總(方案, &近來成績);
+    let (總用時, 總次數) = 近來成績.iter().fold((0.0, 0), |(用時, 次數), 成績| {
+        (用時 + 成績.用時, 次數 + 成績.次數)
+    });
+    let 全體平均用時 = if 總次數 > 0 {
+        總用時 / 總次數 as f64
+    } else {
+        0.0
+    };
+
+    let 衆權重 = 庫
+        .衆片段
+        .iter()
+        .map(|片段| {
+            let 輸入碼 = 片段.顯示輸入碼().unwrap_or_default();
+            let 鍵位擊錯率 = {
+                let 衆擊錯率 = 片段鍵位(方案, 輸入碼)
+                    .iter()
+                    .filter_map(|鍵| 鍵位表.get(鍵))
+                    .map(|成績| 熱圖指標::擊錯率.取值(成績))
+                    .collect::<Vec<_>>();
+                if 衆擊錯率.is_empty() {
+                    0.0
+                } else {
+                    衆擊錯率.iter().sum::<f64>() / 衆擊錯率.len() as f64
+                }
+            };
+            let 近況 = 片段近況 {
+                成績: 成績表.get(輸入碼).copied(),
+                鍵位擊錯率,
+                間隔天數: 末次練習.get(輸入碼).map(|&日期| (此刻 - 日期) / 一日),
+            };
+            片段權重(&近況, 全體平均用時)
+        })
+        .collect::<Vec<_>>();
+    庫.寫成碼表(&庫.按權重抽取(&衆權重, 題量, 隨機數))
+}
+
+pub type 佈置加練動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 加練機關輸出信號 {
+    pub 佈置加練: 佈置加練動作,
+}
+
+#[define_opaque(佈置加練動作)]
+pub fn 加練機關(
+    方案: &輸入方案機關輸出信號,
+    作業: &作業機關輸出信號,
+    紀錄: &練習紀錄機關輸出信號,
+) -> 加練機關輸出信號 {
+    let 現行方案 = 方案.現行方案;
+    let 方案定義 = 方案.方案定義;
+    let 佈置作業 = 作業.佈置作業;
+    let 方案紀錄 = 紀錄.方案紀錄;
+
+    let 佈置加練 = move || {
+        let 科目 = 現行方案.get_untracked();
+        let 方案定義 = 方案定義.read_untracked();
+        let Some(庫) = 片段庫::收集(科目.配套練習題().unwrap_or(&[]), &方案定義)
+        else {
+            return;
+        };
+        let 碼表 = 生成加練(
+            &庫,
+            &方案定義,
+            &方案紀錄.read_untracked(),
+            js_sys::Date::now(),
+            加練題量,
+            js_sys::Math::random,
+        );
+        佈置作業(作業::生成(
+            科目,
+            生成題 {
+                標題: 加練標題,
+                內容: 作業內容 {
+                    碼表,
+                    字幕: 字幕格式::自動生成,
+                },
+            },
+        ));
+    };
+
+    加練機關輸出信號 { 佈置加練 }
+}
@@ -2,16 +2,18 @@ use gloo_net::http::Request;
 use lazy_regex::regex;
 use leptos::logging::log;
 use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::cmp::min;
+use strum::Display;
 
-use crate::action::*;
+use crate::action::動作;
 use crate::definition::{碼表格式, 輸入方案定義, 轉寫法定義};
 use crate::drill::{練習題, 題目來源};
 use crate::gear::{
     caption::字幕格式,
     theory::{方案選項, 輸入方案機關輸出信號},
 };
+use crate::headless::引擎核心;
 use crate::spelling_algebra::施展拼寫運算;
 
 #[derive(Clone, PartialEq)]
@@ -19,6 +21,14 @@ pub struct 作業 {
     pub 科目: 方案選項,
     pub 題號: Option<usize>,
     pub 自訂反查碼: Option<String>,
+    pub 生成題: Option<生成題>,
+}
+
+/// 依練習情況即時生成的練習題
+#[derive(Clone, PartialEq)]
+pub struct 生成題 {
+    pub 標題: &'static str,
+    pub 內容: 作業內容<'static>,
 }
 
 impl 作業 {
@@ -27,6 +37,7 @@ impl 作業 {
             科目,
             題號: Some(題號),
             自訂反查碼: None,
+            生成題: None,
         }
     }
 
@@ -35,6 +46,7 @@ impl 作業 {
             科目,
             題號: None,
             自訂反查碼: Some(反查碼),
+            生成題: None,
         }
     }
 
@@ -43,6 +55,16 @@ impl 作業 {
             科目,
             題號: None,
             自訂反查碼: None,
+            生成題: None,
+        }
+    }
+
+    pub fn 生成(科目: 方案選項, 生成題: 生成題) -> Self {
+        Self {
+            科目,
+            題號: None,
+            自訂反查碼: None,
+            生成題: Some(生成題),
         }
     }
 
@@ -51,13 +73,13 @@ impl 作業 {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct 作業內容<'a> {
     pub 碼表: 碼表定義<'a>,
     pub 字幕: 字幕格式<'a>,
 }
 
-fn 解析習題(習題文本: &str) -> 作業內容<'static> {
+pub fn 解析習題(習題文本: &str) -> 作業內容<'static> {
     match 習題文本.split_once("//") {
         Some((編碼, 字幕)) => 作業內容 {
             碼表: 碼表定義::自訂(Cow::Owned(編碼.trim().to_string())),
@@ -87,6 +109,25 @@ impl 對照輸入碼 {
         })
     }
 
+    /// 列出所有可用的字根碼. 正規字根碼居首, 其後爲派生所得,
+    /// 再後依次爲模糊、縮寫所得.
+    pub fn 反查衆字根碼<'a>(&'a self, 轉寫法: &轉寫法定義<'a>) -> Vec<String> {
+        match (&self.字根碼原文, &self.轉寫碼原文) {
+            (Some(字根碼), _) => vec![字根碼.to_owned()],
+            (None, Some(轉寫碼)) if 轉寫法.驗證拼式(轉寫碼) => {
+                let mut 衆字根碼 = 轉寫法.拼式拆分爲衆字根碼(轉寫碼);
+                衆字根碼.sort_by_key(|字根碼| 字根碼.類別);
+                衆字根碼
+                    .into_iter()
+                    .map(|字根碼| {
+                        施展拼寫運算(&字根碼.拼式, 轉寫法.輸入碼表示).unwrap_or(字根碼.拼式)
+                    })
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+
     /// 用於顯示的輸入碼. 優先顯示轉寫碼.
     pub fn 顯示輸入碼(&self) -> Option<&str> {
         self.轉寫碼原文.as_deref().or(self.字根碼原文.as_deref())
@@ -99,30 +140,50 @@ pub struct 步進法 {
     pub 迴轉: bool,
 }
 
+/// 擊錯時作業是否推進
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Serialize, Deserialize)]
+pub enum 推進方式 {
+    /// 擊中目標方可推進, 擊錯須重擊
+    #[default]
+    #[strum(to_string = "重擊")]
+    擊中推進,
+    /// 擊完一個片段, 對錯都推進. 擊錯的片段記下, 在字幕中標出
+    #[strum(to_string = "記錯")]
+    記錯推進,
+}
+
+impl 推進方式 {
+    pub fn 下一項(self) -> Self {
+        match self {
+            推進方式::擊中推進 => 推進方式::記錯推進,
+            推進方式::記錯推進 => 推進方式::擊中推進,
+        }
+    }
+}
+
 pub type 重置作業進度動作 = impl 動作;
-pub type 作業推進動作 = impl 動作給一參數得一結果<步進法>;
-pub type 作業回退動作 = impl 動作給一參數得一結果<步進法>;
+pub type 切換推進方式動作 = impl 動作;
 
 #[derive(Clone)]
 pub struct 作業機關輸出信號 {
     pub 當前作業: ReadSignal<作業>,
     pub 佈置作業: WriteSignal<作業>,
-    pub 作業進度: ReadSignal<usize>,
     pub 重置作業進度: 重置作業進度動作,
     pub 目標作業內容: LocalResource<Option<作業內容<'static>>>,
-    pub 目標碼表格式: Signal<Option<碼表格式>>,
-    pub 目標輸入碼序列: Memo<Box<[對照輸入碼]>>,
-    pub 目標輸入碼片段: Signal<Option<對照輸入碼>>,
-    pub 作業推進: 作業推進動作,
-    pub 作業回退: 作業回退動作,
-    pub 有無作業: Signal<bool>,
-    pub 作業進度完成: Signal<bool>,
+    pub 目標輸入碼片段: Memo<Option<對照輸入碼>>,
+    pub 作業進度完成: Memo<bool>,
+    /// 打完末一片段, 尚未再擊鍵. 分段的作業迴轉到開頭, 故不能只看作業進度完成
+    pub 一題打完: Memo<bool>,
+    pub 推進方式: Memo<推進方式>,
+    pub 切換推進方式: 切換推進方式動作,
 }
 
-#[define_opaque(重置作業進度動作, 作業推進動作, 作業回退動作)]
-pub fn 作業機關(方案: &輸入方案機關輸出信號) -> 作業機關輸出信號 {
+#[define_opaque(重置作業進度動作, 切換推進方式動作)]
+pub fn 作業機關(
+    方案: &輸入方案機關輸出信號,
+    核心: RwSignal<引擎核心>,
+) -> 作業機關輸出信號 {
     let 現行方案 = 方案.現行方案;
-    let 方案定義 = 方案.方案定義;
     let 初始方案 = 現行方案.get_untracked();
     let (當前作業, 佈置作業) = signal(作業::練習題(初始方案, 0));
 
@@ -134,10 +195,8 @@ pub fn 作業機關(方案: &輸入方案機關輸出信號) -> 作業機關輸
         false,
     );
 
-    let (作業進度, 更新作業進度) = signal(0);
-
     let 重置作業進度 = move || {
-        更新作業進度(0);
+        核心.write().重置作業進度();
     };
 
     let 目標作業內容 = LocalResource::new(move || {
@@ -163,112 +222,54 @@ pub fn 作業機關(方案: &輸入方案機關輸出信號) -> 作業機關輸
                     字幕: 字幕.clone(),
                 }),
 
-                None => 作業.自訂反查碼.as_deref().map(解析習題),
+                None => 作業
+                    .生成題
+                    .map(|生成題| 生成題.內容)
+                    .or_else(|| 作業.自訂反查碼.as_deref().map(解析習題)),
             }
         }
     });
 
-    let 目標碼表格式 = Signal::derive(move || {
-        目標作業內容
-            .read()
-            .as_ref()
-            .flatten()
-            .and_then(|作業| 作業.碼表.碼表格式())
-    });
-
-    let 目標輸入碼序列 = Memo::new(move |_| {
-        目標作業內容
-            .read()
-            .as_ref()
-            .flatten()
-            .map(|作業| 解析碼表(&作業.碼表, &方案定義.read()))
-            .unwrap_or(Box::new([]))
-    });
-
-    let _ = Effect::watch(
-        目標輸入碼序列,
-        move |輸入碼, _, _| {
-            log!("更新了目標輸入碼: {}", 輸入碼.len());
-            重置作業進度();
-        },
-        false,
-    );
-
-    let 目標輸入碼片段 = Signal::derive(move || {
-        目標輸入碼序列.with(|輸入碼| {
-            if 輸入碼.is_empty() {
-                None
-            } else {
-                輸入碼.get(min(作業進度(), 輸入碼.len() - 1)).cloned()
-            }
-        })
+    Effect::new(move || {
+        let 內容 = 目標作業內容.read().as_ref().flatten().cloned();
+        let mut 核心 = 核心.write();
+        核心.佈置作業(內容);
+        log!("更新了目標輸入碼: {}", 核心.目標輸入碼序列().len());
     });
 
-    let 作業推進 = move |步進: 步進法| {
-        let 當前進度 = 作業進度();
-        let 全文長度 = 目標輸入碼序列.read().len();
-        let 目標進度 = 步進.目標.unwrap_or(當前進度 + 1);
-        if 步進.迴轉 && 目標進度 >= 全文長度 {
-            重置作業進度();
-            Ok(())
-        }
-        // 非迴轉態可推進至全文結束位置
-        else if 目標進度 <= 全文長度 {
-            更新作業進度(目標進度);
-            Ok(())
-        } else {
-            Err(未有())
-        }
-    };
+    let 目標輸入碼片段 = Memo::new(move |_| 核心.read().目標輸入碼片段().cloned());
+    let 作業進度完成 = Memo::new(move |_| 核心.read().作業進度完成());
+    let 一題打完 = Memo::new(move |_| 核心.read().一題打完());
+    let 推進方式 = Memo::new(move |_| 核心.read().推進方式());
 
-    let 作業回退 = move |步進: 步進法| {
-        let 當前進度 = 作業進度();
-        let 全文長度 = 目標輸入碼序列.read().len();
-        match 步進.目標 {
-            Some(目標進度) if 步進.迴轉 || 當前進度 > 目標進度 => {
-                更新作業進度(目標進度);
-                Ok(())
-            }
-            None if 步進.迴轉 && 當前進度 == 0 && 全文長度 > 0 => {
-                更新作業進度(全文長度 - 1);
-                Ok(())
-            }
-            None if 當前進度 > 0 => {
-                更新作業進度(當前進度 - 1);
-                Ok(())
-            }
-            _ => Err(未有()),
-        }
+    let 切換推進方式 = move || {
+        核心.update(|核心| {
+            let 下一項 = 核心.推進方式().下一項();
+            核心.設置推進方式(下一項);
+        });
     };
 
-    let 輸入碼總數 = move || 目標輸入碼序列.read().len();
-
-    let 有無作業 = Signal::derive(move || 輸入碼總數() > 0);
-
-    let 作業進度完成 = Signal::derive(move || 有無作業() && 作業進度() == 輸入碼總數());
-
     作業機關輸出信號 {
         當前作業,
         佈置作業,
-        作業進度,
         重置作業進度,
         目標作業內容,
-        目標碼表格式,
-        目標輸入碼序列,
         目標輸入碼片段,
-        作業推進,
-        作業回退,
-        有無作業,
         作業進度完成,
+        一題打完,
+        推進方式,
+        切換推進方式,
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum 碼表定義<'a> {
     逐鍵(&'a str),
     連擊(&'a str),
     並擊(&'a str),
     自訂(Cow<'a, str>),
+    /// 即時生成的碼表, 格式由生成者指定
+    生成(碼表格式, String),
 }
 
 impl 碼表定義<'_> {
@@ -278,6 +279,7 @@ impl 碼表定義<'_> {
             Self::連擊(s) => s,
             Self::並擊(s) => s,
             Self::自訂(s) => s,
+            Self::生成(_, s) => s,
         }
     }
 
@@ -287,20 +289,30 @@ impl 碼表定義<'_> {
             Self::連擊(_) => Some(碼表格式::連擊),
             Self::並擊(_) => Some(碼表格式::並擊),
             Self::自訂(_) => None,
+            Self::生成(格式, _) => Some(*格式),
         }
     }
 }
 
-fn 解析碼表(碼表: &碼表定義, 方案: &輸入方案定義) -> Box<[對照輸入碼]> {
+pub fn 解析碼表(碼表: &碼表定義, 方案: &輸入方案定義) -> Box<[對照輸入碼]> {
     match 碼表 {
         碼表定義::逐鍵(輸入碼序列) => 解析逐鍵輸入碼序列(輸入碼序列, 方案),
         碼表定義::連擊(輸入碼序列) => 解析連擊輸入碼序列(輸入碼序列),
         碼表定義::並擊(輸入碼序列) => 解析並擊輸入碼序列(輸入碼序列),
-        碼表定義::自訂(輸入碼序列) => match 方案.編碼法 {
-            碼表格式::逐鍵 => 解析逐鍵輸入碼序列(輸入碼序列, 方案),
-            碼表格式::連擊 => 解析連擊輸入碼序列(輸入碼序列),
-            碼表格式::並擊 => 解析並擊輸入碼序列(輸入碼序列),
-        },
+        碼表定義::自訂(輸入碼序列) => 按格式解析(方案.編碼法, 輸入碼序列, 方案),
+        碼表定義::生成(格式, 輸入碼序列) => 按格式解析(*格式, 輸入碼序列, 方案),
+    }
+}
+
+fn 按格式解析(
+    格式: 碼表格式,
+    輸入碼序列: &str,
+    方案: &輸入方案定義,
+) -> Box<[對照輸入碼]> {
+    match 格式 {
+        碼表格式::逐鍵 => 解析逐鍵輸入碼序列(輸入碼序列, 方案),
+        碼表格式::連擊 => 解析連擊輸入碼序列(輸入碼序列),
+        碼表格式::並擊 => 解析並擊輸入碼序列(輸入碼序列),
     }
 }
 
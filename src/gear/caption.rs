@@ -1,11 +1,10 @@
 use leptos::prelude::*;
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 
 use crate::definition::觸鍵方式;
-use crate::gear::{
-    assignment::{作業機關輸出信號, 對照輸入碼},
-    theory::輸入方案機關輸出信號,
-};
+use crate::gear::assignment::對照輸入碼;
+use crate::headless::引擎核心;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum 字幕步進 {
@@ -22,7 +21,7 @@ impl From<觸鍵方式> for 字幕步進 {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum 字幕格式<'a> {
     自動生成,
     自訂(Cow<'a, str>),
@@ -88,119 +87,143 @@ fn 所屬段落序號(衆段落: &[字幕段落], 進度: usize) -> usize {
 #[derive(Clone)]
 pub struct 字幕表示 {
     pub 已完成: String,
+    /// 已完成的文字, 按是否擊錯分成幾截
+    pub 已完成分截: Vec<(String, bool)>,
     pub 指標文字: String,
     pub 未完成: String,
 }
 
-#[derive(Clone, Copy)]
-pub struct 字幕機關輸出信號 {
-    pub 分段字幕: Memo<Box<[字幕段落<'static>]>>,
-    pub 當前段落: Memo<Option<字幕段落<'static>>>,
-    pub 前序段落: Signal<Option<字幕段落<'static>>>,
-    pub 段落表示: Signal<Option<字幕表示>>,
+/// 影子進度所在之字, 前後文字僅用於對齊
+#[derive(Clone, PartialEq)]
+pub struct 影子表示 {
+    pub 之前: String,
+    pub 指標文字: String,
+    pub 之後: String,
 }
 
-pub fn 字幕機關(
-    方案: &輸入方案機關輸出信號,
-    作業: &作業機關輸出信號,
-) -> 字幕機關輸出信號 {
-    let 指法 = 方案.指法;
-    let 作業進度 = 作業.作業進度;
-    let 目標作業內容 = 作業.目標作業內容;
-    let 目標輸入碼序列 = 作業.目標輸入碼序列;
-
-    let 分段字幕 = Memo::new(move |_| {
-        目標作業內容.read().as_ref().flatten().map_or_else(
-            || Box::from([]),
-            |作業| match 作業.字幕 {
-                字幕格式::自動生成 => {
-                    let 步進 = 字幕步進::from(指法());
-                    生成字幕(步進, &目標輸入碼序列.read())
-                }
-                字幕格式::自訂(ref 字幕) => {
-                    標註字序(字幕.split_whitespace().map(String::from).map(Cow::Owned))
-                }
-                字幕格式::詞句(字幕) => {
-                    標註字序(字幕.split_whitespace().map(Cow::Borrowed))
-                }
-                字幕格式::段落(字幕步進::逐字, 字幕) => 標註字序(
-                    字幕
-                        .lines()
-                        .map(|每一行| 每一行.split_whitespace().collect::<Vec<_>>().join("[ ]"))
-                        .map(Cow::Owned),
-                ),
-                字幕格式::段落(字幕步進::逐詞, 字幕) => 標註字序(
-                    字幕
-                        .lines()
-                        .map(|每一行| {
-                            每一行
-                                .split_whitespace()
-                                .flat_map(|每個詞| ["[", 每個詞, " ]"])
-                                .collect::<String>()
-                        })
-                        .map(Cow::Owned),
-                ),
-            },
-        )
-    });
-
-    let 當前段落 = Memo::new(move |_| {
-        分段字幕.with(|衆段落| {
-            let 全文進度 = 作業進度();
-            let 當前段落號 = 所屬段落序號(衆段落, 全文進度);
-            衆段落
-                .get(當前段落號)
-                .or_else(|| {
-                    衆段落
-                        .last()
-                        .filter(|字幕段落(_, 全文結束, _)| *全文結束 == 全文進度)
+/// 按作業內容中的字幕格式, 將字幕分段並標註字序
+pub fn 生成分段字幕<'a>(
+    字幕: &字幕格式<'a>,
+    指法: 觸鍵方式,
+    輸入碼序列: &[對照輸入碼],
+) -> Box<[字幕段落<'a>]> {
+    match *字幕 {
+        字幕格式::自動生成 => 生成字幕(字幕步進::from(指法), 輸入碼序列),
+        字幕格式::自訂(ref 字幕) => {
+            標註字序(字幕.split_whitespace().map(String::from).map(Cow::Owned))
+        }
+        字幕格式::詞句(字幕) => 標註字序(字幕.split_whitespace().map(Cow::Borrowed)),
+        字幕格式::段落(字幕步進::逐字, 字幕) => 標註字序(
+            字幕
+                .lines()
+                .map(|每一行| 每一行.split_whitespace().collect::<Vec<_>>().join("[ ]"))
+                .map(Cow::Owned),
+        ),
+        字幕格式::段落(字幕步進::逐詞, 字幕) => 標註字序(
+            字幕
+                .lines()
+                .map(|每一行| {
+                    每一行
+                        .split_whitespace()
+                        .flat_map(|每個詞| ["[", 每個詞, " ]"])
+                        .collect::<String>()
                 })
-                .cloned()
-        })
-    });
-
-    let 前序段落 = Signal::derive(move || {
-        分段字幕.with(|衆段落| {
-            let 全文進度 = 作業進度();
-            let 當前段落號 = 所屬段落序號(衆段落, 全文進度);
-            if 當前段落號 == 0 {
-                None
-            } else {
-                衆段落.get(當前段落號 - 1).cloned()
-            }
-        })
-    });
-
-    let 段落表示 = Signal::derive(move || {
-        當前段落().map(|字幕段落(段落起始, _, 段落文字)| {
-            let 全文進度 = 作業進度();
-            let 段落進度 = 全文進度 - 段落起始;
-            let 已完成 = 字幕指標::from(段落文字.as_ref())
-                .take(段落進度)
-                .collect::<String>();
-            let 指標文字 = 字幕指標::from(段落文字.as_ref())
-                .skip(段落進度)
-                .take(1)
-                .collect::<String>();
-            let 未完成 = 字幕指標::from(段落文字.as_ref())
-                .skip(段落進度 + 1)
-                .collect::<String>();
-            字幕表示 {
-                已完成,
-                指標文字,
-                未完成,
+                .map(Cow::Owned),
+        ),
+    }
+}
+
+/// 作業進度所在的段落. 全文完成時, 停留在末段.
+pub fn 當前段落<'s, 'a>(
+    衆段落: &'s [字幕段落<'a>],
+    全文進度: usize,
+) -> Option<&'s 字幕段落<'a>> {
+    衆段落.get(所屬段落序號(衆段落, 全文進度)).or_else(|| {
+        衆段落
+            .last()
+            .filter(|字幕段落(_, 全文結束, _)| *全文結束 == 全文進度)
+    })
+}
+
+pub fn 前序段落<'s, 'a>(
+    衆段落: &'s [字幕段落<'a>],
+    全文進度: usize,
+) -> Option<&'s 字幕段落<'a>> {
+    match 所屬段落序號(衆段落, 全文進度) {
+        0 => None,
+        當前段落號 => 衆段落.get(當前段落號 - 1),
+    }
+}
+
+impl 字幕段落<'_> {
+    /// 全文中第 `全文序號` 個片段對應的文字, 不在本段則爲空
+    pub fn 文字(&self, 全文序號: usize) -> Option<String> {
+        let 字幕段落(段落起始, 段落結束, ref 段落文字) = *self;
+        (段落起始..段落結束)
+            .contains(&全文序號)
+            .then(|| 字幕指標::from(段落文字.as_ref()).nth(全文序號 - 段落起始))
+            .flatten()
+    }
+
+    /// 影子不在本段則爲空. 打完本段時, 指標文字爲空.
+    pub fn 影子表示(&self, 影子進度: usize) -> Option<影子表示> {
+        let 字幕段落(段落起始, 段落結束, ref 段落文字) = *self;
+        (段落起始..=段落結束).contains(&影子進度).then(|| {
+            let 段落進度 = 影子進度 - 段落起始;
+            let 衆字 = || 字幕指標::from(段落文字.as_ref());
+            影子表示 {
+                之前: 衆字().take(段落進度).collect(),
+                指標文字: 衆字().nth(段落進度).unwrap_or_default(),
+                之後: 衆字().skip(段落進度 + 1).collect(),
             }
         })
-    });
+    }
 
-    字幕機關輸出信號 {
-        分段字幕,
-        當前段落,
-        前序段落,
-        段落表示,
+    /// `擊錯片段` 爲全文中擊錯的片段序號
+    pub fn 表示(&self, 全文進度: usize, 擊錯片段: &BTreeSet<usize>) -> 字幕表示 {
+        let 字幕段落(段落起始, _, ref 段落文字) = *self;
+        let 段落進度 = 全文進度 - 段落起始;
+        let 已完成 = 字幕指標::from(段落文字.as_ref())
+            .take(段落進度)
+            .collect::<String>();
+        let mut 已完成分截 = Vec::<(String, bool)>::new();
+        for (序號, 文字) in 字幕指標::from(段落文字.as_ref()).take(段落進度).enumerate()
+        {
+            let 擊錯 = 擊錯片段.contains(&(段落起始 + 序號));
+            match 已完成分截.last_mut() {
+                Some((這一截, 這一截擊錯)) if *這一截擊錯 == 擊錯 => {
+                    這一截.push_str(&文字)
+                }
+                _ => 已完成分截.push((文字, 擊錯)),
+            }
+        }
+        let 指標文字 = 字幕指標::from(段落文字.as_ref())
+            .skip(段落進度)
+            .take(1)
+            .collect::<String>();
+        let 未完成 = 字幕指標::from(段落文字.as_ref())
+            .skip(段落進度 + 1)
+            .collect::<String>();
+        字幕表示 {
+            已完成,
+            已完成分截,
+            指標文字,
+            未完成,
+        }
     }
 }
 
+#[derive(Clone, Copy)]
+pub struct 字幕機關輸出信號 {
+    pub 段落表示: Signal<Option<字幕表示>>,
+}
+
+pub fn 字幕機關(核心: RwSignal<引擎核心>) -> 字幕機關輸出信號 {
+    let 段落表示 = Signal::derive(move || 核心.read().段落表示());
+
+    字幕機關輸出信號 { 段落表示 }
+}
+
 fn 生成字幕<'a>(
     步進: 字幕步進, 輸入碼序列: &[對照輸入碼]
 ) -> Box<[字幕段落<'a>]> {
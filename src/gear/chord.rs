@@ -1,12 +1,13 @@
 use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::Display;
 
 use crate::action::動作;
 use crate::definition::鍵組;
-use crate::gear::{
-    assignment::作業機關輸出信號, layout::盤面選擇碼, theory::輸入方案機關輸出信號
-};
+use crate::headless::引擎核心;
 use crate::key_code::KeyCode;
 
+#[derive(Clone, Default, PartialEq)]
 pub struct 並擊狀態 {
     pub 實時落鍵: 鍵組,
     pub 累計擊鍵: 鍵組,
@@ -47,79 +48,73 @@ impl 並擊狀態 {
     pub fn 並擊完成(&mut self) {}
 }
 
-pub type 並擊重置動作 = impl 動作;
+/// 並擊與目標不盡相同時, 如何判定對錯
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Serialize, Deserialize)]
+pub enum 並擊判定 {
+    /// 轉寫所得拼式一致即可, 容錯、派生的並擊亦算擊中
+    #[default]
+    #[strum(to_string = "同拼式")]
+    同拼式,
+    /// 須按出反查所得的正規並擊
+    #[strum(to_string = "正規")]
+    正規並擊,
+    /// 拼式一致即算擊中, 但非正規的並擊另行計數
+    #[strum(to_string = "標記")]
+    標記非正規,
+}
 
-#[derive(Clone)]
+impl 並擊判定 {
+    pub fn 下一項(self) -> Self {
+        match self {
+            並擊判定::同拼式 => 並擊判定::正規並擊,
+            並擊判定::正規並擊 => 並擊判定::標記非正規,
+            並擊判定::標記非正規 => 並擊判定::同拼式,
+        }
+    }
+}
+
+pub type 切換並擊判定動作 = impl 動作;
+
+#[derive(Clone, Copy)]
 pub struct 並擊機關輸出信號 {
-    pub 並擊狀態流: ReadSignal<並擊狀態>,
-    pub 並擊狀態變更: WriteSignal<並擊狀態>,
-    pub 重置並擊狀態: 並擊重置動作,
+    pub 並擊狀態流: Memo<並擊狀態>,
     pub 實況並擊碼: Memo<String>,
-    pub 並擊所得拼音: Signal<Option<String>>,
+    pub 並擊所得拼音: Memo<Option<String>>,
     pub 反查所得並擊碼: Memo<Option<String>>,
-    pub 反查鍵位: Signal<Option<鍵組>>,
-    pub 並擊完成: Signal<bool>,
+    pub 反查鍵位: Memo<Option<鍵組>>,
+    pub 並擊完成: Memo<bool>,
     pub 並擊成功: Memo<bool>,
+    pub 並擊判定: Memo<並擊判定>,
+    pub 切換並擊判定: 切換並擊判定動作,
 }
 
-#[define_opaque(並擊重置動作)]
-pub fn 並擊機關(
-    方案: &輸入方案機關輸出信號,
-    作業: &作業機關輸出信號,
-) -> 並擊機關輸出信號 {
-    let 方案定義 = 方案.方案定義;
-    let 目標輸入碼片段 = 作業.目標輸入碼片段;
-
-    let (並擊狀態流, 並擊狀態變更) = signal(並擊狀態::new());
-
-    let 重置並擊狀態 = move || {
-        並擊狀態變更.write().重置();
+#[define_opaque(切換並擊判定動作)]
+pub fn 並擊機關(核心: RwSignal<引擎核心>) -> 並擊機關輸出信號 {
+    let 並擊狀態流 = Memo::new(move |_| 核心.read().並擊狀態().clone());
+    let 實況並擊碼 = Memo::new(move |_| 核心.read().實況並擊碼());
+    let 並擊所得拼音 = Memo::new(move |_| 核心.read().並擊所得拼音());
+    let 反查所得並擊碼 = Memo::new(move |_| 核心.read().反查所得並擊碼());
+    let 反查鍵位 = Memo::new(move |_| 核心.read().反查鍵位());
+    let 並擊完成 = Memo::new(move |_| 核心.read().並擊完成());
+    let 並擊成功 = Memo::new(move |_| 核心.read().並擊成功());
+    let 並擊判定 = Memo::new(move |_| 核心.read().並擊判定());
+
+    let 切換並擊判定 = move || {
+        核心.update(|核心| {
+            let 下一項 = 核心.並擊判定().下一項();
+            核心.設置並擊判定(下一項);
+        });
     };
 
-    let 實況並擊碼 =
-        Memo::new(move |_| 方案定義.read().寫成字根碼(&並擊狀態流.read().累計擊鍵));
-    let 並擊所得拼音 =
-        Signal::derive(move || 方案定義.read().轉寫法.字根碼轉寫爲拼式(&實況並擊碼()));
-
-    let 反查所得並擊碼 = Memo::new(move |_| {
-        目標輸入碼片段
-            .read()
-            .as_ref()
-            .and_then(|對照碼| 對照碼.反查字根碼(&方案定義.read().轉寫法))
-    });
-    let 反查鍵位 = Signal::derive(move || {
-        反查所得並擊碼
-            .read()
-            .as_deref()
-            .map(|並擊碼| 方案定義.read().讀出鍵位(並擊碼, &盤面選擇碼(0)))
-    });
-
-    let _並擊開始 = Signal::derive(move || !並擊狀態流.read().實時落鍵.0.is_empty());
-
-    let 並擊完成 = Signal::derive(move || {
-        並擊狀態流.read().實時落鍵.0.is_empty() && !實況並擊碼.read().is_empty()
-    });
-
-    let 並擊成功 = Memo::new(move |_| {
-        // 拼音一致即爲成功，允許並擊碼不同
-        目標輸入碼片段()
-            .is_some_and(|輸入碼| {
-                輸入碼.字根碼原文.is_some_and(|查得| 查得 == 實況並擊碼()) ||
-                輸入碼.轉寫碼原文.is_some_and(|查得| 並擊所得拼音().is_some_and(|擊得| 查得 == 擊得))
-            })
-            // 拼音爲非音節形式的聲母、韻母，須比較並擊碼
-            || 反查所得並擊碼().is_some_and(|查得| 查得 == 實況並擊碼())
-    });
-
     並擊機關輸出信號 {
         並擊狀態流,
-        並擊狀態變更,
-        重置並擊狀態,
         實況並擊碼,
         並擊所得拼音,
         反查所得並擊碼,
         反查鍵位,
         並擊完成,
         並擊成功,
+        並擊判定,
+        切換並擊判定,
     }
 }
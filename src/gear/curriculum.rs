@@ -0,0 +1,351 @@
+//! 鍵位解鎖
+//!
+//! 仿 keybr 之法: 起初只開放字根表中最常用的幾個鍵, 練習題只出這幾個鍵打得出的片段;
+//! 一課的正確率與速度達標, 便解鎖下一個鍵. 各方案已解鎖的鍵數存於瀏覽器.
+
+use leptos::logging::log;
+use leptos::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::action::動作;
+use crate::definition::{碼表格式, 觸鍵方式, 輸入方案定義};
+use crate::drill::練習題;
+use crate::gear::{
+    adaptive::片段庫,
+    assignment::{
+        作業, 作業內容, 作業機關輸出信號, 對照輸入碼, 生成題, 碼表定義
+    },
+    caption::字幕格式,
+    heatmap::片段鍵位,
+    history::本地存儲,
+    layout::盤面選擇碼,
+    statistics::{統計摘要, 統計機關輸出信號},
+    theory::輸入方案機關輸出信號,
+};
+use crate::key_code::KeyCode;
+use crate::theory_audit::遍歷諸鍵並擊;
+
+pub const 解鎖標題: &str = "鍵位解鎖";
+
+const 起始鍵數: usize = 4;
+
+/// 開放的鍵打得出的片段少於此數, 便多開放一個鍵
+const 最少片段數: usize = 5;
+
+const 課程題量: usize = 30;
+
+/// 並擊方案只遍歷不多於此數的鍵的組合
+const 並擊鍵數上限: usize = 4;
+
+/// 含新鍵的片段出現的機會是其他片段的幾倍
+const 新鍵權重: f64 = 3.0;
+
+pub const 達標正確率: f64 = 0.9;
+
+/// 每分鐘片段數. 並擊計音節, 連擊計字根.
+pub fn 達標速度(指法: 觸鍵方式) -> f64 {
+    match 指法 {
+        觸鍵方式::並擊 => 20.0,
+        觸鍵方式::連擊 => 40.0,
+    }
+}
+
+pub fn 是否達標(摘要: &統計摘要, 指法: 觸鍵方式) -> bool {
+    摘要.首擊正確率() >= 達標正確率 && 摘要.每分鐘片段數() >= 達標速度(指法)
+}
+
+/// 開放鍵位的先後: 配套練習題中用得多的鍵在前, 未用到的按字根表次序殿後
+pub fn 解鎖次序(方案: &輸入方案定義, 練習題: &[練習題]) -> Vec<KeyCode> {
+    let mut 用量 = BTreeMap::<KeyCode, usize>::new();
+    for 片段 in 片段庫::收集(練習題, 方案).map_or_else(Vec::new, |庫| 庫.衆片段) {
+        for 鍵 in 片段鍵位(方案, 片段.顯示輸入碼().unwrap_or_default()) {
+            *用量.entry(鍵).or_default() += 1;
+        }
+    }
+    let mut 次序 = Vec::<KeyCode>::new();
+    for 字根 in 方案.字根表 {
+        if 字根.鍵碼 != KeyCode::No && !次序.contains(&字根.鍵碼) {
+            次序.push(字根.鍵碼);
+        }
+    }
+    // 穩定排序, 用量相同者保持字根表次序
+    次序.sort_by_key(|鍵| std::cmp::Reverse(用量.get(鍵).copied().unwrap_or(0)));
+    次序
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct 課程 {
+    pub 已解鎖: Vec<KeyCode>,
+    /// 最後解鎖的鍵, 練習題側重於它
+    pub 新鍵: Option<KeyCode>,
+    pub 全部鍵數: usize,
+    pub 片段庫: 片段庫,
+}
+
+/// 片段所用的鍵. 字根全在動態盤面上的方案, 基本盤面上讀不出鍵位, 便在各盤面上找.
+fn 片段所用鍵(方案: &輸入方案定義, 片段: &對照輸入碼) -> Vec<KeyCode> {
+    let Some(字根碼) = 片段.字根碼原文.as_deref() else {
+        return 片段鍵位(方案, 片段.顯示輸入碼().unwrap_or_default());
+    };
+    let 基本盤面鍵位 = 方案.讀出鍵位(字根碼, &盤面選擇碼(0));
+    let 鍵位 = if !基本盤面鍵位.0.is_empty() {
+        基本盤面鍵位
+    } else {
+        方案.讀出鍵位(字根碼, &盤面選擇碼(u64::MAX))
+    };
+    鍵位.0.into_iter().collect()
+}
+
+/// 只用 `衆鍵` 打得出的片段.
+/// 並擊方案遍歷諸鍵的組合, 取轉寫得出拼式者; 連擊方案取各鍵基本盤面上的單字字根,
+/// 基本盤面上沒有字根的, 改從練習題的片段中挑選.
+fn 可打片段(
+    方案: &輸入方案定義, 練習題片段: Option<&片段庫>, 衆鍵: &[KeyCode]
+) -> 片段庫 {
+    match 方案.指法 {
+        觸鍵方式::並擊 => {
+            let mut 衆片段 = Vec::<對照輸入碼>::new();
+            let mut 已收拼式 = Vec::<String>::new();
+            let mut 有效並擊 = 遍歷諸鍵並擊(方案, 衆鍵, 並擊鍵數上限).有效並擊;
+            // 同一拼式取鍵數最少的並擊
+            有效並擊.sort_by_key(|並擊| 並擊.鍵位.0.len());
+            for 並擊 in 有效並擊 {
+                if 已收拼式.contains(&並擊.拼式) {
+                    continue;
+                }
+                // 正規並擊已開放者只寫拼式, 否則註明並擊碼
+                let 正規鍵位 = 片段鍵位(方案, &並擊.拼式);
+                let 正規並擊可打 =
+                    !正規鍵位.is_empty() && 正規鍵位.iter().all(|鍵| 衆鍵.contains(鍵));
+                // 並擊碼經表示規則改寫後, 未必讀得回原來的鍵位
+                if !正規並擊可打 && 方案.讀出鍵位(&並擊.並擊碼, &盤面選擇碼(0)) != 並擊.鍵位
+                {
+                    continue;
+                }
+                衆片段.push(對照輸入碼 {
+                    字根碼原文: (!正規並擊可打).then(|| 並擊.並擊碼.clone()),
+                    轉寫碼原文: Some(並擊.拼式.clone()),
+                });
+                已收拼式.push(並擊.拼式);
+            }
+            片段庫 {
+                格式: 碼表格式::並擊,
+                衆片段,
+            }
+        }
+        觸鍵方式::連擊 => {
+            let 衆片段 = 方案
+                .字根表
+                .iter()
+                .filter(|字根| {
+                    衆鍵.contains(&字根.鍵碼)
+                        && 盤面選擇碼(0).包含盤面(&字根.盤面)
+                        && 字根.輸入碼.chars().count() == 1
+                })
+                .map(|字根| 對照輸入碼 {
+                    字根碼原文: Some(字根.輸入碼.to_owned()),
+                    轉寫碼原文: None,
+                })
+                .collect::<Vec<_>>();
+            match 練習題片段 {
+                Some(庫) if 衆片段.is_empty() => 片段庫 {
+                    格式: 庫.格式,
+                    衆片段: 庫
+                        .衆片段
+                        .iter()
+                        .filter(|片段| {
+                            let 鍵位 = 片段所用鍵(方案, 片段);
+                            !鍵位.is_empty() && 鍵位.iter().all(|鍵| 衆鍵.contains(鍵))
+                        })
+                        .cloned()
+                        .collect(),
+                },
+                _ => 片段庫 {
+                    格式: 碼表格式::逐鍵,
+                    衆片段,
+                },
+            }
+        }
+    }
+}
+
+fn 含有新鍵(方案: &輸入方案定義, 片段: &對照輸入碼, 新鍵: KeyCode) -> bool {
+    片段所用鍵(方案, 片段).contains(&新鍵)
+}
+
+/// 按次序開放前 `解鎖數` 個鍵.
+/// 所得片段太少, 或新鍵打不出任何片段時, 接着開放下一個鍵.
+pub fn 編排課程(
+    方案: &輸入方案定義,
+    練習題: &[練習題],
+    次序: &[KeyCode],
+    解鎖數: usize,
+) -> 課程 {
+    let 練習題片段 = 片段庫::收集(練習題, 方案);
+    let mut 解鎖數 = 解鎖數.clamp(起始鍵數.min(次序.len()), 次序.len());
+    loop {
+        let 已解鎖 = &次序[..解鎖數];
+        let 新鍵 = 已解鎖.last().copied();
+        let 庫 = 可打片段(方案, 練習題片段.as_ref(), 已解鎖);
+        let 足夠 = 庫.衆片段.len() >= 最少片段數
+            && 新鍵.is_none_or(|新鍵| 庫.衆片段.iter().any(|片段| 含有新鍵(方案, 片段, 新鍵)));
+        if 足夠 || 解鎖數 == 次序.len() {
+            return 課程 {
+                已解鎖: 已解鎖.to_vec(),
+                新鍵,
+                全部鍵數: 次序.len(),
+                片段庫: 庫,
+            };
+        }
+        解鎖數 += 1;
+    }
+}
+
+/// 從已開放的鍵打得出的片段中抽取練習, 含新鍵的片段多出現
+pub fn 生成課程練習(
+    方案: &輸入方案定義,
+    課程: &課程,
+    題量: usize,
+    隨機數: impl FnMut() -> f64,
+) -> 碼表定義<'static> {
+    let 庫 = &課程.片段庫;
+    let 衆權重 = 庫
+        .衆片段
+        .iter()
+        .map(|片段| match 課程.新鍵 {
+            Some(新鍵) if 含有新鍵(方案, 片段, 新鍵) => 新鍵權重,
+            _ => 1.0,
+        })
+        .collect::<Vec<_>>();
+    庫.寫成碼表(&庫.按權重抽取(&衆權重, 題量, 隨機數))
+}
+
+/// 各方案已解鎖的鍵數, 以方案代號爲鍵
+const 解鎖進度存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/unlocked",
+};
+
+/// 一課結束後的評定
+#[derive(Clone, Debug, PartialEq)]
+pub struct 課程評定 {
+    pub 達標: bool,
+    /// 達標後新解鎖的鍵的字根
+    pub 解鎖字根: Option<String>,
+}
+
+pub type 佈置解鎖練習動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 解鎖機關輸出信號 {
+    /// 現行課程. 未開始鍵位解鎖練習時爲空.
+    pub 現行課程: ReadSignal<Option<課程>>,
+    pub 課程評定: ReadSignal<Option<課程評定>>,
+    pub 佈置解鎖練習: 佈置解鎖練習動作,
+}
+
+#[define_opaque(佈置解鎖練習動作)]
+pub fn 解鎖機關(
+    方案: &輸入方案機關輸出信號,
+    作業: &作業機關輸出信號,
+    統計: &統計機關輸出信號,
+) -> 解鎖機關輸出信號 {
+    let 現行方案 = 方案.現行方案;
+    let 方案定義 = 方案.方案定義;
+    let 指法 = 方案.指法;
+    let 當前作業 = 作業.當前作業;
+    let 佈置作業 = 作業.佈置作業;
+    let 一題打完 = 作業.一題打完;
+    let 統計摘要 = 統計.統計摘要;
+
+    let (現行課程, 設置現行課程) = signal(None::<課程>);
+    let (課程評定, 設置課程評定) = signal(None::<課程評定>);
+
+    let 是解鎖練習 = move || {
+        當前作業
+            .read_untracked()
+            .生成題
+            .as_ref()
+            .is_some_and(|題| 題.標題 == 解鎖標題)
+    };
+
+    let 佈置解鎖練習 = move || {
+        let 科目 = 現行方案.get_untracked();
+        let 方案定義 = 方案定義.read_untracked();
+        let 練習題 = 科目.配套練習題().unwrap_or(&[]);
+        let 次序 = 解鎖次序(&方案定義, 練習題);
+        let 解鎖數 = 解鎖進度存儲
+            .讀出::<BTreeMap<String, usize>>()
+            .and_then(|進度| 進度.get(科目.slug()).copied())
+            .unwrap_or(起始鍵數);
+        let 課程 = 編排課程(&方案定義, 練習題, &次序, 解鎖數);
+        let 碼表 = 生成課程練習(&方案定義, &課程, 課程題量, js_sys::Math::random);
+        設置現行課程(Some(課程));
+        設置課程評定(None);
+        佈置作業(作業::生成(
+            科目,
+            生成題 {
+                標題: 解鎖標題,
+                內容: 作業內容 {
+                    碼表,
+                    字幕: 字幕格式::自動生成,
+                },
+            },
+        ));
+    };
+
+    let _ = Effect::watch(
+        一題打完,
+        move |&完成, _, _| {
+            if !完成 || !是解鎖練習() {
+                return;
+            }
+            let Some(課程) = 現行課程.get_untracked() else {
+                return;
+            };
+            let 達標 = 是否達標(&統計摘要.read_untracked(), 指法.get_untracked());
+            let mut 解鎖字根 = None;
+            if 達標 && 課程.已解鎖.len() < 課程.全部鍵數 {
+                let 方案定義 = 方案定義.read_untracked();
+                let 次序 = 解鎖次序(
+                    &方案定義,
+                    現行方案.get_untracked().配套練習題().unwrap_or(&[]),
+                );
+                解鎖字根 = 次序
+                    .get(課程.已解鎖.len())
+                    .map(|&鍵| 方案定義.寫成字根碼(&鍵));
+                let mut 進度 = 解鎖進度存儲
+                    .讀出::<BTreeMap<String, usize>>()
+                    .unwrap_or_default();
+                進度.insert(
+                    現行方案.read_untracked().slug().to_owned(),
+                    課程.已解鎖.len() + 1,
+                );
+                if 解鎖進度存儲.存入(&進度).is_err() {
+                    log!("解鎖進度未能存入瀏覽器");
+                }
+            }
+            設置課程評定(Some(課程評定 {
+                達標, 解鎖字根
+            }));
+        },
+        false,
+    );
+
+    // 換了別的作業, 便不再顯示課程
+    let _ = Effect::watch(
+        move || 當前作業.read().生成題.as_ref().map(|題| 題.標題),
+        move |標題, _, _| {
+            if *標題 != Some(解鎖標題) {
+                設置現行課程(None);
+                設置課程評定(None);
+            }
+        },
+        false,
+    );
+
+    解鎖機關輸出信號 {
+        現行課程,
+        課程評定,
+        佈置解鎖練習,
+    }
+}
@@ -0,0 +1,303 @@
+//! 配列幾何
+//!
+//! 鍵盤圖上每個鍵的位置、大小及轉角. 長度以鍵距 (1u, 含鍵間空隙) 爲單位, 縱座標向下.
+//! 內建配列按矩陣逐行排鍵, 行列的錯位、分體間距、拇指區諸鍵均以數據描述.
+
+use crate::gear::layout::{矩陣座標, 配列};
+use crate::key_code::KeyCode;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct 鍵位幾何 {
+    pub 鍵碼: KeyCode,
+    /// 刻印取自盤面上的哪個位置, 爲空則不取刻印
+    pub 刻印座標: Option<矩陣座標>,
+    pub 左: f64,
+    pub 上: f64,
+    pub 寬: f64,
+    pub 高: f64,
+    /// 順時針轉角, 度
+    pub 轉角: f64,
+    /// 繞此點旋轉
+    pub 旋轉中心: (f64, f64),
+    pub 拇指鍵: bool,
+}
+
+impl 鍵位幾何 {
+    pub fn 中心(&self) -> (f64, f64) {
+        (self.左 + self.寬 / 2.0, self.上 + self.高 / 2.0)
+    }
+
+    /// 轉動後四角的位置
+    pub fn 四角(&self) -> [(f64, f64); 4] {
+        let (弧度正弦, 弧度餘弦) = self.轉角.to_radians().sin_cos();
+        let (中心橫, 中心縱) = self.旋轉中心;
+        [
+            (self.左, self.上),
+            (self.左 + self.寬, self.上),
+            (self.左, self.上 + self.高),
+            (self.左 + self.寬, self.上 + self.高),
+        ]
+        .map(|(橫, 縱)| {
+            let (相對橫, 相對縱) = (橫 - 中心橫, 縱 - 中心縱);
+            (
+                中心橫 + 相對橫 * 弧度餘弦 - 相對縱 * 弧度正弦,
+                中心縱 + 相對橫 * 弧度正弦 + 相對縱 * 弧度餘弦,
+            )
+        })
+    }
+
+    /// 首擊歸位鍵, 鍵面上有凸點
+    pub fn 定位鍵(&self) -> bool {
+        matches!(self.鍵碼, KeyCode::F | KeyCode::J)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct 配列幾何 {
+    pub 衆鍵位: Vec<鍵位幾何>,
+}
+
+impl 配列幾何 {
+    /// 平移諸鍵, 使左上角在原點
+    pub fn new(mut 衆鍵位: Vec<鍵位幾何>) -> Self {
+        let (最左, 最上) = 衆鍵位
+            .iter()
+            .flat_map(鍵位幾何::四角)
+            .fold((f64::INFINITY, f64::INFINITY), |(左, 上), (橫, 縱)| {
+                (左.min(橫), 上.min(縱))
+            });
+        if 最左.is_finite() && 最上.is_finite() {
+            for 鍵位 in &mut 衆鍵位 {
+                鍵位.左 -= 最左;
+                鍵位.上 -= 最上;
+                鍵位.旋轉中心 = (鍵位.旋轉中心.0 - 最左, 鍵位.旋轉中心.1 - 最上);
+            }
+        }
+        Self { 衆鍵位 }
+    }
+
+    /// (寬, 高), 含轉動的鍵
+    pub fn 尺寸(&self) -> (f64, f64) {
+        self.衆鍵位
+            .iter()
+            .flat_map(鍵位幾何::四角)
+            .fold((0.0, 0.0), |(寬, 高), (橫, 縱)| {
+                (f64::max(寬, 橫), f64::max(高, 縱))
+            })
+    }
+
+    pub fn 鍵位(&self, 鍵碼: KeyCode) -> Option<&鍵位幾何> {
+        self.衆鍵位.iter().find(|鍵位| 鍵位.鍵碼 == 鍵碼)
+    }
+}
+
+/// 拇指區的一個鍵, 座標與字母區相同
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct 拇指鍵描述 {
+    pub 左: f64,
+    pub 上: f64,
+    pub 寬: f64,
+    /// 繞鍵位中心順時針轉動, 度
+    pub 轉角: f64,
+}
+
+/// 按配列矩陣逐行排鍵的方法
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct 排鍵法 {
+    /// E 至 B 各行 01 列鍵位的橫座標, 即行間錯位
+    pub 行首: [f64; 4],
+    /// 自 01 列起各列的縱向錯位
+    pub 列錯位: &'static [f64],
+    /// 左右兩半在此列之前分開, 及兩半相隔的距離
+    pub 分體: Option<(usize, f64)>,
+    /// 矩陣 A 行諸鍵 (略去 `No`) 依次所在的位置
+    pub 拇指區: &'static [拇指鍵描述],
+}
+
+impl 排鍵法 {
+    pub fn 排列(&self, 配列: 配列) -> 配列幾何 {
+        let mut 衆鍵位 = Vec::new();
+        for (行, 此行) in 配列.矩陣().iter().enumerate() {
+            let 偏移 = 配列.列偏移(行);
+            let 衆鍵 = 此行
+                .iter()
+                .enumerate()
+                .filter(|&(_, &鍵碼)| 鍵碼 != KeyCode::No);
+            if 行 == 4 {
+                衆鍵位.extend(衆鍵.zip(self.拇指區).map(|((列, &鍵碼), 描述)| {
+                    let 鍵位 = 鍵位幾何 {
+                        鍵碼,
+                        刻印座標: 配列.刻印座標(行, 列),
+                        左: 描述.左,
+                        上: 描述.上,
+                        寬: 描述.寬,
+                        高: 1.0,
+                        轉角: 描述.轉角,
+                        旋轉中心: (0.0, 0.0),
+                        拇指鍵: true,
+                    };
+                    鍵位幾何 {
+                        旋轉中心: 鍵位.中心(),
+                        ..鍵位
+                    }
+                }));
+                continue;
+            }
+            let 行首 = self.行首.get(行).copied().unwrap_or_default();
+            衆鍵位.extend(衆鍵.map(|(列, &鍵碼)| {
+                // E00、B00 在 01 列左邊
+                let 序 = 列 as f64 - 偏移 as f64;
+                let 分體間距 = match self.分體 {
+                    Some((分界, 間距)) if 列 >= 分界 + 偏移 => 間距,
+                    _ => 0.0,
+                };
+                let 錯位 = 列
+                    .checked_sub(偏移)
+                    .and_then(|列| self.列錯位.get(列))
+                    .copied()
+                    .unwrap_or_default();
+                let 左 = 行首 + 序 + 分體間距;
+                let 上 = 行 as f64 + 錯位;
+                鍵位幾何 {
+                    鍵碼,
+                    刻印座標: 配列.刻印座標(行, 列),
+                    左,
+                    上,
+                    寬: 1.0,
+                    高: 1.0,
+                    轉角: 0.0,
+                    旋轉中心: (左 + 0.5, 上 + 0.5),
+                    拇指鍵: false,
+                }
+            }));
+        }
+        配列幾何::new(衆鍵位)
+    }
+}
+
+const 無錯位: &[f64] = &[];
+
+/// 小指大幅下沉, 中指最高
+const 縱向錯列: &[f64] = &[1.0, 0.25, -0.25, 0.15, 0.35, 0.35, 0.15, -0.25, 0.25, 1.0];
+
+pub const 主鍵盤區排鍵法: 排鍵法 = 排鍵法 {
+    行首: [0.0, 0.5, 0.75, 1.25],
+    列錯位: 無錯位,
+    分體: None,
+    拇指區: &[拇指鍵描述 {
+        左: 2.75,
+        上: 4.0,
+        寬: 6.25,
+        轉角: 0.0,
+    }],
+};
+
+pub const 字母鍵盤排鍵法: 排鍵法 = 排鍵法 {
+    行首: [0.0, 0.0, 0.25, 0.75],
+    列錯位: 無錯位,
+    分體: None,
+    拇指區: &[拇指鍵描述 {
+        左: 2.0,
+        上: 4.0,
+        寬: 6.0,
+        轉角: 0.0,
+    }],
+};
+
+pub const 正交直列排鍵法: 排鍵法 = 排鍵法 {
+    行首: [0.0; 4],
+    列錯位: 無錯位,
+    分體: None,
+    拇指區: &[拇指鍵描述 {
+        左: 2.5,
+        上: 4.0,
+        寬: 5.0,
+        轉角: 0.0,
+    }],
+};
+
+pub const 直列分體排鍵法: 排鍵法 = 排鍵法 {
+    行首: [0.0; 4],
+    列錯位: 無錯位,
+    分體: Some((5, 1.0)),
+    拇指區: &[
+        拇指鍵描述 {
+            左: 2.8,
+            上: 4.0,
+            寬: 2.2,
+            轉角: 0.0,
+        },
+        拇指鍵描述 {
+            左: 6.0,
+            上: 4.0,
+            寬: 2.2,
+            轉角: 0.0,
+        },
+    ],
+};
+
+pub const 縱向錯列分體排鍵法: 排鍵法 = 排鍵法 {
+    行首: [0.0; 4],
+    列錯位: 縱向錯列,
+    分體: Some((5, 1.5)),
+    // 僅保留內側雙拇指鍵
+    拇指區: &[
+        拇指鍵描述 {
+            左: 4.0,
+            上: 5.0,
+            寬: 1.0,
+            轉角: 30.0,
+        },
+        拇指鍵描述 {
+            左: 6.5,
+            上: 5.0,
+            寬: 1.0,
+            轉角: -30.0,
+        },
+    ],
+};
+
+/// 每手三個拇指鍵, 呈扇形排開
+pub const 拇指簇分體排鍵法: 排鍵法 = 排鍵法 {
+    行首: [0.0; 4],
+    列錯位: 縱向錯列,
+    分體: Some((5, 1.5)),
+    拇指區: &[
+        拇指鍵描述 {
+            左: 2.2,
+            上: 4.6,
+            寬: 1.0,
+            轉角: 8.0,
+        },
+        拇指鍵描述 {
+            左: 3.3,
+            上: 4.75,
+            寬: 1.0,
+            轉角: 16.0,
+        },
+        拇指鍵描述 {
+            左: 4.4,
+            上: 5.0,
+            寬: 1.0,
+            轉角: 24.0,
+        },
+        拇指鍵描述 {
+            左: 6.1,
+            上: 5.0,
+            寬: 1.0,
+            轉角: -24.0,
+        },
+        拇指鍵描述 {
+            左: 7.2,
+            上: 4.75,
+            寬: 1.0,
+            轉角: -16.0,
+        },
+        拇指鍵描述 {
+            左: 8.3,
+            上: 4.6,
+            寬: 1.0,
+            轉角: -8.0,
+        },
+    ],
+};
@@ -0,0 +1,330 @@
+//! 影子競速
+//!
+//! 每打完一題, 將本輪擊鍵存爲錄像; 同一題用時最短的一輪即個人最佳.
+//! 再練此題時, 字幕上另有一個影子光標, 按個人最佳 (或錄放面板中的錄像) 的步調前進;
+//! 打完報告與影子相差幾秒.
+
+use leptos::logging::log;
+use leptos::prelude::*;
+use std::slice;
+use std::time::Duration;
+use strum::Display;
+
+use crate::action::{動作, 動作給一參數};
+use crate::definition::輸入方案定義;
+use crate::gear::{
+    assignment::{作業內容, 作業機關輸出信號},
+    caption::影子表示,
+    history::本地存儲,
+    input::擊鍵事件,
+    replay::{擊鍵錄像, 重放到核心, 錄像作業, 錄放機關輸出信號},
+    theory::{方案選項, 輸入方案機關輸出信號},
+};
+use crate::headless::引擎核心;
+use crate::key_code::KeyCode;
+
+/// 每個方案、每道題只存一輪, 總數以此爲限, 先刪最舊的
+pub const 最佳紀錄上限: usize = 100;
+
+/// 影子光標刷新間隔, 毫秒
+const 影子步調間隔: u64 = 50;
+
+const 最佳紀錄存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/ghosts",
+};
+
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq)]
+pub enum 影子來源 {
+    #[default]
+    #[strum(to_string = "關")]
+    關,
+    #[strum(to_string = "最佳")]
+    個人最佳,
+    /// 錄放面板中錄製或導入的錄像
+    #[strum(to_string = "錄像")]
+    錄像,
+}
+
+impl 影子來源 {
+    pub fn 下一項(self) -> Self {
+        match self {
+            影子來源::關 => 影子來源::個人最佳,
+            影子來源::個人最佳 => 影子來源::錄像,
+            影子來源::錄像 => 影子來源::關,
+        }
+    }
+}
+
+/// 錄像中每次作業推進的時刻, 從首擊起算
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct 影子軌跡 {
+    /// (時刻, 推進後的作業進度), 按時刻先後排列
+    pub 衆節點: Vec<(f64, usize)>,
+    /// 打完全題的時刻, 未打完則爲空
+    pub 完成時刻: Option<f64>,
+}
+
+impl 影子軌跡 {
+    /// 在引擎核心中重放錄像, 記下作業進度的變化
+    pub fn 推算(
+        方案: 輸入方案定義<'static>,
+        作業內容: 作業內容<'static>,
+        錄像: &擊鍵錄像,
+    ) -> Self {
+        let mut 核心 = 引擎核心::new(方案);
+        核心.佈置作業(Some(作業內容));
+        核心.設置推進方式(錄像.推進方式);
+        核心.設置並擊判定(錄像.並擊判定);
+        let mut 軌跡 = 影子軌跡::default();
+        // 從首擊起算, 與本輪起跑對齊
+        let 首擊 = 錄像.首擊時刻();
+        for 事件 in &錄像.衆事件 {
+            let 時刻 = 事件.時刻 - 首擊;
+            let 原進度 = 核心.作業進度();
+            let 衆反饋 = 重放到核心(&mut 核心, slice::from_ref(事件));
+            // 分段的作業打完即迴轉到開頭, 以反饋爲準, 影子停在末尾
+            if 衆反饋.iter().any(|反饋| 反饋.打完一題) {
+                軌跡.衆節點.push((時刻, 核心.目標輸入碼序列().len()));
+                軌跡.完成時刻 = Some(時刻);
+                break;
+            }
+            if 核心.作業進度() != 原進度 {
+                軌跡.衆節點.push((時刻, 核心.作業進度()));
+            }
+        }
+        軌跡
+    }
+
+    /// 起跑後 `時刻` 毫秒時的作業進度
+    pub fn 進度(&self, 時刻: f64) -> usize {
+        let 已過節點數 = self
+            .衆節點
+            .partition_point(|&(節點時刻, _)| 節點時刻 <= 時刻);
+        match 已過節點數 {
+            0 => 0,
+            n => self.衆節點[n - 1].1,
+        }
+    }
+}
+
+fn 同一練習(甲: &擊鍵錄像, 乙: &擊鍵錄像) -> bool {
+    甲.方案 == 乙.方案 && 甲.方案文件 == 乙.方案文件 && 甲.作業 == 乙.作業
+}
+
+/// 新錄像比同一練習的舊紀錄快, 或此前未有紀錄, 則存入. 返回是否刷新了紀錄.
+pub fn 更新最佳紀錄(衆紀錄: &mut Vec<擊鍵錄像>, 新錄像: 擊鍵錄像) -> bool {
+    match 衆紀錄.iter_mut().find(|紀錄| 同一練習(紀錄, &新錄像)) {
+        Some(紀錄) if 紀錄.時長() <= 新錄像.時長() => return false,
+        Some(紀錄) => *紀錄 = 新錄像,
+        None => 衆紀錄.push(新錄像),
+    }
+    if 衆紀錄.len() > 最佳紀錄上限 {
+        衆紀錄.sort_by(|甲, 乙| 乙.日期.total_cmp(&甲.日期));
+        衆紀錄.truncate(最佳紀錄上限);
+    }
+    true
+}
+
+pub type 記錄本輪擊鍵動作 = impl 動作給一參數<擊鍵事件>;
+pub type 切換影子來源動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 影子機關輸出信號 {
+    pub 影子來源: ReadSignal<影子來源>,
+    pub 影子字幕: Signal<Option<影子表示>>,
+    /// 本輪用時減影子用時, 毫秒. 負數爲勝.
+    pub 影子時差: ReadSignal<Option<f64>>,
+    pub 記錄本輪擊鍵: 記錄本輪擊鍵動作,
+    pub 切換影子來源: 切換影子來源動作,
+}
+
+#[define_opaque(記錄本輪擊鍵動作, 切換影子來源動作)]
+pub fn 影子機關(
+    方案: &輸入方案機關輸出信號,
+    作業: &作業機關輸出信號,
+    錄放: &錄放機關輸出信號,
+    核心: RwSignal<引擎核心>,
+) -> 影子機關輸出信號 {
+    let 現行方案 = 方案.現行方案;
+    let 方案定義 = 方案.方案定義;
+    let 方案文件網址 = 方案.方案文件網址;
+    let 當前作業 = 作業.當前作業;
+    let 一題打完 = 作業.一題打完;
+    let 錄像 = 錄放.錄像;
+
+    let (影子來源, 設置影子來源) = signal(影子來源::default());
+    let (最佳紀錄, 設置最佳紀錄) = signal(最佳紀錄存儲.讀出::<Vec<擊鍵錄像>>().unwrap_or_default());
+    let 本輪擊鍵 = StoredValue::new(Vec::<擊鍵事件>::new());
+    let (起跑時刻, 設置起跑時刻) = signal(None::<f64>);
+    let (現在, 設置現在) = signal(0.0);
+    let (影子時差, 設置影子時差) = signal(None::<f64>);
+
+    let 作業進度 = Memo::new(move |_| 核心.read().作業進度());
+    let 現行作業內容 = Memo::new(move |_| 核心.read().作業內容().cloned());
+    let 現行錄像作業 = Memo::new(move |_| {
+        let 科目 = 現行方案();
+        (
+            科目.slug().to_owned(),
+            (科目 == 方案選項::自訂方案).then(方案文件網址).flatten(),
+            錄像作業::取自(&當前作業.read(), 方案定義.read().編碼法),
+        )
+    });
+
+    let 影子錄像 = Memo::new(move |_| {
+        let (方案代號, 方案文件, 作業) = 現行錄像作業();
+        let 是本題錄像 = |錄像: &&擊鍵錄像| {
+            錄像.方案 == 方案代號 && 錄像.方案文件 == 方案文件 && 錄像.作業 == 作業
+        };
+        match 影子來源() {
+            影子來源::關 => None,
+            影子來源::個人最佳 => 最佳紀錄.read().iter().find(是本題錄像).cloned(),
+            影子來源::錄像 => 錄像.read().as_ref().filter(是本題錄像).cloned(),
+        }
+    });
+    let 影子軌跡 = Memo::new(move |_| {
+        let 錄像 = 影子錄像()?;
+        let 作業內容 = 現行作業內容()?;
+        Some(影子軌跡::推算(
+            方案定義.get_untracked(),
+            作業內容,
+            &錄像,
+        ))
+    });
+
+    let 影子進度 = Memo::new(move |_| {
+        影子軌跡.with(|軌跡| {
+            軌跡.as_ref().map(|軌跡| match 起跑時刻() {
+                Some(起跑) => 軌跡.進度(現在() - 起跑),
+                None => 0,
+            })
+        })
+    });
+    let 影子字幕 = Signal::derive(move || {
+        let 進度 = 影子進度()?;
+        核心.read().當前段落()?.影子表示(進度)
+    });
+
+    let 收起本輪 = move || {
+        本輪擊鍵.update_value(Vec::clear);
+        設置起跑時刻(None);
+    };
+
+    // 重新開始、換題, 都從頭計. 分段的作業打完迴轉到開頭, 不在此列
+    let _ = Effect::watch(
+        作業進度,
+        move |&進度, _, _| {
+            if 進度 == 0 && !一題打完.get_untracked() {
+                收起本輪();
+                設置影子時差(None);
+            }
+        },
+        false,
+    );
+    let _ = Effect::watch(
+        當前作業,
+        move |_, _, _| {
+            收起本輪();
+            設置影子時差(None);
+        },
+        false,
+    );
+
+    let 步調 = set_interval_with_handle(
+        move || {
+            if 起跑時刻.get_untracked().is_some()
+                && !一題打完.get_untracked()
+                && 影子軌跡.with_untracked(Option::is_some)
+            {
+                設置現在(js_sys::Date::now());
+            }
+        },
+        Duration::from_millis(影子步調間隔),
+    )
+    .ok();
+    on_cleanup(move || {
+        if let Some(步調) = 步調 {
+            步調.clear();
+        }
+    });
+
+    let 記錄本輪擊鍵 = move |事件: 擊鍵事件| {
+        // 擊鍵先記錄後送入核心, 此時核心尚未因此擊開始新一輪
+        if 起跑時刻.get_untracked().is_none() || 一題打完.get_untracked() {
+            // 首次落下非功能鍵時起跑; 打完一題後再擊, 另起一輪
+            let 功能鍵 = matches!(
+                事件.鍵碼,
+                KeyCode::Escape
+                    | KeyCode::Tab
+                    | KeyCode::Enter
+                    | KeyCode::Backspace
+                    | KeyCode::Grave
+                    | KeyCode::No
+            );
+            if !事件.落鍵 || 功能鍵 || 作業進度.get_untracked() != 0 {
+                return;
+            }
+            本輪擊鍵.update_value(Vec::clear);
+            設置影子時差(None);
+            設置起跑時刻(Some(事件.時刻));
+            設置現在(事件.時刻);
+        }
+        本輪擊鍵.update_value(|衆事件| 衆事件.push(事件));
+    };
+
+    let _ = Effect::watch(
+        一題打完,
+        move |&完成, _, _| {
+            let Some(起跑) = 起跑時刻.get_untracked() else {
+                return;
+            };
+            if !完成 {
+                return;
+            }
+            let (方案代號, 方案文件, 作業) = 現行錄像作業.get_untracked();
+            let 本輪 = 擊鍵錄像 {
+                方案: 方案代號,
+                作業,
+                方案文件,
+                推進方式: 核心.read_untracked().推進方式(),
+                並擊判定: 核心.read_untracked().並擊判定(),
+                日期: 起跑,
+                衆事件: 本輪擊鍵
+                    .get_value()
+                    .into_iter()
+                    .map(|事件| 擊鍵事件 {
+                        時刻: 事件.時刻 - 起跑,
+                        ..事件
+                    })
+                    .collect(),
+            };
+            // 影子停在本輪打完之時
+            設置現在(起跑 + 本輪.時長());
+            let 影子用時 =
+                影子軌跡.with_untracked(|軌跡| 軌跡.as_ref().and_then(|軌跡| 軌跡.完成時刻));
+            設置影子時差(影子用時.map(|影子用時| 本輪.時長() - 影子用時));
+            // 自習沒有固定的題目, 不比
+            if 本輪.作業 == 錄像作業::default() {
+                return;
+            }
+            設置最佳紀錄.update(|衆紀錄| {
+                if 更新最佳紀錄(衆紀錄, 本輪) && 最佳紀錄存儲.存入(衆紀錄).is_err()
+                {
+                    log!("個人最佳未能存入瀏覽器");
+                }
+            });
+        },
+        false,
+    );
+
+    let 切換影子來源 = move || {
+        設置影子來源.update(|來源| *來源 = 來源.下一項());
+    };
+
+    影子機關輸出信號 {
+        影子來源,
+        影子字幕,
+        影子時差,
+        記錄本輪擊鍵,
+        切換影子來源,
+    }
+}
@@ -0,0 +1,228 @@
+//! 鍵位熱圖
+//!
+//! 將本次練習或歷次紀錄中各片段的成績分攤到其並擊所用的鍵上,
+//! 按擊錯率或平均用時爲鍵盤圖着色, 並列出最差的片段, 以便看出哪些手指、哪些並擊須加練.
+
+use leptos::prelude::*;
+use std::collections::BTreeMap;
+use strum::Display;
+
+use crate::action::動作;
+use crate::definition::輸入方案定義;
+use crate::gear::{
+    assignment::對照輸入碼,
+    history::{片段成績, 練習成績, 練習紀錄機關輸出信號},
+    layout::盤面選擇碼,
+    statistics::統計機關輸出信號,
+    theory::輸入方案機關輸出信號,
+};
+use crate::key_code::KeyCode;
+
+/// 最差片段表的行數
+const 最差片段數: usize = 10;
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum 熱圖指標 {
+    擊錯率,
+    #[strum(to_string = "用時")]
+    平均用時,
+}
+
+impl 熱圖指標 {
+    pub fn 取值(&self, 成績: &片段成績) -> f64 {
+        match self {
+            // 擊錯次數佔全部嘗試的比例
+            熱圖指標::擊錯率 => {
+                let 嘗試次數 = 成績.次數 + 成績.擊錯次數;
+                if 嘗試次數 == 0 {
+                    0.0
+                } else {
+                    成績.擊錯次數 as f64 / 嘗試次數 as f64
+                }
+            }
+            熱圖指標::平均用時 => {
+                if 成績.次數 == 0 {
+                    0.0
+                } else {
+                    成績.用時 / 成績.次數 as f64
+                }
+            }
+        }
+    }
+
+    pub fn 表示(&self, 值: f64) -> String {
+        match self {
+            熱圖指標::擊錯率 => format!("{:.0}%", 值 * 100.0),
+            熱圖指標::平均用時 => format!("{:.2}秒", 值 / 1000.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum 熱圖來源 {
+    #[strum(to_string = "本次")]
+    本次練習,
+    #[strum(to_string = "歷次")]
+    歷次紀錄,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct 熱圖選項 {
+    pub 指標: 熱圖指標,
+    pub 來源: 熱圖來源,
+}
+
+/// 點擊切換熱圖時依次經過的選項, 末了關閉熱圖
+const 熱圖選項輪替: [熱圖選項; 4] = [
+    熱圖選項 {
+        指標: 熱圖指標::擊錯率,
+        來源: 熱圖來源::本次練習,
+    },
+    熱圖選項 {
+        指標: 熱圖指標::平均用時,
+        來源: 熱圖來源::本次練習,
+    },
+    熱圖選項 {
+        指標: 熱圖指標::擊錯率,
+        來源: 熱圖來源::歷次紀錄,
+    },
+    熱圖選項 {
+        指標: 熱圖指標::平均用時,
+        來源: 熱圖來源::歷次紀錄,
+    },
+];
+
+/// 片段的輸入碼所用的鍵. 輸入碼或爲拼式, 或爲字根碼.
+pub fn 片段鍵位(方案: &輸入方案定義, 輸入碼: &str) -> Vec<KeyCode> {
+    let 對照碼 = 對照輸入碼 {
+        字根碼原文: None,
+        轉寫碼原文: Some(輸入碼.to_owned()),
+    };
+    let 字根碼 = 對照碼
+        .反查字根碼(&方案.轉寫法)
+        .unwrap_or_else(|| 輸入碼.to_owned());
+    方案
+        .讀出鍵位(&字根碼, &盤面選擇碼(0))
+        .0
+        .into_iter()
+        .collect()
+}
+
+/// 將各片段的成績計入其所用的每個鍵. 所得成績的輸入碼爲該鍵的字根碼.
+pub fn 按鍵位彙總(
+    方案: &輸入方案定義,
+    衆片段: &[片段成績],
+) -> BTreeMap<KeyCode, 片段成績> {
+    let mut 鍵位表 = BTreeMap::<KeyCode, 片段成績>::new();
+    for 片段 in 衆片段 {
+        for 鍵碼 in 片段鍵位(方案, &片段.輸入碼) {
+            let 累計 = 鍵位表.entry(鍵碼).or_insert_with(|| 片段成績 {
+                輸入碼: 方案.寫成字根碼(&鍵碼),
+                ..Default::default()
+            });
+            累計.次數 += 片段.次數;
+            累計.擊錯次數 += 片段.擊錯次數;
+            累計.用時 += 片段.用時;
+        }
+    }
+    鍵位表
+}
+
+/// 各鍵的指標值除以最大值, 得 0 至 1 的熱度
+pub fn 熱度表(
+    鍵位表: &BTreeMap<KeyCode, 片段成績>, 指標: 熱圖指標
+) -> BTreeMap<KeyCode, f64> {
+    let 最大值 = 鍵位表
+        .values()
+        .map(|成績| 指標.取值(成績))
+        .fold(0.0, f64::max);
+    鍵位表
+        .iter()
+        .map(|(&鍵碼, 成績)| {
+            let 值 = 指標.取值(成績);
+            let 熱度 = if 最大值 > 0.0 {
+                值 / 最大值
+            } else {
+                0.0
+            };
+            (鍵碼, 熱度)
+        })
+        .collect()
+}
+
+/// 按指標由差到好排列, 不列從未擊錯的片段
+pub fn 最差片段(
+    衆片段: &[片段成績], 指標: 熱圖指標, 數目: usize
+) -> Vec<片段成績> {
+    let mut 衆片段 = 衆片段
+        .iter()
+        .filter(|片段| 指標 != 熱圖指標::擊錯率 || 片段.擊錯次數 > 0)
+        .cloned()
+        .collect::<Vec<_>>();
+    衆片段.sort_by(|甲, 乙| 指標.取值(乙).total_cmp(&指標.取值(甲)));
+    衆片段.truncate(數目);
+    衆片段
+}
+
+pub type 切換熱圖動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 熱圖機關輸出信號 {
+    /// 爲空則不顯示熱圖
+    pub 熱圖選項: ReadSignal<Option<熱圖選項>>,
+    pub 切換熱圖: 切換熱圖動作,
+    pub 鍵位熱度: Memo<BTreeMap<KeyCode, f64>>,
+    pub 最差片段: Memo<Vec<片段成績>>,
+}
+
+#[define_opaque(切換熱圖動作)]
+pub fn 熱圖機關(
+    方案: &輸入方案機關輸出信號,
+    統計: &統計機關輸出信號,
+    紀錄: &練習紀錄機關輸出信號,
+) -> 熱圖機關輸出信號 {
+    let 方案定義 = 方案.方案定義;
+    let 練習統計 = 統計.練習統計;
+    let 方案紀錄 = 紀錄.方案紀錄;
+
+    let (熱圖選項, 設置熱圖選項) = signal(None::<熱圖選項>);
+
+    let 切換熱圖 = move || {
+        let 下一選項 = match 熱圖選項.get_untracked() {
+            None => 熱圖選項輪替.first().copied(),
+            Some(現行) => 熱圖選項輪替
+                .iter()
+                .skip_while(|&&選項| 選項 != 現行)
+                .nth(1)
+                .copied(),
+        };
+        設置熱圖選項(下一選項);
+    };
+
+    let 衆片段成績 = Memo::new(move |_| match 熱圖選項().map(|選項| 選項.來源) {
+        None => vec![],
+        Some(熱圖來源::本次練習) => 練習成績::合併片段(練習統計.read().衆片段()),
+        Some(熱圖來源::歷次紀錄) => {
+            片段成績::合併(方案紀錄.read().iter().flat_map(|成績| &成績.衆片段))
+        }
+    });
+
+    let 鍵位熱度 = Memo::new(move |_| {
+        熱圖選項().map_or_else(BTreeMap::new, |選項| {
+            熱度表(&按鍵位彙總(&方案定義.read(), &衆片段成績.read()), 選項.指標)
+        })
+    });
+
+    let 最差片段 = Memo::new(move |_| {
+        熱圖選項().map_or_else(Vec::new, |選項| {
+            最差片段(&衆片段成績.read(), 選項.指標, 最差片段數)
+        })
+    });
+
+    熱圖機關輸出信號 {
+        熱圖選項,
+        切換熱圖,
+        鍵位熱度,
+        最差片段,
+    }
+}
@@ -0,0 +1,274 @@
+//! 練習紀錄
+//!
+//! 每完成一道作業, 將成績連同方案、練習題、配列、日期及各片段的擊錯次數存入瀏覽器,
+//! 以便跨日查看進步. 存取經由 [`成績存儲`], 瀏覽器中用 localStorage, 別處可換作內存.
+
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::web_sys;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::action::{動作, 成功失敗, 未有};
+use crate::gear::{
+    assignment::{作業, 作業機關輸出信號},
+    layout::{佈局機關輸出信號, 配列},
+    statistics::{片段紀錄, 統計摘要, 統計機關輸出信號, 練習統計},
+    theory::輸入方案機關輸出信號,
+};
+
+/// 超出此數則捨棄最早的紀錄
+const 保留紀錄數: usize = 1000;
+
+/// 同一輸入碼在一次作業中的成績
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct 片段成績 {
+    pub 輸入碼: String,
+    pub 次數: usize,
+    pub 擊錯次數: usize,
+    /// 各次用時之和, 毫秒
+    pub 用時: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct 練習成績 {
+    /// 方案代號, 同網址中的
+    pub 方案: String,
+    /// 自訂作業爲空
+    pub 題號: Option<usize>,
+    pub 標題: String,
+    /// 配列代號, 同網址中的
+    pub 配列: String,
+    /// 完成時刻, 自 1970 年起的毫秒數
+    pub 日期: f64,
+    pub 摘要: 統計摘要,
+    pub 衆片段: Vec<片段成績>,
+}
+
+impl 片段成績 {
+    /// 按輸入碼合併, 依首次出現的次序排列
+    pub fn 合併<'a>(衆成績: impl IntoIterator<Item = &'a 片段成績>) -> Vec<片段成績> {
+        let mut 序號表 = BTreeMap::new();
+        let mut 合併所得: Vec<片段成績> = vec![];
+        for 成績 in 衆成績 {
+            let 序號 = *序號表.entry(成績.輸入碼.as_str()).or_insert_with(|| {
+                合併所得.push(片段成績 {
+                    輸入碼: 成績.輸入碼.clone(),
+                    ..Default::default()
+                });
+                合併所得.len() - 1
+            });
+            let 累計 = &mut 合併所得[序號];
+            累計.次數 += 成績.次數;
+            累計.擊錯次數 += 成績.擊錯次數;
+            累計.用時 += 成績.用時;
+        }
+        合併所得
+    }
+}
+
+impl 練習成績 {
+    /// 打完一題時記下成績. 未擊中任何片段則不記
+    pub fn 記下(
+        作業: &作業, 配列: 配列, 日期: f64, 統計: &練習統計
+    ) -> Option<Self> {
+        let 摘要 = 統計.摘要();
+        if 摘要.片段數 == 0 {
+            return None;
+        }
+        let 標題 = 作業
+            .題號
+            .and_then(|題號| 作業.科目.配套練習題()?.get(題號))
+            .map(|題| 題.標題)
+            .or(作業.生成題.as_ref().map(|題| 題.標題))
+            .unwrap_or("自訂");
+        Some(練習成績 {
+            方案: 作業.科目.slug().to_owned(),
+            題號: 作業.題號,
+            標題: 標題.to_owned(),
+            配列: 配列.slug().to_owned(),
+            日期,
+            摘要,
+            衆片段: Self::合併片段(統計.衆片段()),
+        })
+    }
+
+    /// 按輸入碼合併各片段紀錄
+    pub fn 合併片段(衆片段: &[片段紀錄]) -> Vec<片段成績> {
+        let 衆成績 = 衆片段
+            .iter()
+            .map(|片段| 片段成績 {
+                輸入碼: 片段.輸入碼.clone(),
+                次數: 1,
+                擊錯次數: 片段.擊錯次數,
+                用時: 片段.用時,
+            })
+            .collect::<Vec<_>>();
+        片段成績::合併(&衆成績)
+    }
+
+    /// 擊錯次數最多的片段, 不含未曾擊錯的
+    pub fn 易錯片段(&self, 數目: usize) -> Vec<&片段成績> {
+        let mut 衆片段 = self
+            .衆片段
+            .iter()
+            .filter(|片段| 片段.擊錯次數 > 0)
+            .collect::<Vec<_>>();
+        衆片段.sort_by_key(|片段| std::cmp::Reverse(片段.擊錯次數));
+        衆片段.truncate(數目);
+        衆片段
+    }
+}
+
+pub trait 成績存儲 {
+    fn 讀取(&self) -> Vec<練習成績>;
+    fn 寫入(&self, 衆成績: &[練習成績]) -> 成功失敗;
+
+    /// 追加一條紀錄, 返回存儲後的全部紀錄
+    fn 追加(&self, 成績: 練習成績) -> Result<Vec<練習成績>, 未有> {
+        let mut 衆成績 = self.讀取();
+        衆成績.push(成績);
+        if 衆成績.len() > 保留紀錄數 {
+            衆成績.drain(..衆成績.len() - 保留紀錄數);
+        }
+        self.寫入(&衆成績)?;
+        Ok(衆成績)
+    }
+}
+
+/// 瀏覽器 localStorage 中的一項, 值以 JSON 存儲
+#[derive(Clone, Copy)]
+pub struct 本地存儲 {
+    pub 鍵名: &'static str,
+}
+
+impl 本地存儲 {
+    fn storage(&self) -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+
+    /// 讀出以 JSON 存儲的值, 無值或無法解析則爲空
+    pub fn 讀出<T: DeserializeOwned>(&self) -> Option<T> {
+        let 文本 = self.storage()?.get_item(self.鍵名).ok().flatten()?;
+        serde_json::from_str(&文本)
+            .inspect_err(|錯誤| log!("{} 無法解析: {錯誤}", self.鍵名))
+            .ok()
+    }
+
+    pub fn 存入<T: Serialize + ?Sized>(&self, 值: &T) -> 成功失敗 {
+        let 文本 = serde_json::to_string(值).map_err(|_| 未有())?;
+        self.storage()
+            .ok_or(未有())?
+            .set_item(self.鍵名, &文本)
+            .map_err(|_| 未有())
+    }
+}
+
+impl 成績存儲 for 本地存儲 {
+    fn 讀取(&self) -> Vec<練習成績> {
+        self.讀出().unwrap_or_default()
+    }
+
+    fn 寫入(&self, 衆成績: &[練習成績]) -> 成功失敗 {
+        self.存入(衆成績)
+    }
+}
+
+/// 不落盤的存儲, 關閉頁面即丢失
+#[derive(Default)]
+pub struct 內存存儲 {
+    衆成績: RefCell<Vec<練習成績>>,
+}
+
+impl 成績存儲 for 內存存儲 {
+    fn 讀取(&self) -> Vec<練習成績> {
+        self.衆成績.borrow().clone()
+    }
+
+    fn 寫入(&self, 衆成績: &[練習成績]) -> 成功失敗 {
+        *self.衆成績.borrow_mut() = 衆成績.to_vec();
+        Ok(())
+    }
+}
+
+const 練習紀錄存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/history",
+};
+
+pub type 清除紀錄動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 練習紀錄機關輸出信號 {
+    /// 現行方案的紀錄, 按完成先後排列
+    pub 方案紀錄: Memo<Vec<練習成績>>,
+    pub 清除紀錄: 清除紀錄動作,
+}
+
+#[define_opaque(清除紀錄動作)]
+pub fn 練習紀錄機關(
+    方案: &輸入方案機關輸出信號,
+    佈局: &佈局機關輸出信號,
+    作業: &作業機關輸出信號,
+    統計: &統計機關輸出信號,
+) -> 練習紀錄機關輸出信號 {
+    let 現行方案 = 方案.現行方案;
+    let 實際配列 = 佈局.實際配列;
+    let 當前作業 = 作業.當前作業;
+    let 一題打完 = 作業.一題打完;
+    let 練習統計 = 統計.練習統計;
+
+    let (全部紀錄, 更新紀錄) = signal(練習紀錄存儲.讀取());
+
+    let _ = Effect::watch(
+        一題打完,
+        move |&打完, _, _| {
+            if !打完 {
+                return;
+            }
+            let Some(成績) = 練習成績::記下(
+                &當前作業.read_untracked(),
+                實際配列.get_untracked(),
+                js_sys::Date::now(),
+                &練習統計.read_untracked(),
+            ) else {
+                return;
+            };
+            match 練習紀錄存儲.追加(成績.clone()) {
+                Ok(衆成績) => 更新紀錄(衆成績),
+                Err(_) => {
+                    log!("練習紀錄未能存入瀏覽器");
+                    更新紀錄.write().push(成績);
+                }
+            }
+        },
+        false,
+    );
+
+    let 方案紀錄 = Memo::new(move |_| {
+        let 方案代號 = 現行方案.read().slug().to_owned();
+        全部紀錄
+            .read()
+            .iter()
+            .filter(|成績| 成績.方案 == 方案代號)
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    let 清除紀錄 = move || {
+        let 方案代號 = 現行方案.read_untracked().slug().to_owned();
+        let 餘下紀錄 = 全部紀錄
+            .get_untracked()
+            .into_iter()
+            .filter(|成績| 成績.方案 != 方案代號)
+            .collect::<Vec<_>>();
+        if 練習紀錄存儲.寫入(&餘下紀錄).is_err() {
+            log!("練習紀錄未能存入瀏覽器");
+        }
+        更新紀錄(餘下紀錄);
+    };
+
+    練習紀錄機關輸出信號 {
+        方案紀錄, 清除紀錄
+    }
+}
@@ -0,0 +1,183 @@
+//! 系統佈局
+//!
+//! 網頁按鍵事件的 `code` 只標明按鍵的物理位置, 且以美式鍵盤命名.
+//! 操作系統設爲 Dvorak、Colemak 等佈局, 或者用 AZERTY、JIS 鍵盤時, 同一位置輸入的字符另有不同.
+//! 本節按選定的系統佈局, 將物理位置換成方案所認的鍵碼 (即美式鍵盤上輸入同一字符的鍵),
+//! 鍵盤圖上也按物理位置重排刻印.
+
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::action::{動作, 動作給一參數};
+use crate::gear::history::本地存儲;
+use crate::key_code::{字符鍵值轉換, 鍵值字符轉換, 鍵碼網頁鍵值轉換, KeyCode};
+
+const 系統佈局存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/host-layout",
+};
+
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, PartialEq, Eq, Serialize, Deserialize)]
+pub enum 系統佈局 {
+    #[default]
+    #[strum(to_string = "QWERTY")]
+    美式,
+    #[strum(to_string = "Dvorak")]
+    德沃夏克,
+    #[strum(to_string = "Colemak")]
+    科爾馬克,
+    #[strum(to_string = "AZERTY")]
+    法式,
+    #[strum(to_string = "JIS")]
+    日式,
+}
+
+macro_rules! 換鍵表 {
+    [ $( $物理位置:ident => $鍵:ident ),* $(,)? ] => {
+        &[ $( (KeyCode::$物理位置, KeyCode::$鍵), )* ]
+    };
+}
+
+/// (物理位置, 該位置輸入的字符在美式鍵盤上的鍵), 未列出的位置不變
+const 德沃夏克換鍵表: &[(KeyCode, KeyCode)] = 換鍵表![
+    Minus => LeftBracket, Equal => RightBracket,
+    Q => Quote, W => Comma, E => Dot, R => P, T => Y, Y => F, U => G, I => C, O => R, P => L,
+    LeftBracket => Slash, RightBracket => Equal,
+    S => O, D => E, F => U, G => I, H => D, J => H, K => T, L => N, Semicolon => S, Quote => Minus,
+    Z => Semicolon, X => Q, C => J, V => K, B => X, N => B, Comma => W, Dot => V, Slash => Z,
+];
+
+const 科爾馬克換鍵表: &[(KeyCode, KeyCode)] = 換鍵表![
+    E => F, R => P, T => G, Y => J, U => L, I => U, O => Y, P => Semicolon,
+    S => R, D => S, F => T, G => D, J => N, K => E, L => I, Semicolon => O,
+    N => K,
+];
+
+/// 數字須按上檔輸入, 仍作數字鍵; 標點只換與字母互換位置者
+const 法式換鍵表: &[(KeyCode, KeyCode)] = 換鍵表![
+    Q => A, W => Z,
+    A => Q, Semicolon => M,
+    Z => W, M => Comma, Comma => Semicolon,
+];
+
+/// 字母與美式相同. `@` 鍵在美式鍵盤上無對應, 不作字符鍵.
+const 日式換鍵表: &[(KeyCode, KeyCode)] = 換鍵表![
+    LeftBracket => No, RightBracket => LeftBracket, Backslash => RightBracket,
+];
+
+impl 系統佈局 {
+    fn 換鍵表(&self) -> &'static [(KeyCode, KeyCode)] {
+        match self {
+            系統佈局::美式 => &[],
+            系統佈局::德沃夏克 => 德沃夏克換鍵表,
+            系統佈局::科爾馬克 => 科爾馬克換鍵表,
+            系統佈局::法式 => 法式換鍵表,
+            系統佈局::日式 => 日式換鍵表,
+        }
+    }
+
+    /// 物理位置上的按鍵, 在本佈局下相當於美式鍵盤的哪個鍵
+    pub fn 轉換(&self, 物理位置: KeyCode) -> KeyCode {
+        self.換鍵表()
+            .iter()
+            .find(|(位置, _)| *位置 == 物理位置)
+            .map_or(物理位置, |&(_, 鍵碼)| 鍵碼)
+    }
+
+    pub fn 下一項(self) -> Self {
+        系統佈局::iter()
+            .cycle()
+            .skip_while(|&佈局| 佈局 != self)
+            .nth(1)
+            .unwrap_or_default()
+    }
+}
+
+/// 按各物理位置實際輸入的字符, 認出系統佈局. `查字符` 由網頁鍵值查得該位置的字符.
+///
+/// 每種佈局只比對字母鍵及其換鍵表所列的位置, 他處各佈局字符不一, 不作依據.
+/// 美式無換鍵表, 字母相同者皆會符合, 故置於最後比對.
+pub fn 辨認系統佈局(查字符: impl Fn(&str) -> Option<String>) -> Option<系統佈局> {
+    let mut 衆候選 = 系統佈局::iter().collect::<Vec<_>>();
+    衆候選.sort_by_key(|佈局| 佈局.換鍵表().is_empty());
+    衆候選.into_iter().find(|佈局| {
+        ('a'..='z')
+            .map(字符鍵值轉換)
+            .chain(佈局.換鍵表().iter().map(|&(位置, _)| 位置))
+            .all(|位置| {
+                let 應得字符 = 鍵值字符轉換(佈局.轉換(位置));
+                let 實得字符 = 鍵碼網頁鍵值轉換(位置).and_then(&查字符);
+                match (應得字符, 實得字符) {
+                    (Some(應得), Some(實得)) => 實得.chars().eq([應得]),
+                    // 任一方無從比對, 不計
+                    _ => true,
+                }
+            })
+    })
+}
+
+/// 經 Keyboard Map API 讀取系統佈局, 瀏覽器不支持則爲空
+async fn 偵測系統佈局() -> Option<系統佈局> {
+    use js_sys::{Function, Promise, Reflect};
+
+    let 鍵盤 = Reflect::get(&window().navigator(), &"keyboard".into())
+        .ok()
+        .filter(|鍵盤| !鍵盤.is_undefined())?;
+    let 讀取佈局表 = Reflect::get(&鍵盤, &"getLayoutMap".into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    let 承諾 = 讀取佈局表.call0(&鍵盤).ok()?.dyn_into::<Promise>().ok()?;
+    let 佈局表 = wasm_bindgen_futures::JsFuture::from(承諾).await.ok()?;
+    let 查表 = Reflect::get(&佈局表, &"get".into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    辨認系統佈局(|鍵值| 查表.call1(&佈局表, &鍵值.into()).ok()?.as_string())
+}
+
+pub type 選用系統佈局動作 = impl 動作給一參數<系統佈局>;
+pub type 切換系統佈局動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 系統佈局機關輸出信號 {
+    pub 系統佈局: ReadSignal<系統佈局>,
+    pub 選用系統佈局: 選用系統佈局動作,
+    pub 切換系統佈局: 切換系統佈局動作,
+}
+
+#[define_opaque(選用系統佈局動作, 切換系統佈局動作)]
+pub fn 系統佈局機關() -> 系統佈局機關輸出信號 {
+    let 已存佈局 = 系統佈局存儲.讀出::<系統佈局>();
+    let (系統佈局, 設置系統佈局) = signal(已存佈局.unwrap_or_default());
+
+    let 選用系統佈局 = move |佈局: 系統佈局| {
+        設置系統佈局(佈局);
+        if 系統佈局存儲.存入(&佈局).is_err() {
+            log!("系統佈局未能存入瀏覽器");
+        }
+    };
+
+    // 未曾選過, 則按瀏覽器報告的佈局設置
+    if 已存佈局.is_none() {
+        let 偵測結果 = LocalResource::new(偵測系統佈局);
+        Effect::new(move || {
+            if let Some(佈局) = 偵測結果.get().flatten() {
+                log!("偵測到系統佈局: {}", 佈局);
+                設置系統佈局(佈局);
+            }
+        });
+    }
+
+    let 切換系統佈局 = move || {
+        選用系統佈局(系統佈局.get_untracked().下一項());
+    };
+
+    系統佈局機關輸出信號 {
+        系統佈局,
+        選用系統佈局,
+        切換系統佈局,
+    }
+}
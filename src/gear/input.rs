@@ -1,7 +1,10 @@
 use leptos::{ev, leptos_dom::helpers::window_event_listener, logging::log, prelude::*};
 use leptos_use::use_window_focus;
+use serde::{Deserialize, Serialize};
 
-use crate::action::{動作, 動作給一參數, 動作給一參數得一結果};
+use crate::action::{動作, 動作給一參數得一結果};
+use crate::gear::host_layout::系統佈局;
+use crate::gear::layout::替代鍵位表;
 use crate::key_code::{網頁鍵值轉換, KeyCode};
 
 pub fn 焦點事件處理機關(重置並擊狀態: impl 動作) {
@@ -27,33 +30,73 @@ pub struct 觸鍵消息 {
     pub 檔位: 檔位,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum 擊鍵來源 {
+    實體鍵盤,
+    /// 點擊、觸摸畫面上的鍵盤圖
+    屏幕鍵盤,
+}
+
+/// 一次落鍵或抬鍵. 可錄製下來, 再原樣送回引擎.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct 擊鍵事件 {
+    /// 毫秒. 錄像中爲距錄製開始的時長.
+    pub 時刻: f64,
+    pub 鍵碼: KeyCode,
+    pub 落鍵: bool,
+    pub 來源: 擊鍵來源,
+    #[serde(default)]
+    pub 上檔: bool,
+}
+
+impl 擊鍵事件 {
+    pub fn 觸鍵消息(&self) -> 觸鍵消息 {
+        觸鍵消息 {
+            鍵碼: self.鍵碼,
+            檔位: 檔位 {
+                shift: self.上檔,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// 按鍵經 `系統佈局` 換成方案所認的鍵碼, 再按 `替代鍵位` 換成配列所缺的鍵.
+/// `處理擊鍵` 返回真值, 則攔下瀏覽器的默認行爲.
 pub fn 輸入事件處理機關(
-    處理功能鍵: impl 動作給一參數得一結果<觸鍵消息, bool>,
-    既然落鍵: impl 動作給一參數<KeyCode>,
-    既然抬鍵: impl 動作給一參數<KeyCode>,
+    系統佈局: ReadSignal<系統佈局>,
+    替代鍵位: Signal<替代鍵位表>,
+    處理擊鍵: impl 動作給一參數得一結果<擊鍵事件, bool>,
 ) {
+    let 轉換鍵碼 = move |鍵值: &str| {
+        let 鍵碼 = 系統佈局.get_untracked().轉換(網頁鍵值轉換(鍵值));
+        替代鍵位.read_untracked().轉換(鍵碼)
+    };
     let keydown_handle = window_event_listener(ev::keydown, move |ev| {
         log!("落鍵 key = {}, code = {}", &ev.key(), ev.code());
-        let 鍵碼 = 網頁鍵值轉換(&ev.code());
-        let 檔位 = 檔位 {
-            shift: ev.shift_key(),
-            ctrl: ev.ctrl_key(),
-            alt: ev.alt_key(),
-            meta: ev.meta_key(),
+        let 事件 = 擊鍵事件 {
+            時刻: js_sys::Date::now(),
+            鍵碼: 轉換鍵碼(&ev.code()),
+            落鍵: true,
+            來源: 擊鍵來源::實體鍵盤,
+            上檔: ev.shift_key(),
         };
-        if 處理功能鍵(觸鍵消息 { 鍵碼, 檔位 }) {
+        if 處理擊鍵(事件) {
             ev.prevent_default();
         }
-        if 鍵碼 != KeyCode::No {
-            既然落鍵(鍵碼);
-        }
     });
 
     let keyup_handle = window_event_listener(ev::keyup, move |ev| {
         log!("抬鍵 key = {}, code = {}", &ev.key(), &ev.code());
-        let 鍵碼 = 網頁鍵值轉換(&ev.code());
+        let 鍵碼 = 轉換鍵碼(&ev.code());
         if 鍵碼 != KeyCode::No {
-            既然抬鍵(鍵碼);
+            處理擊鍵(擊鍵事件 {
+                時刻: js_sys::Date::now(),
+                鍵碼,
+                落鍵: false,
+                來源: 擊鍵來源::實體鍵盤,
+                上檔: ev.shift_key(),
+            });
         }
     });
 
@@ -5,12 +5,24 @@
 //! - 盤面 :: 也稱層, 定義在盤面選擇碼指定的狀態下各鍵碼映射到哪些字符
 //! - 鍵面刻印 :: 鍵面顯示的文字
 
+use gloo_net::http::Request;
+use leptos::logging::log;
 use leptos::prelude::*;
+use serde::Deserialize;
 use strum::{Display, EnumIter};
 
+use crate::action::{動作, 動作給一參數得一結果, 成功失敗, 未有};
 use crate::app_state::選用配列動作;
+use crate::definition::輸入方案定義;
+use crate::gear::geometry::{
+    主鍵盤區排鍵法, 字母鍵盤排鍵法, 拇指簇分體排鍵法, 正交直列排鍵法, 直列分體排鍵法,
+    縱向錯列分體排鍵法, 配列幾何,
+};
+use crate::gear::history::本地存儲;
 use crate::gear::theory::輸入方案機關輸出信號;
+use crate::headless::引擎核心;
 use crate::key_code::KeyCode;
+use crate::kle::載入配列文件;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct 盤面選擇碼(pub u64);
@@ -31,26 +43,41 @@ impl 盤面選擇碼 {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct 矩陣座標(pub usize, pub usize);
 
+/// 主鍵盤區以外的 ISO、JIS 鍵位. 盤面按主鍵盤區的位置排列, 這幾個鍵的刻印記在盤面各行末尾.
+/// (鍵碼, 刻印在盤面上的位置, ISO/IEC 9995-1 鍵位)
+pub const 擴展鍵位: &[(KeyCode, 矩陣座標, &str)] = &[
+    // JIS 鍵盤的 ¥ 鍵. 主鍵盤區已將 ` 置於 E13, 故記在 E 行第 14 位
+    (KeyCode::International3, 矩陣座標(0, 13), "E13"),
+    // JIS 鍵盤的 ろ 鍵
+    (KeyCode::International1, 矩陣座標(3, 10), "B11"),
+    // ISO 鍵盤左 Shift 右側的鍵
+    (KeyCode::NonusBackslash, 矩陣座標(3, 11), "B00"),
+];
+
 /// 矩陣的行列座標按照 ISO/IEC 9995-1 的鍵位標註法顯示.
 /// 空格至數字行從下到上編號爲 A 到 E.
 /// 本品只做字母數字區, 因此 A 行列號從 03 開始, 其他各行從 01 開始.
+/// 擴展鍵位按其本來的鍵位顯示.
 impl std::fmt::Display for 矩陣座標 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        if let Some((_, _, 鍵位)) = 擴展鍵位.iter().find(|(_, 座標, _)| 座標 == self) {
+            return write!(f, "{鍵位}");
+        }
         match self.0 {
             // (0, 0) -> E01
             i @ 0..=3 => {
                 let 行號 = char::from_u32('E' as u32 - i as u32).ok_or(std::fmt::Error {})?;
                 let 列號 = self.1 + 1;
-                write!(f, "{行號}{列號}")
+                write!(f, "{行號}{列號:02}")
             }
             // (4, 0) -> A03
             4 => {
                 let 行號 = 'A';
                 let 列號 = self.1 + 3;
-                write!(f, "{行號}{列號}")
+                write!(f, "{行號}{列號:02}")
             }
             _ => Err(std::fmt::Error {}),
         }
@@ -141,58 +168,88 @@ pub struct 鍵面映射 {
 pub type 鍵盤矩陣<'a> = &'a [&'a [KeyCode]];
 pub type 盤面定義<'a> = &'a [&'a [鍵面刻印]];
 
-#[derive(Clone, Copy, Default, Display, EnumIter, PartialEq)]
+#[derive(Clone, Copy, Default, Deserialize, Display, EnumIter, PartialEq)]
 pub enum 配列 {
     #[default]
     主鍵盤區,
+    歐規主鍵盤區,
+    日規主鍵盤區,
     字母鍵盤,
     正交直列,
     直列分體,
     正交直列帶數字行,
     直列分體帶數字行,
     縱向錯列分體,
+    拇指簇分體,
+    /// 鍵位取自導入的配列文件. 矩陣同主鍵盤區, 用於對照刻印.
+    自訂,
 }
 
 impl 配列 {
-    pub fn 橫向交錯(&self) -> bool {
-        matches!(self, 配列::主鍵盤區 | 配列::字母鍵盤)
-    }
-    pub fn 縱向交錯(&self) -> bool {
-        matches!(self, 配列::縱向錯列分體)
-    }
-    pub fn 直列(&self) -> bool {
-        matches!(
-            self,
-            配列::正交直列
-                | 配列::直列分體
-                | 配列::正交直列帶數字行
-                | 配列::直列分體帶數字行
-        )
-    }
-    pub fn 分體(&self) -> bool {
-        matches!(
-            self,
-            配列::直列分體 | 配列::直列分體帶數字行 | 配列::縱向錯列分體
-        )
-    }
-    pub fn 規格(&self) -> usize {
-        match self {
-            配列::主鍵盤區 => 60,
-            配列::字母鍵盤 => 30,
-            配列::正交直列 | 配列::直列分體 | 配列::縱向錯列分體 => 30,
-            配列::正交直列帶數字行 | 配列::直列分體帶數字行 => 60,
-        }
+    pub fn 幾何(&self) -> 配列幾何 {
+        let 排鍵法 = match self {
+            // 未導入配列文件時, 自訂配列按主鍵盤區畫
+            配列::主鍵盤區 | 配列::歐規主鍵盤區 | 配列::日規主鍵盤區 | 配列::自訂 => {
+                主鍵盤區排鍵法
+            }
+            配列::字母鍵盤 => 字母鍵盤排鍵法,
+            配列::正交直列 | 配列::正交直列帶數字行 => 正交直列排鍵法,
+            配列::直列分體 | 配列::直列分體帶數字行 => 直列分體排鍵法,
+            配列::縱向錯列分體 => 縱向錯列分體排鍵法,
+            配列::拇指簇分體 => 拇指簇分體排鍵法,
+        };
+        排鍵法.排列(*self)
     }
     pub fn 矩陣(&self) -> 鍵盤矩陣<'static> {
         match self {
-            配列::主鍵盤區 => 主鍵盤區矩陣,
+            配列::主鍵盤區 | 配列::自訂 => 主鍵盤區矩陣,
+            配列::歐規主鍵盤區 => 歐規主鍵盤區矩陣,
+            配列::日規主鍵盤區 => 日規主鍵盤區矩陣,
             配列::字母鍵盤 => 字母鍵盤矩陣,
             配列::正交直列 => 正交直列矩陣,
             配列::直列分體 | 配列::縱向錯列分體 => 直列分體矩陣,
             配列::正交直列帶數字行 => 正交直列帶數字行矩陣,
             配列::直列分體帶數字行 => 直列分體帶數字行矩陣,
+            配列::拇指簇分體 => 拇指簇分體矩陣,
+        }
+    }
+    /// 矩陣中該行在 01 列 (A 行爲 03 列) 之前有幾個鍵, 如 E00、B00
+    pub fn 列偏移(&self, 行: usize) -> usize {
+        match (self, 行) {
+            (配列::歐規主鍵盤區, 3) | (配列::日規主鍵盤區, 0) | (配列::拇指簇分體, 4) => {
+                1
+            }
+            _ => 0,
         }
     }
+    /// 矩陣中 (行, 列) 處的鍵, 其刻印在盤面上的位置. 功能鍵不取盤面上的刻印.
+    pub fn 刻印座標(&self, 行: usize, 列: usize) -> Option<矩陣座標> {
+        let 鍵碼 = *self.矩陣().get(行)?.get(列)?;
+        if 功能鍵::衆功能鍵.iter().any(|功能鍵| 功能鍵.鍵碼 == 鍵碼) {
+            return None;
+        }
+        let 是擴展鍵位 = 擴展鍵位.iter().any(|&(鍵, _, _)| 鍵 == 鍵碼);
+        match 列.checked_sub(self.列偏移(行)) {
+            Some(列) if !是擴展鍵位 => Some(矩陣座標(行, 列)),
+            _ => self.鍵碼刻印座標(鍵碼),
+        }
+    }
+    /// 鍵碼的刻印在盤面上的位置. 配列中沒有的鍵, 到主鍵盤區找.
+    pub fn 鍵碼刻印座標(&self, 鍵碼: KeyCode) -> Option<矩陣座標> {
+        if let Some(&(_, 座標, _)) = 擴展鍵位.iter().find(|&&(鍵, _, _)| 鍵 == 鍵碼) {
+            return Some(座標);
+        }
+        [*self, 配列::主鍵盤區].into_iter().find_map(|配列| {
+            配列.矩陣().iter().enumerate().find_map(|(行, 此行)| {
+                let 偏移 = 配列.列偏移(行);
+                此行
+                    .iter()
+                    .skip(偏移)
+                    .position(|&鍵| 鍵 == 鍵碼)
+                    .map(|列| 矩陣座標(行, 列))
+            })
+        })
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -222,6 +279,25 @@ impl 鍵盤佈局 {
             })
     }
 
+    /// 默認盤面的頂層上有刻印的鍵. 只看首選配列中有的鍵位, 其餘刻印是爲其他配列準備的.
+    pub fn 刻印所在鍵位(&self) -> Vec<KeyCode> {
+        let Some(&頂層) = self.盤面.get(self.默認盤面.頂層盤面()) else {
+            return vec![];
+        };
+        let 配列 = self.首選配列;
+        配列
+            .矩陣()
+            .iter()
+            .enumerate()
+            .flat_map(|(行, 此行)| {
+                此行.iter().enumerate().filter_map(move |(列, &鍵碼)| {
+                    let 座標 = 配列.刻印座標(行, 列)?;
+                    matches!(self.從盤面讀取刻印(頂層, 座標)?, 鍵面刻印::有刻(_)).then_some(鍵碼)
+                })
+            })
+            .collect()
+    }
+
     fn 從盤面讀取刻印(
         &self,
         此盤面: 盤面定義<'static>,
@@ -248,6 +324,23 @@ const 主鍵盤區矩陣: 鍵盤矩陣<'static> = 矩陣![
     [Space]                                                       // A03
 ];
 
+const 歐規主鍵盤區矩陣: 鍵盤矩陣<'static> = 矩陣![
+    [Kc1 Kc2 Kc3 Kc4 Kc5 Kc6 Kc7 Kc8 Kc9 Kc0 Minus Equal Grave],  // E01 - E13
+    [Q W E R T Y U I O P LeftBracket RightBracket],               // D01 - D12
+    [A S D F G H J K L Semicolon Quote Backslash],                // C01 - C12
+    [NonusBackslash Z X C V B N M Comma Dot Slash],               // B00 - B10
+    [Space]                                                       // A03
+];
+
+const 日規主鍵盤區矩陣: 鍵盤矩陣<'static> = 矩陣![
+    // JIS 鍵盤的 半角/全角 鍵位於 E00, 網頁鍵值與 ` 鍵相同
+    [Grave Kc1 Kc2 Kc3 Kc4 Kc5 Kc6 Kc7 Kc8 Kc9 Kc0 Minus Equal International3],  // E00 - E13
+    [Q W E R T Y U I O P LeftBracket RightBracket],                               // D01 - D12
+    [A S D F G H J K L Semicolon Quote Backslash],                                // C01 - C12
+    [Z X C V B N M Comma Dot Slash International1],                               // B01 - B11
+    [Space]                                                                       // A03
+];
+
 const 字母鍵盤矩陣: 鍵盤矩陣<'static> = 矩陣![
     [],                             // E01
     [Q W E R T Y U I O P],          // D01 - D10
@@ -288,6 +381,14 @@ const 直列分體帶數字行矩陣: 鍵盤矩陣<'static> = 矩陣![
     [No Space Space]                            // A03 - A05
 ];
 
+const 拇指簇分體矩陣: 鍵盤矩陣<'static> = 矩陣![
+    [],                                         // E01
+    [Q W E R T Y U I O P],                      // D01 - D10
+    [A S D F G H J K L Semicolon],              // C01 - C10
+    [Z X C V B N M Comma Dot Slash],            // B01 - B10
+    [Escape Tab Space Space Enter Backspace]    // A02 - A07
+];
+
 #[macro_export]
 macro_rules! 盤面 {
     [ $( [ $( $鍵:tt )* ] $(,)? )* ] => {
@@ -336,18 +437,18 @@ macro_rules! 標註 {
 }
 
 pub const 基本盤面: 盤面定義<'static> = 盤面![
-    [ "1" "2" "3" "4" "5" "6" "7" "8" "9" "0" "-" "=" "`" ],  // E01 - E13
-    [ q w e r t y u i o p "[" "]" ],                          // D01 - D12
-    [ a s d f g h j k l ";" "'" "\\" ],                       // C01 - C12
-    [ z x c v b n m "," "." "/" ],                            // B01 - B10
-    [ "␣" "␣" "␣" ]                                           // A03 - A05
+    [ "1" "2" "3" "4" "5" "6" "7" "8" "9" "0" "-" "=" "`" "¥" ],  // E01 - E13, JIS E13
+    [ q w e r t y u i o p "[" "]" ],                              // D01 - D12
+    [ a s d f g h j k l ";" "'" "\\" ],                           // C01 - C12
+    [ z x c v b n m "," "." "/" "\\" "\\" ],                      // B01 - B11, B00
+    [ "␣" "␣" "␣" ]                                               // A03 - A05
 ];
 
 pub const 上檔盤面: 盤面定義<'static> = 盤面![
-    [ "!" "@" "#" "$" "%" "^" "&" "*" "(" ")" "_" "+" "~" ],
+    [ "!" "@" "#" "$" "%" "^" "&" "*" "(" ")" "_" "+" "~" "|" ],
     [ Q W E R T Y U I O P "{" "}" ],
     [ A S D F G H J K L ":" "\"" "|" ],
-    [ Z X C V B N M "<" ">" "?" ],
+    [ Z X C V B N M "<" ">" "?" "_" "|" ],
     [ _ _ _ ]
 ];
 
@@ -355,7 +456,7 @@ pub const 大寫字母盤面: 盤面定義<'static> = 盤面![
     [ _ _ _ _ _ _ _ _ _ _ _ _ _ _ ],
     [ Q W E R T Y U I O P _ _ ],
     [ A S D F G H J K L _ _ _ ],
-    [ Z X C V B N M _ _ _ ],
+    [ Z X C V B N M _ _ _ _ _ ],
     [ "␣" "␣" "␣" ]
 ];
 
@@ -388,37 +489,200 @@ pub mod 功能鍵 {
     pub const 衆功能鍵: &[鍵面映射] = &[退出鍵, 製表鍵, 退格鍵, 回車鍵];
 }
 
+/// 字根表及盤面用到的鍵
+pub fn 方案所用鍵位(方案: &輸入方案定義) -> Vec<KeyCode> {
+    let mut 衆鍵碼 = Vec::new();
+    let 字根鍵碼 = 方案.字根表.iter().map(|鍵位| 鍵位.鍵碼);
+    for 鍵碼 in 字根鍵碼.chain(方案.佈局.刻印所在鍵位()) {
+        if 鍵碼 != KeyCode::No && !衆鍵碼.contains(&鍵碼) {
+            衆鍵碼.push(鍵碼);
+        }
+    }
+    衆鍵碼
+}
+
+/// 方案用到而配列中沒有的鍵
+pub fn 缺失鍵位(所用鍵位: &[KeyCode], 配列幾何: &配列幾何) -> Vec<KeyCode> {
+    所用鍵位
+        .iter()
+        .copied()
+        .filter(|&鍵碼| 配列幾何.鍵位(鍵碼).is_none())
+        .collect()
+}
+
+/// 配列中的鍵改作所缺的鍵. (配列中的鍵, 代替的鍵)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct 替代鍵位表(pub Vec<(KeyCode, KeyCode)>);
+
+impl 替代鍵位表 {
+    /// 從配列中方案未用的字符鍵裏, 爲所缺的鍵逐一挑選在主鍵盤區上離得最近的.
+    /// 閒置的鍵不夠, 則餘下的鍵仍缺.
+    pub fn 編排(
+        缺失鍵位: &[KeyCode], 所用鍵位: &[KeyCode], 配列幾何: &配列幾何
+    ) -> Self {
+        let 參照幾何 = [
+            配列::主鍵盤區.幾何(),
+            配列::歐規主鍵盤區.幾何(),
+            配列::日規主鍵盤區.幾何(),
+        ];
+        let 參照位置 = |鍵碼| {
+            參照幾何
+                .iter()
+                .find_map(|幾何| 幾何.鍵位(鍵碼))
+                .map(|鍵位| 鍵位.中心())
+        };
+        let mut 閒置鍵位 = Vec::<(KeyCode, (f64, f64))>::new();
+        for 鍵位 in &配列幾何.衆鍵位 {
+            let 鍵碼 = 鍵位.鍵碼;
+            if 鍵位.刻印座標.is_none()
+                || matches!(鍵碼, KeyCode::No | KeyCode::Space)
+                || 所用鍵位.contains(&鍵碼)
+                || 閒置鍵位.iter().any(|&(鍵, _)| 鍵 == 鍵碼)
+            {
+                continue;
+            }
+            if let Some(位置) = 參照位置(鍵碼) {
+                閒置鍵位.push((鍵碼, 位置));
+            }
+        }
+        let mut 衆替代 = Vec::new();
+        for &缺鍵 in 缺失鍵位 {
+            let Some((缺鍵橫, 缺鍵縱)) = 參照位置(缺鍵) else {
+                continue;
+            };
+            let 距離 = |&(_, (橫, 縱)): &(KeyCode, (f64, f64))| (橫 - 缺鍵橫).hypot(縱 - 缺鍵縱);
+            let 最近 = 閒置鍵位
+                .iter()
+                .enumerate()
+                .min_by(|(_, 甲), (_, 乙)| 距離(甲).total_cmp(&距離(乙)))
+                .map(|(序號, _)| 序號);
+            if let Some(序號) = 最近 {
+                let (鍵碼, _) = 閒置鍵位.remove(序號);
+                衆替代.push((鍵碼, 缺鍵));
+            }
+        }
+        Self(衆替代)
+    }
+
+    /// 配列中的鍵若已改作他鍵, 換成所代替的鍵
+    pub fn 轉換(&self, 鍵碼: KeyCode) -> KeyCode {
+        self.0
+            .iter()
+            .find(|(鍵, _)| *鍵 == 鍵碼)
+            .map_or(鍵碼, |&(_, 代替)| 代替)
+    }
+}
+
+const 配列文件存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/kle-layout",
+};
+
+const 替代缺鍵存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/substitute-missing-keys",
+};
+
+pub type 導入配列動作 = impl 動作給一參數得一結果<String>;
+pub type 切換替代缺鍵動作 = impl 動作;
+
 #[derive(Clone)]
 pub struct 佈局機關輸出信號 {
     pub 實際配列: Signal<配列>,
     pub 選用配列: 選用配列動作,
-    pub 當選盤面: ReadSignal<盤面選擇碼>,
-    pub 選擇盤面: WriteSignal<盤面選擇碼>,
+    /// 鍵盤圖按此排鍵. 自訂配列取自導入的配列文件.
+    pub 配列幾何: Memo<配列幾何>,
+    pub 導入配列: 導入配列動作,
+    /// 方案用到而配列中沒有的鍵
+    pub 缺失鍵位: Memo<Vec<KeyCode>>,
+    pub 替代缺鍵: ReadSignal<bool>,
+    /// 未開啓替代缺鍵時爲空
+    pub 替代鍵位: Memo<替代鍵位表>,
+    pub 切換替代缺鍵: 切換替代缺鍵動作,
+    pub 當選盤面: Memo<盤面選擇碼>,
 }
 
+#[define_opaque(導入配列動作, 切換替代缺鍵動作)]
 pub fn 佈局機關(
     方案: &輸入方案機關輸出信號,
+    核心: RwSignal<引擎核心>,
     已選配列: Signal<Option<配列>>,
     選用配列: 選用配列動作,
+    配列文件網址: Signal<Option<String>>,
 ) -> 佈局機關輸出信號 {
     let 方案定義 = 方案.方案定義;
     let 實際配列 =
         Signal::derive(move || 已選配列.read().unwrap_or_else(|| 方案定義().佈局.首選配列));
 
-    let (當選盤面, 選擇盤面) = signal(方案定義.get_untracked().佈局.默認盤面);
-
-    let _ = Effect::watch(
-        方案定義,
-        move |&方案, _, _| {
-            選擇盤面(方案.佈局.默認盤面);
-        },
-        false,
+    let (自訂幾何, 設置自訂幾何) = signal(
+        配列文件存儲
+            .讀出::<String>()
+            .and_then(|文本| 載入配列文件(&文本).ok()),
     );
+    let 配列幾何 = Memo::new(move |_| match 實際配列() {
+        配列::自訂 => 自訂幾何().unwrap_or_else(|| 配列::自訂.幾何()),
+        配列 => 配列.幾何(),
+    });
+
+    let 導入配列 = move |文本: String| -> 成功失敗 {
+        let 幾何 = 載入配列文件(&文本).map_err(|錯誤| {
+            log!("導入配列失敗: {}", 錯誤);
+            未有()
+        })?;
+        if 配列文件存儲.存入(&文本).is_err() {
+            log!("配列文件未能存入瀏覽器");
+        }
+        設置自訂幾何(Some(幾何));
+        選用配列(Some(配列::自訂));
+        Ok(())
+    };
+
+    let 求取的配列文件 = LocalResource::new(move || {
+        let 網址 = 配列文件網址.get();
+        async move { Request::get(&網址?).send().await.ok()?.text().await.ok() }
+    });
+    Effect::new(move || {
+        if let Some(文本) = 求取的配列文件.get().flatten() {
+            let _ = 導入配列(文本);
+        }
+    });
+
+    let 所用鍵位 = Memo::new(move |_| 方案所用鍵位(&方案定義()));
+    let 缺失鍵位 = Memo::new(move |_| 缺失鍵位(&所用鍵位.read(), &配列幾何.read()));
+    Effect::new(move || {
+        if !缺失鍵位.read().is_empty() {
+            log!(
+                "配列 {} 缺少方案所用的鍵: {:?}",
+                實際配列(),
+                缺失鍵位.read()
+            );
+        }
+    });
+
+    let (替代缺鍵, 設置替代缺鍵) = signal(替代缺鍵存儲.讀出::<bool>().unwrap_or_default());
+    let 替代鍵位 = Memo::new(move |_| {
+        if !替代缺鍵() {
+            return 替代鍵位表::default();
+        }
+        替代鍵位表::編排(&缺失鍵位.read(), &所用鍵位.read(), &配列幾何.read())
+    });
+    let 切換替代缺鍵 = move || {
+        let 開啓 = !替代缺鍵.get_untracked();
+        設置替代缺鍵(開啓);
+        if 替代缺鍵存儲.存入(&開啓).is_err() {
+            log!("替代缺鍵選項未能存入瀏覽器");
+        }
+    };
+
+    let 當選盤面 = Memo::new(move |_| 核心.read().當選盤面());
 
     佈局機關輸出信號 {
         實際配列,
         選用配列,
+        配列幾何,
+        導入配列,
+        缺失鍵位,
+        替代缺鍵,
+        替代鍵位,
+        切換替代缺鍵,
         當選盤面,
-        選擇盤面,
     }
 }
@@ -1,8 +1,18 @@
+pub mod adaptive;
 pub mod assignment;
 pub mod caption;
 pub mod chord;
+pub mod curriculum;
+pub mod geometry;
+pub mod ghost;
+pub mod heatmap;
+pub mod history;
+pub mod host_layout;
 pub mod input;
 pub mod key_press;
 pub mod layout;
 pub mod mode;
+pub mod replay;
+pub mod retry;
+pub mod statistics;
 pub mod theory;
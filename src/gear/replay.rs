@@ -0,0 +1,390 @@
+//! 擊鍵錄放
+//!
+//! 錄下每次落鍵、抬鍵的時刻與來源, 連同作業與判定設置存爲錄像.
+//! 重放時按原速或變速將擊鍵依次送入引擎核心, 鍵盤圖與字幕隨之變化,
+//! 可用於檢討指法; 導出的錄像亦可在別處確定地重現問題.
+
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::task::{spawn_local, tick};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::slice;
+use std::time::Duration;
+
+use crate::action::{
+    動作, 動作給一參數, 動作給一參數得一結果, 成功失敗, 未有
+};
+use crate::definition::碼表格式;
+use crate::gear::{
+    adaptive::加練標題,
+    assignment::{
+        作業, 作業內容, 作業機關輸出信號, 推進方式, 生成題, 碼表定義
+    },
+    caption::字幕格式,
+    chord::並擊判定,
+    curriculum::解鎖標題,
+    history::本地存儲,
+    input::{擊鍵事件, 擊鍵來源},
+    retry::重練標題,
+    theory::{方案選項, 輸入方案機關輸出信號},
+};
+use crate::headless::{功能鍵反饋, 引擎核心, 擊鍵反饋};
+use crate::key_code::KeyCode;
+
+/// 錄像中的生成題不在這幾種之列時, 重放時所用的標題
+pub const 重放標題: &str = "錄像重放";
+
+/// 點擊切換重放速度時依次經過的倍數
+const 重放倍速輪替: [f64; 4] = [1.0, 2.0, 4.0, 0.5];
+
+const 錄像存儲: 本地存儲 = 本地存儲 {
+    鍵名: "typewriter/recording",
+};
+
+/// 即時生成的練習題無從再生, 錄下其碼表與字幕
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct 錄像生成題 {
+    pub 標題: String,
+    pub 格式: 碼表格式,
+    pub 碼表: String,
+    /// 爲空則自動生成字幕
+    pub 字幕: Option<String>,
+}
+
+/// 錄製時的作業
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct 錄像作業 {
+    pub 題號: Option<usize>,
+    pub 自訂反查碼: Option<String>,
+    pub 生成題: Option<錄像生成題>,
+}
+
+impl 錄像作業 {
+    pub fn 取自(作業: &作業, 編碼法: 碼表格式) -> Self {
+        錄像作業 {
+            題號: 作業.題號,
+            自訂反查碼: 作業.自訂反查碼.clone(),
+            生成題: 作業.生成題.as_ref().map(|題| 錄像生成題 {
+                標題: 題.標題.to_owned(),
+                格式: 題.內容.碼表.碼表格式().unwrap_or(編碼法),
+                碼表: 題.內容.碼表.碼表原文().to_owned(),
+                字幕: match &題.內容.字幕 {
+                    字幕格式::自訂(字幕) => Some(字幕.to_string()),
+                    字幕格式::詞句(字幕) => Some(字幕.to_string()),
+                    _ => None,
+                },
+            }),
+        }
+    }
+
+    pub fn 還原(&self, 科目: 方案選項) -> 作業 {
+        if let Some(題號) = self.題號 {
+            return 作業::練習題(科目, 題號);
+        }
+        if let Some(反查碼) = &self.自訂反查碼 {
+            return 作業::自訂(科目, 反查碼.clone());
+        }
+        let Some(題) = &self.生成題 else {
+            return 作業::自習(科目);
+        };
+        let 標題 = [加練標題, 解鎖標題, 重練標題]
+            .into_iter()
+            .find(|標題| *標題 == 題.標題)
+            .unwrap_or(重放標題);
+        作業::生成(
+            科目,
+            生成題 {
+                標題,
+                內容: 作業內容 {
+                    碼表: 碼表定義::生成(題.格式, 題.碼表.clone()),
+                    字幕: 題.字幕.clone().map_or(字幕格式::自動生成, |字幕| {
+                        字幕格式::自訂(Cow::Owned(字幕))
+                    }),
+                },
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct 擊鍵錄像 {
+    /// 方案代號
+    pub 方案: String,
+    pub 作業: 錄像作業,
+    #[serde(default)]
+    pub 推進方式: 推進方式,
+    #[serde(default)]
+    pub 並擊判定: 並擊判定,
+    /// 自訂方案的數據文件網址; 內建方案則爲空
+    #[serde(default)]
+    pub 方案文件: Option<String>,
+    /// 錄製開始的時刻, 毫秒
+    pub 日期: f64,
+    pub 衆事件: Vec<擊鍵事件>,
+}
+
+impl 擊鍵錄像 {
+    /// 開始錄製至首擊, 毫秒
+    pub fn 首擊時刻(&self) -> f64 {
+        self.衆事件.first().map_or(0.0, |事件| 事件.時刻)
+    }
+
+    /// 首擊至末擊, 毫秒
+    pub fn 時長(&self) -> f64 {
+        self.衆事件
+            .last()
+            .map_or(0.0, |事件| 事件.時刻 - self.首擊時刻())
+    }
+
+    pub fn 落鍵數(&self) -> usize {
+        self.衆事件.iter().filter(|事件| 事件.落鍵).count()
+    }
+}
+
+/// 不經網頁界面, 將錄像中的擊鍵依次送入引擎核心, 返回每次擊鍵的反饋.
+/// 功能鍵按錄入狀態處理, 見 [`引擎核心::功能鍵`]; 開啓選單的鍵略去.
+pub fn 重放到核心(核心: &mut 引擎核心, 衆事件: &[擊鍵事件]) -> Vec<擊鍵反饋> {
+    衆事件
+        .iter()
+        .map(|事件| {
+            if !事件.落鍵 {
+                return 核心.抬鍵(事件.鍵碼);
+            }
+            let 功能鍵已處理 = 核心.功能鍵(事件.鍵碼, 事件.上檔) != 功能鍵反饋::非功能鍵;
+            // 實體鍵盤的功能鍵處理過後亦照常落鍵, 屏幕鍵盤上的則攔下
+            let 攔下 = 功能鍵已處理 && 事件.來源 == 擊鍵來源::屏幕鍵盤;
+            if 攔下 || 事件.鍵碼 == KeyCode::No {
+                擊鍵反饋::default()
+            } else {
+                核心.落鍵(事件.鍵碼)
+            }
+        })
+        .collect()
+}
+
+pub type 錄製擊鍵動作 = impl 動作給一參數<擊鍵事件>;
+pub type 開始錄製動作 = impl 動作;
+pub type 停止錄製動作 = impl 動作;
+pub type 開始重放動作 = impl 動作;
+pub type 停止重放動作 = impl 動作;
+pub type 切換重放倍速動作 = impl 動作;
+pub type 導入錄像動作 = impl 動作給一參數得一結果<String>;
+
+#[derive(Clone, Copy)]
+pub struct 錄放機關輸出信號 {
+    pub 錄製中: ReadSignal<bool>,
+    pub 放映中: ReadSignal<bool>,
+    /// 最近錄製或導入的錄像
+    pub 錄像: ReadSignal<Option<擊鍵錄像>>,
+    pub 重放倍速: ReadSignal<f64>,
+    pub 錄製擊鍵: 錄製擊鍵動作,
+    pub 開始錄製: 開始錄製動作,
+    pub 停止錄製: 停止錄製動作,
+    pub 開始重放: 開始重放動作,
+    pub 停止重放: 停止重放動作,
+    pub 切換重放倍速: 切換重放倍速動作,
+    pub 導入錄像: 導入錄像動作,
+}
+
+#[define_opaque(
+    錄製擊鍵動作,
+    開始錄製動作,
+    停止錄製動作,
+    開始重放動作,
+    停止重放動作,
+    切換重放倍速動作,
+    導入錄像動作
+)]
+pub fn 錄放機關(
+    方案: &輸入方案機關輸出信號,
+    作業: &作業機關輸出信號,
+    核心: RwSignal<引擎核心>,
+) -> 錄放機關輸出信號 {
+    let 現行方案 = 方案.現行方案;
+    let 選用方案 = 方案.選用方案;
+    let 方案定義 = 方案.方案定義;
+    let 方案文件網址 = 方案.方案文件網址;
+    let 當前作業 = 作業.當前作業;
+    let 佈置作業 = 作業.佈置作業;
+    let 目標作業內容 = 作業.目標作業內容;
+    let 一題打完 = 作業.一題打完;
+
+    let (錄製中, 設置錄製中) = signal(false);
+    let (放映中, 設置放映中) = signal(false);
+    let (錄像, 設置錄像) = signal(錄像存儲.讀出::<擊鍵錄像>());
+    let (重放倍速, 設置重放倍速) = signal(1.0);
+    // 每次開始或停止重放都換一代, 上一代排定的擊鍵便不再送出
+    let 放映代次 = StoredValue::new(0_usize);
+
+    let 錄製擊鍵 = move |事件: 擊鍵事件| {
+        if !錄製中.get_untracked() {
+            return;
+        }
+        設置錄像.update(|錄像| {
+            if let Some(錄像) = 錄像 {
+                錄像.衆事件.push(擊鍵事件 {
+                    時刻: 事件.時刻 - 錄像.日期,
+                    ..事件
+                });
+            }
+        });
+    };
+
+    let 開始錄製 = move || {
+        let 核心狀態 = 核心.read_untracked();
+        let 科目 = 現行方案.get_untracked();
+        let 新錄像 = 擊鍵錄像 {
+            方案: 科目.slug().to_owned(),
+            作業: 錄像作業::取自(
+                &當前作業.read_untracked(),
+                方案定義.read_untracked().編碼法,
+            ),
+            推進方式: 核心狀態.推進方式(),
+            並擊判定: 核心狀態.並擊判定(),
+            方案文件: (科目 == 方案選項::自訂方案)
+                .then(|| 方案文件網址.get_untracked())
+                .flatten(),
+            日期: js_sys::Date::now(),
+            衆事件: vec![],
+        };
+        drop(核心狀態);
+        // 從頭錄起, 重放時纔對得上
+        核心.update(|核心| {
+            核心.重置作業進度();
+            核心.重置輸入狀態();
+        });
+        設置錄像(Some(新錄像));
+        設置錄製中(true);
+    };
+
+    let 停止錄製 = move || {
+        if !錄製中.get_untracked() {
+            return;
+        }
+        設置錄製中(false);
+        if let Some(錄像) = &*錄像.read_untracked() {
+            if 錄像存儲.存入(錄像).is_err() {
+                log!("錄像未能存入瀏覽器");
+            }
+        }
+    };
+
+    // 打完一題即停錄
+    let _ = Effect::watch(
+        一題打完,
+        move |&打完, _, _| {
+            if 打完 {
+                停止錄製();
+            }
+        },
+        false,
+    );
+
+    let 停止重放 = move || {
+        放映代次.update_value(|代次| *代次 += 1);
+        設置放映中(false);
+    };
+
+    let 開始重放 = move || {
+        let Some(錄像) = 錄像.get_untracked() else {
+            return;
+        };
+        let 科目 = 方案選項::from_slug(&錄像.方案).unwrap_or(現行方案.get_untracked());
+        // 自訂方案無從按代號找回, 須是同一數據文件
+        if 科目 == 方案選項::自訂方案 && 錄像.方案文件 != 方案文件網址.get_untracked()
+        {
+            log!("錄像所用的自訂方案不是現行方案: {:?}", 錄像.方案文件);
+            return;
+        }
+        停止錄製();
+        放映代次.update_value(|代次| *代次 += 1);
+        let 本代 = 放映代次.get_value();
+        let 倍速 = 重放倍速.get_untracked();
+        設置放映中(true);
+
+        if 科目 != 現行方案.get_untracked() {
+            選用方案(科目);
+        }
+        spawn_local(async move {
+            // 換方案後, 待其佈置的默認作業落定, 再佈置錄像中的作業
+            tick().await;
+            if 放映代次.get_value() != 本代 {
+                return;
+            }
+            佈置作業(錄像.作業.還原(科目));
+            tick().await;
+            // 等練習題載入, 核心隨之佈置作業
+            let _ = 目標作業內容.await;
+            tick().await;
+            if 放映代次.get_value() != 本代 {
+                return;
+            }
+            核心.update(|核心| {
+                核心.設置推進方式(錄像.推進方式);
+                核心.設置並擊判定(錄像.並擊判定);
+                核心.重置作業進度();
+                核心.重置輸入狀態();
+            });
+            if 錄像.衆事件.is_empty() {
+                設置放映中(false);
+                return;
+            }
+            let 末次 = 錄像.衆事件.len() - 1;
+            let 首擊 = 錄像.首擊時刻();
+            for (序號, 事件) in 錄像.衆事件.into_iter().enumerate() {
+                let 延時 = Duration::from_secs_f64((事件.時刻 - 首擊).max(0.0) / 倍速 / 1000.0);
+                set_timeout(
+                    move || {
+                        if 放映代次.get_value() != 本代 {
+                            return;
+                        }
+                        // 只送入引擎核心, 不觸動選單、重練等界面狀態
+                        核心.update(|核心| {
+                            重放到核心(核心, slice::from_ref(&事件));
+                        });
+                        if 序號 == 末次 {
+                            設置放映中(false);
+                        }
+                    },
+                    延時,
+                );
+            }
+        });
+    };
+
+    let 切換重放倍速 = move || {
+        let 現行 = 重放倍速.get_untracked();
+        let 下一項 = 重放倍速輪替
+            .iter()
+            .skip_while(|&&倍速| 倍速 != 現行)
+            .nth(1)
+            .copied()
+            .unwrap_or(重放倍速輪替[0]);
+        設置重放倍速(下一項);
+    };
+
+    let 導入錄像 = move |文本: String| -> 成功失敗 {
+        let 導入的錄像 = serde_json::from_str::<擊鍵錄像>(&文本).map_err(|錯誤| {
+            log!("錄像格式有誤: {}", 錯誤);
+            未有()
+        })?;
+        let _ = 錄像存儲.存入(&導入的錄像);
+        設置錄像(Some(導入的錄像));
+        Ok(())
+    };
+
+    錄放機關輸出信號 {
+        錄製中,
+        放映中,
+        錄像,
+        重放倍速,
+        錄製擊鍵,
+        開始錄製,
+        停止錄製,
+        開始重放,
+        停止重放,
+        切換重放倍速,
+        導入錄像,
+    }
+}
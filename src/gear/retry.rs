@@ -0,0 +1,162 @@
+//! 重練錯處
+//!
+//! 從剛打過的作業中挑出擊錯或偏慢的片段, 連同字幕中對應的文字, 另組一道練習題.
+//! 打完一題而有錯處, 提議重練: 錄入時擊下任一字鍵即重練, 按 Esc 略過.
+
+use leptos::prelude::*;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use crate::action::動作;
+use crate::gear::{
+    adaptive::片段庫,
+    assignment::{作業, 作業內容, 作業機關輸出信號, 對照輸入碼, 生成題},
+    caption::字幕格式,
+    statistics::{片段紀錄, 統計機關輸出信號},
+    theory::輸入方案機關輸出信號,
+};
+use crate::headless::引擎核心;
+
+pub const 重練標題: &str = "重練錯處";
+
+/// 用時超過平均用時的此倍數, 算偏慢
+const 偏慢倍數: f64 = 1.5;
+
+/// 擊錯過的片段, 及 `計入偏慢` 時用時偏長的片段, 以作業中的序號表示.
+/// `擊錯片段` 爲記錯推進時跳過的片段.
+pub fn 待重練片段(
+    衆片段: &[片段紀錄],
+    擊錯片段: &BTreeSet<usize>,
+    計入偏慢: bool,
+) -> BTreeSet<usize> {
+    let 平均用時 = if 衆片段.is_empty() {
+        0.0
+    } else {
+        衆片段.iter().map(|片段| 片段.用時).sum::<f64>() / 衆片段.len() as f64
+    };
+    衆片段
+        .iter()
+        .filter(|片段| 片段.擊錯次數 > 0 || 計入偏慢 && 片段.用時 > 平均用時 * 偏慢倍數)
+        .map(|片段| 片段.進度)
+        .chain(擊錯片段.iter().copied())
+        .collect()
+}
+
+/// 取作業中指定序號的片段, 另組作業. 字幕沿用原作業中對應的文字.
+/// 空白片段只作分隔, 不收.
+pub fn 重練內容(
+    核心: &引擎核心, 衆序號: &BTreeSet<usize>
+) -> Option<作業內容<'static>> {
+    let 原作業 = 核心.作業內容()?;
+    let 輸入碼序列 = 核心.目標輸入碼序列();
+    let mut 衆片段 = Vec::<&對照輸入碼>::new();
+    let mut 字幕 = String::new();
+    for &序號 in 衆序號 {
+        let Some(片段) = 輸入碼序列.get(序號) else {
+            continue;
+        };
+        let Some(輸入碼) = 片段.顯示輸入碼().filter(|碼| !碼.trim().is_empty()) else {
+            continue;
+        };
+        let 文字 = 核心
+            .分段字幕()
+            .iter()
+            .find_map(|段落| 段落.文字(序號))
+            .unwrap_or_else(|| 輸入碼.to_owned());
+        // 文字組中不可有空白, 逐詞字幕中的詞後空格去掉
+        let 文字 = 文字.split_whitespace().collect::<String>();
+        match 文字.chars().count() {
+            1 => 字幕.push_str(&文字),
+            _ => 字幕.push_str(&format!("[{文字}]")),
+        }
+        衆片段.push(片段);
+    }
+    if 衆片段.is_empty() {
+        return None;
+    }
+    let 庫 = 片段庫 {
+        格式: 核心.目標碼表格式().unwrap_or(核心.方案().編碼法),
+        衆片段: vec![],
+    };
+    Some(作業內容 {
+        碼表: 庫.寫成碼表(&衆片段),
+        字幕: match 原作業.字幕 {
+            字幕格式::自動生成 => 字幕格式::自動生成,
+            _ => 字幕格式::自訂(Cow::Owned(字幕)),
+        },
+    })
+}
+
+pub type 佈置重練動作 = impl 動作;
+pub type 婉拒重練動作 = impl 動作;
+
+#[derive(Clone, Copy)]
+pub struct 重練機關輸出信號 {
+    /// 爲空則無可重練
+    pub 待重練片段: Memo<BTreeSet<usize>>,
+    /// 剛打完一題, 有錯處且未被略過
+    pub 提議重練: Memo<bool>,
+    pub 佈置重練: 佈置重練動作,
+    pub 婉拒重練: 婉拒重練動作,
+}
+
+#[define_opaque(佈置重練動作, 婉拒重練動作)]
+pub fn 重練機關(
+    方案: &輸入方案機關輸出信號,
+    作業: &作業機關輸出信號,
+    統計: &統計機關輸出信號,
+    核心: RwSignal<引擎核心>,
+) -> 重練機關輸出信號 {
+    let 現行方案 = 方案.現行方案;
+    let 當前作業 = 作業.當前作業;
+    let 佈置作業 = 作業.佈置作業;
+    let 一題打完 = 作業.一題打完;
+    let 練習統計 = 統計.練習統計;
+
+    let 待重練片段 = Memo::new(move |_| {
+        // 重練題中只看錯處, 否則總有相對偏慢的片段, 練不完
+        let 是重練題 = 當前作業
+            .read()
+            .生成題
+            .as_ref()
+            .is_some_and(|題| 題.標題 == 重練標題);
+        待重練片段(練習統計.read().衆片段(), 核心.read().擊錯片段(), !是重練題)
+    });
+
+    let 已婉拒 = RwSignal::new(false);
+    // 每打完一題, 重新提議
+    let _ = Effect::watch(
+        一題打完,
+        move |_, _, _| {
+            已婉拒.set(false);
+        },
+        false,
+    );
+
+    let 提議重練 = Memo::new(move |_| 一題打完() && !已婉拒() && !待重練片段.read().is_empty());
+
+    let 佈置重練 = move || {
+        let 核心 = 核心.read_untracked();
+        let Some(內容) = 重練內容(&核心, &待重練片段.read_untracked()) else {
+            return;
+        };
+        佈置作業(作業::生成(
+            現行方案.get_untracked(),
+            生成題 {
+                標題: 重練標題,
+                內容,
+            },
+        ));
+    };
+
+    let 婉拒重練 = move || {
+        已婉拒.set(true);
+    };
+
+    重練機關輸出信號 {
+        待重練片段,
+        提議重練,
+        佈置重練,
+        婉拒重練,
+    }
+}
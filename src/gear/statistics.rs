@@ -0,0 +1,241 @@
+//! 練習統計
+//!
+//! 記錄作業中每個片段從輪到它至擊中所用的時間、首次擊鍵前的遲疑、擊中之前擊錯幾次,
+//! 彙總爲速度與首擊正確率. 片段在並擊方案中爲音節, 在連擊方案中爲字詞或字根.
+//! 時刻以毫秒計, 由調用方給出, 故亦可在瀏覽器以外使用.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::動作給一參數;
+use crate::headless::{引擎核心, 擊鍵反饋};
+
+/// 擊鍵之前正在作答的片段
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct 作答位置 {
+    pub 進度: usize,
+    /// 無作業時爲空, 不計入統計
+    pub 輸入碼: Option<String>,
+}
+
+impl 作答位置 {
+    pub fn 取自(核心: &引擎核心) -> Self {
+        作答位置 {
+            進度: 核心.作業進度(),
+            輸入碼: 核心
+                .目標輸入碼片段()
+                .filter(|_| !核心.作業進度完成())
+                .and_then(|對照碼| 對照碼.顯示輸入碼())
+                .map(str::to_owned),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct 擊鍵紀錄 {
+    pub 落鍵: bool,
+    pub 作答: 作答位置,
+    pub 反饋: 擊鍵反饋,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct 片段紀錄 {
+    pub 進度: usize,
+    pub 輸入碼: String,
+    /// 輪到此片段至首次落鍵, 毫秒
+    pub 遲疑: f64,
+    /// 輪到此片段至擊中, 毫秒
+    pub 用時: f64,
+    pub 擊錯次數: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct 片段計時 {
+    作答: 作答位置,
+    起始時刻: f64,
+    首擊時刻: Option<f64>,
+    擊錯次數: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct 練習統計 {
+    衆片段: Vec<片段紀錄>,
+    擊數: usize,
+    擊錯數: usize,
+    非正規並擊數: usize,
+    開始時刻: Option<f64>,
+    /// 擊中後輪到的進度, 及擊中時刻
+    末次擊中: Option<(usize, f64)>,
+    計時: Option<片段計時>,
+    /// 已打完一題, 保留統計至下一輪首次落鍵
+    一題打完: bool,
+}
+
+impl 練習統計 {
+    pub fn 重置(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn 衆片段(&self) -> &[片段紀錄] {
+        &self.衆片段
+    }
+
+    pub fn 記錄(&mut self, 時刻: f64, 紀錄: &擊鍵紀錄) {
+        let 擊鍵紀錄 {
+            落鍵, 作答, 反饋
+        } = 紀錄;
+        let Some(輸入碼) = &作答.輸入碼 else {
+            return;
+        };
+        // 分段的作業打完後迴轉到開頭, 再落鍵即開始新的一輪
+        if self.一題打完 && *落鍵 {
+            self.重置();
+        }
+        self.開始時刻.get_or_insert(時刻);
+        let 末次擊中 = self.末次擊中;
+        let 計時 = match &mut self.計時 {
+            Some(計時) if 計時.作答 == *作答 => 計時,
+            計時 => 計時.insert(片段計時 {
+                作答: 作答.clone(),
+                // 緊接上一片段擊中, 從擊中時起算; 跳轉而來則從此擊起算
+                起始時刻: 末次擊中
+                    .filter(|&(進度, _)| 進度 == 作答.進度)
+                    .map_or(時刻, |(_, 擊中時刻)| 擊中時刻),
+                首擊時刻: None,
+                擊錯次數: 0,
+            }),
+        };
+        if *落鍵 {
+            計時.首擊時刻.get_or_insert(時刻);
+        }
+        if 反饋.擊發 {
+            self.擊數 += 1;
+        }
+        if 反饋.擊鍵有誤 {
+            self.擊錯數 += 1;
+            計時.擊錯次數 += 1;
+        }
+        if 反饋.非正規並擊 {
+            self.非正規並擊數 += 1;
+        }
+        if 反饋.作業推進 {
+            self.衆片段.push(片段紀錄 {
+                進度: 作答.進度,
+                輸入碼: 輸入碼.clone(),
+                遲疑: 計時.首擊時刻.unwrap_or(時刻) - 計時.起始時刻,
+                用時: 時刻 - 計時.起始時刻,
+                擊錯次數: 計時.擊錯次數,
+            });
+            self.末次擊中 = Some((作答.進度 + 1, 時刻));
+            self.計時 = None;
+        }
+        if 反饋.打完一題 {
+            self.一題打完 = true;
+        }
+    }
+
+    pub fn 摘要(&self) -> 統計摘要 {
+        let 片段數 = self.衆片段.len();
+        let 平均 = |取值: fn(&片段紀錄) -> f64| {
+            if 片段數 == 0 {
+                0.0
+            } else {
+                self.衆片段.iter().map(取值).sum::<f64>() / 片段數 as f64
+            }
+        };
+        統計摘要 {
+            片段數,
+            擊數: self.擊數,
+            擊錯數: self.擊錯數,
+            首擊正確數: self.衆片段.iter().filter(|片段| 片段.擊錯次數 == 0).count(),
+            非正規並擊數: self.非正規並擊數,
+            用時: self
+                .開始時刻
+                .zip(self.末次擊中)
+                .map_or(0.0, |(開始, (_, 擊中))| 擊中 - 開始),
+            平均遲疑: 平均(|片段| 片段.遲疑),
+            平均用時: 平均(|片段| 片段.用時),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct 統計摘要 {
+    pub 片段數: usize,
+    /// 連擊方案計字根數, 並擊方案計並擊次數, 含擊錯的
+    pub 擊數: usize,
+    pub 擊錯數: usize,
+    /// 未曾擊錯便擊中的片段數
+    pub 首擊正確數: usize,
+    /// 拼式相符而並擊不是正規並擊的片段數. 舊紀錄中沒有此項.
+    #[serde(default)]
+    pub 非正規並擊數: usize,
+    /// 首次擊鍵至末次擊中, 毫秒
+    pub 用時: f64,
+    pub 平均遲疑: f64,
+    pub 平均用時: f64,
+}
+
+impl 統計摘要 {
+    fn 每分鐘(&self, 數目: usize) -> f64 {
+        if self.用時 > 0.0 {
+            數目 as f64 * 60_000.0 / self.用時
+        } else {
+            0.0
+        }
+    }
+
+    pub fn 每分鐘片段數(&self) -> f64 {
+        self.每分鐘(self.片段數)
+    }
+
+    pub fn 每分鐘擊數(&self) -> f64 {
+        self.每分鐘(self.擊數)
+    }
+
+    pub fn 首擊正確率(&self) -> f64 {
+        if self.片段數 == 0 {
+            0.0
+        } else {
+            self.首擊正確數 as f64 / self.片段數 as f64
+        }
+    }
+}
+
+pub type 記錄擊鍵動作 = impl 動作給一參數<擊鍵紀錄>;
+
+#[derive(Clone, Copy)]
+pub struct 統計機關輸出信號 {
+    pub 練習統計: ReadSignal<練習統計>,
+    pub 統計摘要: Memo<統計摘要>,
+    pub 記錄擊鍵: 記錄擊鍵動作,
+}
+
+#[define_opaque(記錄擊鍵動作)]
+pub fn 統計機關(核心: RwSignal<引擎核心>) -> 統計機關輸出信號 {
+    let (練習統計, 更新統計) = signal(練習統計::default());
+
+    // 從頭開始作業時, 重新統計. 打完一題而迴轉到開頭者, 留待下一輪落鍵時重新統計
+    let 作業進度 = Memo::new(move |_| 核心.read().作業進度());
+    let _ = Effect::watch(
+        作業進度,
+        move |&進度, _, _| {
+            if 進度 == 0 && !核心.read_untracked().一題打完() {
+                更新統計.write().重置();
+            }
+        },
+        false,
+    );
+
+    let 記錄擊鍵 = move |紀錄: 擊鍵紀錄| {
+        更新統計.write().記錄(js_sys::Date::now(), &紀錄);
+    };
+
+    let 統計摘要 = Memo::new(move |_| 練習統計.read().摘要());
+
+    統計機關輸出信號 {
+        練習統計,
+        統計摘要,
+        記錄擊鍵,
+    }
+}
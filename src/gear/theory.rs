@@ -1,4 +1,6 @@
+use gloo_net::http::Request;
 use lazy_static::lazy_static;
+use leptos::logging::log;
 use leptos::prelude::*;
 use strum::{Display, EnumIter};
 
@@ -16,6 +18,7 @@ use crate::theory::{
     modern_chinese::現代漢語輸入方案, old_chinese::上古漢語輸入方案,
     old_mandarin::近古漢語輸入方案, zhuyin::注音輸入方案,
 };
+use crate::theory_file::載入方案文件;
 
 #[derive(Clone, Copy, Default, Display, EnumIter, PartialEq)]
 pub enum 方案選項 {
@@ -34,6 +37,8 @@ pub enum 方案選項 {
     宮保注音,
     倉頡,
     倉頡七曜並擊,
+    /// 從數據文件載入的方案
+    自訂方案,
 }
 
 type 生成方案函數 = fn(輸入方案環境) -> 輸入方案定義<'static>;
@@ -84,29 +89,50 @@ pub struct 輸入方案機關輸出信號 {
     pub 選用方案: 選用方案動作,
     pub 方案定義: Signal<輸入方案定義<'static>>,
     pub 指法: Signal<觸鍵方式>,
+    /// 自訂方案的數據文件網址
+    pub 方案文件網址: Signal<Option<String>>,
 }
 
-#[derive(Clone, Copy)]
+/// 生成方案定義時參考的外部條件
+#[derive(Clone, Copy, Default)]
 pub struct 輸入方案環境 {
-    pub 已選配列: Signal<Option<配列>>,
+    pub 已選配列: Option<配列>,
+}
+
+/// 按選項生成內建方案的定義. 自訂方案須另行載入.
+pub fn 生成方案定義(
+    方案: 方案選項,
+    環境: 輸入方案環境,
+) -> Option<輸入方案定義<'static>> {
+    方案選單
+        .iter()
+        .find_map(|&(選項, 生成方案定義)| (選項 == 方案).then(|| 生成方案定義(環境)))
 }
 
 pub fn 輸入方案機關(
     現行方案: Signal<方案選項>,
     選用方案: 選用方案動作,
-    環境: 輸入方案環境,
+    已選配列: Signal<Option<配列>>,
+    方案文件網址: Signal<Option<String>>,
 ) -> 輸入方案機關輸出信號 {
+    let 載入的方案 = LocalResource::new(move || {
+        let 網址 = 方案文件網址.get();
+        async move {
+            let 方案文本 = Request::get(&網址?).send().await.ok()?.text().await.ok()?;
+            載入方案文件(&方案文本)
+                .inspect_err(|錯誤| log!("載入方案失敗: {}", 錯誤))
+                .ok()
+        }
+    });
+
     let 方案定義 = Signal::derive(move || {
-        方案選單
-            .iter()
-            .find_map(|&(方案, 生成方案定義)| {
-                if 方案 == 現行方案() {
-                    Some(生成方案定義(環境))
-                } else {
-                    None
-                }
-            })
-            .unwrap_or(未定義方案)
+        if 現行方案() == 方案選項::自訂方案 {
+            return 載入的方案.read().flatten().unwrap_or(未定義方案);
+        }
+        let 環境 = 輸入方案環境 {
+            已選配列: 已選配列(),
+        };
+        生成方案定義(現行方案(), 環境).unwrap_or(未定義方案)
     });
 
     let 指法 = Signal::derive(move || 方案定義.read().指法);
@@ -116,5 +142,6 @@ pub fn 輸入方案機關(
         選用方案,
         方案定義,
         指法,
+        方案文件網址,
     }
 }
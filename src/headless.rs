@@ -0,0 +1,687 @@
+//! 無頭引擎
+//!
+//! 輸入狀態機的本體, 不依賴 Leptos 的響應式運行時.
+//! 餵給它方案定義、作業內容和擊鍵事件, 即可讀出並擊結果、作業進度及字幕狀態.
+//! 網頁界面的各機關將其包裝爲信號; 亦可在瀏覽器以外驅動, 如測試或終端程序.
+
+use std::cmp::min;
+use std::collections::BTreeSet;
+
+use crate::action::{成功失敗, 未有};
+use crate::definition::{
+    判定鍵位, 碼表格式, 觸鍵方式, 輸入方案定義, 選擇鍵面, 鍵位定義, 鍵組
+};
+use crate::gear::{
+    assignment::{作業內容, 對照輸入碼, 推進方式, 步進法, 解析碼表},
+    caption::{前序段落, 字幕段落, 字幕表示, 生成分段字幕, 當前段落},
+    chord::{並擊判定, 並擊狀態},
+    key_press::連擊狀態,
+    layout::盤面選擇碼,
+};
+use crate::key_code::KeyCode;
+use crate::spelling_algebra::施展拼寫運算;
+
+/// 一次擊鍵對作業的影響
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct 擊鍵反饋 {
+    /// 作業完成後繼續擊鍵, 作業內容已清除, 轉入自習
+    pub 轉入自習: bool,
+    /// 錄入了一個字根 (連擊), 或完成了一次並擊
+    pub 擊發: bool,
+    /// 擊發所得與目標輸入碼不合
+    pub 擊鍵有誤: bool,
+    /// 擊中目標輸入碼, 作業推進一步
+    pub 作業推進: bool,
+    /// 按 [`並擊判定::標記非正規`] 判爲擊中, 但所按並擊不是反查所得的正規並擊
+    pub 非正規並擊: bool,
+    /// 擊中或跳過了末一片段, 一題打完. 分段的作業隨即迴轉到開頭
+    pub 打完一題: bool,
+}
+
+/// 錄入時按下功能鍵的結果. 開啓選單等核心以外的事, 交還調用方
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum 功能鍵反饋 {
+    非功能鍵,
+    已處理,
+    開啓練習題選單,
+    開啓反查輸入,
+    開啓方案選單,
+}
+
+pub struct 引擎核心 {
+    方案: 輸入方案定義<'static>,
+    作業內容: Option<作業內容<'static>>,
+    輸入碼序列: Box<[對照輸入碼]>,
+    分段字幕: Box<[字幕段落<'static>]>,
+    作業進度: usize,
+    推進方式: 推進方式,
+    /// 按 [`推進方式::記錯推進`] 擊錯而跳過的片段
+    擊錯片段: BTreeSet<usize>,
+    /// 末一片段已打完, 尚未再擊鍵
+    一題打完: bool,
+    並擊狀態: 並擊狀態,
+    並擊判定: 並擊判定,
+    連擊狀態: 連擊狀態,
+    連擊輸入碼: Vec<String>,
+    連擊進度: usize,
+    當選盤面: 盤面選擇碼,
+}
+
+impl 引擎核心 {
+    pub fn new(方案: 輸入方案定義<'static>) -> Self {
+        Self {
+            當選盤面: 方案.佈局.默認盤面,
+            方案,
+            作業內容: None,
+            輸入碼序列: Box::new([]),
+            分段字幕: Box::new([]),
+            作業進度: 0,
+            推進方式: 推進方式::default(),
+            擊錯片段: BTreeSet::new(),
+            一題打完: false,
+            並擊狀態: 並擊狀態::new(),
+            並擊判定: 並擊判定::default(),
+            連擊狀態: 連擊狀態::default(),
+            連擊輸入碼: vec![],
+            連擊進度: 0,
+        }
+    }
+
+    pub fn 方案(&self) -> &輸入方案定義<'static> {
+        &self.方案
+    }
+
+    pub fn 指法(&self) -> 觸鍵方式 {
+        self.方案.指法
+    }
+
+    /// 換用方案後盤面復位, 並按新方案重新解析作業
+    pub fn 切換方案(&mut self, 方案: 輸入方案定義<'static>) {
+        self.當選盤面 = 方案.佈局.默認盤面;
+        self.方案 = 方案;
+        self.解析作業();
+    }
+
+    /// 佈置新的作業內容. 目標輸入碼有變, 纔從頭開始.
+    pub fn 佈置作業(&mut self, 作業內容: Option<作業內容<'static>>) {
+        self.作業內容 = 作業內容;
+        self.解析作業();
+    }
+
+    fn 解析作業(&mut self) {
+        let 輸入碼序列 = self
+            .作業內容
+            .as_ref()
+            .map(|作業| 解析碼表(&作業.碼表, &self.方案))
+            .unwrap_or(Box::new([]));
+        self.分段字幕 = self.作業內容.as_ref().map_or_else(
+            || Box::from([]),
+            |作業| 生成分段字幕(&作業.字幕, self.方案.指法, &輸入碼序列),
+        );
+        if 輸入碼序列 != self.輸入碼序列 {
+            self.輸入碼序列 = 輸入碼序列;
+            self.重置作業進度();
+        }
+    }
+
+    pub fn 作業內容(&self) -> Option<&作業內容<'static>> {
+        self.作業內容.as_ref()
+    }
+
+    pub fn 分段字幕(&self) -> &[字幕段落<'static>] {
+        &self.分段字幕
+    }
+
+    pub fn 作業進度(&self) -> usize {
+        self.作業進度
+    }
+
+    pub fn 目標輸入碼序列(&self) -> &[對照輸入碼] {
+        &self.輸入碼序列
+    }
+
+    pub fn 目標碼表格式(&self) -> Option<碼表格式> {
+        self.作業內容.as_ref().and_then(|作業| 作業.碼表.碼表格式())
+    }
+
+    /// 當前作答的輸入碼. 全文完成時, 停留在末位.
+    pub fn 目標輸入碼片段(&self) -> Option<&對照輸入碼> {
+        if self.輸入碼序列.is_empty() {
+            None
+        } else {
+            self.輸入碼序列
+                .get(min(self.作業進度, self.輸入碼序列.len() - 1))
+        }
+    }
+
+    pub fn 有無作業(&self) -> bool {
+        !self.輸入碼序列.is_empty()
+    }
+
+    pub fn 作業進度完成(&self) -> bool {
+        self.有無作業() && self.作業進度 == self.輸入碼序列.len()
+    }
+
+    /// 打完末一片段後, 至下一次擊鍵之前爲真.
+    /// 不分段的作業此時亦 [`作業進度完成`](Self::作業進度完成); 分段的作業已迴轉到開頭.
+    pub fn 一題打完(&self) -> bool {
+        self.一題打完
+    }
+
+    pub fn 重置作業進度(&mut self) {
+        self.作業進度 = 0;
+        self.擊錯片段.clear();
+        self.一題打完 = false;
+    }
+
+    pub fn 推進方式(&self) -> 推進方式 {
+        self.推進方式
+    }
+
+    pub fn 設置推進方式(&mut self, 方式: 推進方式) {
+        self.推進方式 = 方式;
+    }
+
+    pub fn 擊錯片段(&self) -> &BTreeSet<usize> {
+        &self.擊錯片段
+    }
+
+    pub fn 作業推進(&mut self, 步進: 步進法) -> 成功失敗 {
+        let 全文長度 = self.輸入碼序列.len();
+        let 目標進度 = 步進.目標.unwrap_or(self.作業進度 + 1);
+        // 迴轉時保留擊錯記號, 待打完一題後再擊鍵, 或另行佈置作業時纔清除
+        if 步進.迴轉 && 目標進度 >= 全文長度 {
+            self.作業進度 = 0;
+            Ok(())
+        }
+        // 非迴轉態可推進至全文結束位置
+        else if 目標進度 <= 全文長度 {
+            self.作業進度 = 目標進度;
+            Ok(())
+        } else {
+            Err(未有())
+        }
+    }
+
+    pub fn 作業回退(&mut self, 步進: 步進法) -> 成功失敗 {
+        let 當前進度 = self.作業進度;
+        let 全文長度 = self.輸入碼序列.len();
+        match 步進.目標 {
+            Some(目標進度) if 步進.迴轉 || 當前進度 > 目標進度 => {
+                self.作業進度 = 目標進度;
+                Ok(())
+            }
+            None if 步進.迴轉 && 當前進度 == 0 && 全文長度 > 0 => {
+                self.作業進度 = 全文長度 - 1;
+                Ok(())
+            }
+            None if 當前進度 > 0 => {
+                self.作業進度 = 當前進度 - 1;
+                Ok(())
+            }
+            _ => Err(未有()),
+        }
+    }
+
+    pub fn 當前段落(&self) -> Option<&字幕段落<'static>> {
+        當前段落(&self.分段字幕, self.作業進度)
+    }
+
+    pub fn 前序段落(&self) -> Option<&字幕段落<'static>> {
+        前序段落(&self.分段字幕, self.作業進度)
+    }
+
+    pub fn 段落表示(&self) -> Option<字幕表示> {
+        self.當前段落()
+            .map(|段落| 段落.表示(self.作業進度, &self.擊錯片段))
+    }
+
+    pub fn 並擊狀態(&self) -> &並擊狀態 {
+        &self.並擊狀態
+    }
+
+    pub fn 重置並擊狀態(&mut self) {
+        self.並擊狀態.重置();
+    }
+
+    pub fn 實況並擊碼(&self) -> String {
+        self.方案.寫成字根碼(&self.並擊狀態.累計擊鍵)
+    }
+
+    pub fn 並擊所得拼音(&self) -> Option<String> {
+        self.方案.轉寫法.字根碼轉寫爲拼式(&self.實況並擊碼())
+    }
+
+    /// 並擊碼即並擊方案的字根碼
+    pub fn 反查所得並擊碼(&self) -> Option<String> {
+        self.反查所得字根碼()
+    }
+
+    pub fn 反查鍵位(&self) -> Option<鍵組> {
+        self.反查所得並擊碼()
+            .map(|並擊碼| self.方案.讀出鍵位(&並擊碼, &盤面選擇碼(0)))
+    }
+
+    pub fn 並擊完成(&self) -> bool {
+        self.並擊狀態.實時落鍵.0.is_empty() && !self.實況並擊碼().is_empty()
+    }
+
+    pub fn 並擊判定(&self) -> 並擊判定 {
+        self.並擊判定
+    }
+
+    pub fn 設置並擊判定(&mut self, 判定: 並擊判定) {
+        self.並擊判定 = 判定;
+    }
+
+    pub fn 並擊成功(&self) -> bool {
+        match self.並擊判定 {
+            並擊判定::同拼式 | 並擊判定::標記非正規 => self.並擊合乎拼式(),
+            並擊判定::正規並擊 => self.正規並擊(),
+        }
+    }
+
+    /// 所按並擊即反查所得的並擊碼. 目標無從反查時, 退而比較拼式.
+    pub fn 正規並擊(&self) -> bool {
+        let Some(反查所得) = self.反查所得並擊碼() else {
+            return self.並擊合乎拼式();
+        };
+        // 並擊碼的表示未必與鍵位一一對應, 鍵位相同亦可
+        反查所得 == self.實況並擊碼()
+            || self
+                .反查鍵位()
+                .is_some_and(|鍵位| 鍵位 == self.並擊狀態.累計擊鍵)
+    }
+
+    fn 並擊合乎拼式(&self) -> bool {
+        let 實況並擊碼 = self.實況並擊碼();
+        let 轉寫法 = &self.方案.轉寫法;
+        let Some(目標) = self.目標輸入碼片段() else {
+            return false;
+        };
+        // 拼音一致即爲成功，允許並擊碼不同
+        目標.字根碼原文.as_ref().is_some_and(|查得| *查得 == 實況並擊碼)
+            || 目標.轉寫碼原文.as_ref().is_some_and(|查得| {
+                self.並擊所得拼音().is_some_and(|擊得| *查得 == 擊得)
+            })
+            // 拼音爲非音節形式的聲母、韻母，須比較並擊碼
+            // 派生、模糊、縮寫所得的並擊碼亦可
+            || 目標.反查衆字根碼(轉寫法).contains(&實況並擊碼)
+            || 目標.轉寫碼原文.as_ref().is_some_and(|查得| {
+                轉寫法
+                    .字根碼轉寫爲衆拼式(&實況並擊碼)
+                    .iter()
+                    .any(|擊得| 擊得.拼式 == *查得)
+            })
+    }
+
+    pub fn 當選盤面(&self) -> 盤面選擇碼 {
+        self.當選盤面
+    }
+
+    pub fn 實況字根碼(&self) -> String {
+        self.方案.寫成字根碼(&選擇鍵面 {
+            盤面: self.當選盤面,
+            鍵碼: self.連擊狀態.鍵碼,
+        })
+    }
+
+    /// 目標輸入碼片段的正規字根碼
+    fn 反查所得字根碼(&self) -> Option<String> {
+        self.目標輸入碼片段()
+            .and_then(|對照碼| 對照碼.反查字根碼(&self.方案.轉寫法))
+    }
+
+    fn 實錄分隔鍵(&self) -> Option<&鍵位定義<'static>> {
+        let 實錄鍵碼 = self.連擊狀態.鍵碼;
+        self.方案.查分隔鍵(|鍵位| 鍵位.鍵碼 == 實錄鍵碼)
+    }
+
+    pub fn 清空連擊輸入碼(&mut self) {
+        self.連擊輸入碼.clear();
+        self.連擊進度 = self.作業進度;
+    }
+
+    pub fn 回退連擊輸入碼(&mut self) -> 成功失敗 {
+        if self.作業進度 > self.連擊進度 {
+            // 完成狀態下回退一字
+            return Err(未有());
+        }
+        self.連擊輸入碼.pop().map(|_| ()).ok_or(未有())
+    }
+
+    pub fn 已錄入字根碼(&self) -> String {
+        let 字根碼 = self.連擊輸入碼.join("");
+        施展拼寫運算(&字根碼, self.方案.轉寫法.輸入碼表示).unwrap_or(字根碼)
+    }
+
+    fn 已正確錄入碼長(&self) -> usize {
+        if self.連擊進度 != self.作業進度 {
+            return 0;
+        }
+        self.反查所得字根碼().map_or(0, |查得| {
+            查得
+                .chars()
+                .zip(self.已錄入字根碼().chars())
+                .take_while(|(查得, 錄入)| 查得 == 錄入)
+                .count()
+        })
+    }
+
+    pub fn 逐鍵提示(&self) -> Option<鍵組> {
+        self.反查所得字根碼()
+            .and_then(|字根碼| 字根碼.chars().nth(self.已正確錄入碼長()))
+            .map(|字根碼| self.方案.讀出鍵位(&字根碼.to_string(), &self.當選盤面))
+    }
+
+    fn 編碼法(&self) -> 碼表格式 {
+        self.目標碼表格式().unwrap_or(self.方案.編碼法)
+    }
+
+    pub fn 連擊比對成功(&self) -> bool {
+        let 字根碼 = self.實錄分隔鍵().map_or_else(
+            || match self.編碼法() {
+                碼表格式::逐鍵 => self.實況字根碼(),
+                碼表格式::連擊 => self.已錄入字根碼(),
+                _ => "".to_string(),
+            },
+            |分隔鍵| 分隔鍵.輸入碼.to_string(),
+        );
+        self.反查所得字根碼().is_some_and(|查得| 查得 == 字根碼)
+    }
+
+    /// 連擊所錄不是目標輸入碼的前綴, 或逐鍵所擊不是目標鍵
+    fn 連擊有誤(&self) -> bool {
+        let Some(查得) = self.反查所得字根碼() else {
+            return false;
+        };
+        match self.編碼法() {
+            碼表格式::逐鍵 => 查得 != self.實況字根碼(),
+            碼表格式::連擊 => !查得.starts_with(&self.已錄入字根碼()),
+            _ => false,
+        }
+    }
+
+    fn 完成一詞(&self) -> bool {
+        self.作業進度完成()
+            || self
+                .目標輸入碼片段()
+                .and_then(|對照碼| 對照碼.轉寫碼原文.as_deref())
+                .is_some_and(|轉寫碼| 轉寫碼 == " ")
+    }
+
+    pub fn 連擊片段完成(&self) -> bool {
+        match self.編碼法() {
+            碼表格式::逐鍵 => self.完成一詞(),
+            碼表格式::連擊 => self.作業進度 > self.連擊進度,
+            _ => false,
+        }
+    }
+
+    fn 位於碼段邊界(&self) -> bool {
+        self.連擊輸入碼.last().is_some_and(|末位輸入碼| {
+            self.方案
+                .查分隔鍵(|鍵位| 鍵位.輸入碼 == 末位輸入碼)
+                .is_some()
+                || self
+                    .方案
+                    .查終止鍵(|鍵位| 鍵位.輸入碼 == 末位輸入碼)
+                    .is_some()
+        })
+    }
+
+    fn 碼段起始鍵(&self, 鍵面: &選擇鍵面) -> bool {
+        self.方案.查起始鍵(|鍵位| 鍵面.包含鍵位(鍵位)).is_some()
+    }
+
+    fn 編輯連擊輸入碼(&mut self, 鍵碼: KeyCode) {
+        let 鍵面 = 選擇鍵面 {
+            盤面: self.當選盤面,
+            鍵碼,
+        };
+        let 字根碼 = self.方案.寫成字根碼(&鍵面);
+        if 字根碼.is_empty() {
+            return;
+        }
+        match self.編碼法() {
+            碼表格式::逐鍵 => {
+                let 自由輸入 = self.目標輸入碼片段().is_none();
+                if 自由輸入 || self.連擊比對成功() {
+                    if self.實錄分隔鍵().is_some() || self.位於碼段邊界() {
+                        self.清空連擊輸入碼();
+                    }
+                    self.連擊輸入碼.push(字根碼);
+                }
+            }
+            碼表格式::連擊 => {
+                if self.作業進度 > self.連擊進度
+                    || self.位於碼段邊界()
+                    || self.碼段起始鍵(&鍵面)
+                {
+                    self.清空連擊輸入碼();
+                }
+                self.連擊輸入碼.push(字根碼);
+                // 若連擊比對成功, 作業將隨即推進至下一片段
+                // 先不要清空碼段, 顯示連擊片段完成狀態, 待下一擊再清空
+            }
+            _ => (),
+        }
+    }
+
+    pub fn 自動切換盤面(&mut self) {
+        if let 碼表格式::連擊 = self.編碼法() {
+            let 輸入碼 = self.已錄入字根碼();
+            self.當選盤面 = self
+                .方案
+                .動態切換
+                .iter()
+                .find_map(|規則| 規則.匹配輸入.is_match(&輸入碼).then_some(規則.目標盤面))
+                .unwrap_or(self.方案.佈局.默認盤面);
+        }
+    }
+
+    pub fn 重置輸入狀態(&mut self) {
+        match self.指法() {
+            觸鍵方式::連擊 => self.清空連擊輸入碼(),
+            觸鍵方式::並擊 => self.重置並擊狀態(),
+        }
+        self.自動切換盤面();
+    }
+
+    pub fn 擊中目標(&self) -> bool {
+        match self.指法() {
+            觸鍵方式::連擊 => self.連擊比對成功(),
+            觸鍵方式::並擊 => self.並擊完成() && self.並擊成功(),
+        }
+    }
+
+    fn 分段落則迴轉(&self) -> 步進法 {
+        步進法 {
+            目標: None,
+            迴轉: self.分段字幕.len() > 1,
+        }
+    }
+
+    fn 批閱作業(&mut self) -> bool {
+        // 擊中目標輸入碼後反查下一個輸入碼
+        let 進度 = self.作業進度;
+        let 擊中 = self.擊中目標() && self.作業推進(self.分段落則迴轉()).is_ok();
+        if 擊中 {
+            self.擊錯片段.remove(&進度);
+            self.記下打完一題(進度);
+        }
+        擊中
+    }
+
+    /// 推進前的進度爲末一片段, 則一題打完
+    fn 記下打完一題(&mut self, 進度: usize) {
+        if 進度 + 1 == self.輸入碼序列.len() {
+            self.一題打完 = true;
+        }
+    }
+
+    /// 連擊所錄已與目標等長而仍不合, 或逐鍵擊錯, 這一片段便算打完了
+    fn 連擊片段打完(&self) -> bool {
+        match self.編碼法() {
+            碼表格式::逐鍵 => true,
+            碼表格式::連擊 => self
+                .反查所得字根碼()
+                .is_some_and(|查得| self.已錄入字根碼().chars().count() >= 查得.chars().count()),
+            _ => false,
+        }
+    }
+
+    /// 按記錯推進的方式, 擊錯亦推進到下一片段, 並記下擊錯的片段
+    fn 記錯推進(&mut self) -> bool {
+        if self.推進方式 != 推進方式::記錯推進 || !self.有無作業() || self.作業進度完成()
+        {
+            return false;
+        }
+        let 進度 = self.作業進度;
+        if self.作業推進(self.分段落則迴轉()).is_err() {
+            return false;
+        }
+        // 末一片段擊錯而迴轉到開頭, 亦須記下
+        self.擊錯片段.insert(進度);
+        self.記下打完一題(進度);
+        true
+    }
+
+    fn 另起一段(&self) -> bool {
+        self.當前段落()
+            .is_some_and(|字幕段落(段落起始, _, _)| self.作業進度 == *段落起始)
+    }
+
+    /// 錄入狀態下按下一鍵
+    pub fn 落鍵(&mut self, 鍵碼: KeyCode) -> 擊鍵反饋 {
+        let mut 反饋 = 擊鍵反饋::default();
+        // 繼續擊鍵時消除已完成的反查作業
+        if self.作業進度完成() {
+            self.佈置作業(None);
+            反饋.轉入自習 = true;
+        }
+        // 上一輪已打完, 此擊開始新的一輪
+        if self.一題打完 {
+            self.擊錯片段.clear();
+            self.一題打完 = false;
+        }
+        self.並擊狀態.落鍵(鍵碼);
+        if self.指法() == 觸鍵方式::連擊 {
+            self.連擊狀態.擊發(鍵碼);
+            self.編輯連擊輸入碼(鍵碼);
+            反饋.擊發 = !self.實況字根碼().is_empty();
+            反饋.作業推進 = self.批閱作業();
+            反饋.擊鍵有誤 = 反饋.擊發 && !反饋.作業推進 && self.連擊有誤();
+            if 反饋.擊鍵有誤 && self.連擊片段打完() && self.記錯推進() {
+                反饋.作業推進 = true;
+                self.清空連擊輸入碼();
+            }
+            if 反饋.作業推進 && self.另起一段() {
+                self.清空連擊輸入碼();
+            }
+            self.自動切換盤面();
+            反饋.打完一題 = self.一題打完;
+        }
+        反饋
+    }
+
+    /// 錄入狀態下抬起一鍵
+    pub fn 抬鍵(&mut self, 鍵碼: KeyCode) -> 擊鍵反饋 {
+        let mut 反饋 = 擊鍵反饋::default();
+        self.並擊狀態.抬鍵(鍵碼);
+        match self.指法() {
+            觸鍵方式::連擊 => {
+                // 顯示並擊動態, 抬鍵後清除並擊結果
+                if self.並擊完成() {
+                    self.重置並擊狀態();
+                }
+            }
+            觸鍵方式::並擊 => {
+                反饋.擊發 = self.並擊完成();
+                // 須在推進之前比對, 推進後目標已是下一題
+                let 正規並擊 = self.正規並擊();
+                // 推進到下一題時, 清除上一題的並擊結果
+                // 但在最後一題完成後停下顯示結果
+                反饋.作業推進 = self.批閱作業();
+                反饋.非正規並擊 =
+                    反饋.作業推進 && self.並擊判定 == 並擊判定::標記非正規 && !正規並擊;
+                反饋.擊鍵有誤 = 反饋.擊發 && !反饋.作業推進 && self.有無作業();
+                if 反饋.擊鍵有誤 && self.記錯推進() {
+                    反饋.作業推進 = true;
+                }
+                反饋.打完一題 = 反饋.作業推進 && self.一題打完;
+                if 反饋.作業推進 && !self.作業進度完成() {
+                    self.重置並擊狀態();
+                }
+            }
+        }
+        反饋
+    }
+
+    /// 退格: 刪去一個字根碼, 或退回上一題
+    pub fn 退格(&mut self) {
+        self.一題打完 = false;
+        match self.指法() {
+            觸鍵方式::連擊 => {
+                if self.回退連擊輸入碼().is_err() && self.有無作業() {
+                    let _不看結果 = self.作業回退(步進法::default());
+                    self.重置輸入狀態();
+                }
+            }
+            觸鍵方式::並擊 => {
+                if self.並擊完成() || self.作業回退(步進法::default()).is_ok() {
+                    self.重置並擊狀態();
+                }
+            }
+        }
+    }
+
+    /// 跳至下一段, 或回到上一段; 首尾相接
+    pub fn 跳轉段落(&mut self, 回到前段: bool) -> 成功失敗 {
+        let 跳轉結果 = if 回到前段 {
+            let 目標 = self
+                .前序段落()
+                .or_else(|| self.分段字幕.last())
+                .map(|字幕段落(起, _, _)| *起);
+            self.作業回退(步進法 {
+                目標, 迴轉: true
+            })
+        } else {
+            let 目標 = self.當前段落().map(|字幕段落(_, 止, _)| *止);
+            self.作業推進(步進法 {
+                目標, 迴轉: true
+            })
+        };
+        if 跳轉結果.is_ok() {
+            self.一題打完 = false;
+            self.重置輸入狀態();
+        }
+        跳轉結果
+    }
+
+    /// 錄入時的功能鍵: 退出鍵重置作業進度, 已在開頭則開啓練習題選單;
+    /// 製表鍵跳轉段落, 上檔時回到前段; 退格鍵退格
+    pub fn 功能鍵(&mut self, 鍵碼: KeyCode, 上檔: bool) -> 功能鍵反饋 {
+        match 鍵碼 {
+            KeyCode::Escape => {
+                if self.作業進度() == 0 {
+                    return 功能鍵反饋::開啓練習題選單;
+                }
+                self.重置作業進度();
+                self.重置輸入狀態();
+                功能鍵反饋::已處理
+            }
+            KeyCode::Tab => {
+                let _不看結果 = self.跳轉段落(上檔);
+                功能鍵反饋::已處理
+            }
+            KeyCode::Backspace => {
+                self.退格();
+                功能鍵反饋::已處理
+            }
+            KeyCode::Enter => 功能鍵反饋::開啓反查輸入,
+            KeyCode::Grave => 功能鍵反饋::開啓方案選單,
+            _ => 功能鍵反饋::非功能鍵,
+        }
+    }
+}
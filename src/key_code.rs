@@ -1,9 +1,11 @@
 // Stolen from https://github.com/HaoboGu/rmk
 // Included under The MIT License (https://github.com/HaoboGu/rmk/blob/main/LICENSE-MIT)
+use serde::{Deserialize, Serialize};
+
 /// KeyCode is the internal representation of all keycodes, keyboard operations, etc.
 #[allow(dead_code)]
 #[repr(u16)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum KeyCode {
     /// Reserved, no-key.
     No = 0x0000,
@@ -420,6 +422,9 @@ pub fn 網頁鍵值轉換(鍵值: &str) -> KeyCode {
         "BracketLeft" => KeyCode::LeftBracket,
         "BracketRight" => KeyCode::RightBracket,
         "Backslash" => KeyCode::Backslash,
+        "IntlBackslash" => KeyCode::NonusBackslash,
+        "IntlRo" => KeyCode::International1,
+        "IntlYen" => KeyCode::International3,
         "Space" => KeyCode::Space,
         "Escape" => KeyCode::Escape,
         "Tab" => KeyCode::Tab,
@@ -428,3 +433,124 @@ pub fn 網頁鍵值轉換(鍵值: &str) -> KeyCode {
         _ => KeyCode::No,
     }
 }
+
+/// 網頁鍵值轉換的逆運算, 僅限字母數字區字符鍵
+pub fn 鍵碼網頁鍵值轉換(鍵碼: KeyCode) -> Option<&'static str> {
+    [
+        "KeyA",
+        "KeyB",
+        "KeyC",
+        "KeyD",
+        "KeyE",
+        "KeyF",
+        "KeyG",
+        "KeyH",
+        "KeyI",
+        "KeyJ",
+        "KeyK",
+        "KeyL",
+        "KeyM",
+        "KeyN",
+        "KeyO",
+        "KeyP",
+        "KeyQ",
+        "KeyR",
+        "KeyS",
+        "KeyT",
+        "KeyU",
+        "KeyV",
+        "KeyW",
+        "KeyX",
+        "KeyY",
+        "KeyZ",
+        "Digit0",
+        "Digit1",
+        "Digit2",
+        "Digit3",
+        "Digit4",
+        "Digit5",
+        "Digit6",
+        "Digit7",
+        "Digit8",
+        "Digit9",
+        "Semicolon",
+        "Comma",
+        "Period",
+        "Slash",
+        "Backquote",
+        "Quote",
+        "Minus",
+        "Equal",
+        "BracketLeft",
+        "BracketRight",
+        "Backslash",
+        "IntlBackslash",
+        "IntlRo",
+        "IntlYen",
+        "Space",
+    ]
+    .into_iter()
+    .find(|&鍵值| 網頁鍵值轉換(鍵值) == 鍵碼)
+}
+
+/// 主鍵盤區按鍵在美式鍵盤上輸入的字符, 如 Rime 並擊字母表所用
+pub fn 字符鍵值轉換(字符: char) -> KeyCode {
+    match 字符 {
+        'a' => KeyCode::A,
+        'b' => KeyCode::B,
+        'c' => KeyCode::C,
+        'd' => KeyCode::D,
+        'e' => KeyCode::E,
+        'f' => KeyCode::F,
+        'g' => KeyCode::G,
+        'h' => KeyCode::H,
+        'i' => KeyCode::I,
+        'j' => KeyCode::J,
+        'k' => KeyCode::K,
+        'l' => KeyCode::L,
+        'm' => KeyCode::M,
+        'n' => KeyCode::N,
+        'o' => KeyCode::O,
+        'p' => KeyCode::P,
+        'q' => KeyCode::Q,
+        'r' => KeyCode::R,
+        's' => KeyCode::S,
+        't' => KeyCode::T,
+        'u' => KeyCode::U,
+        'v' => KeyCode::V,
+        'w' => KeyCode::W,
+        'x' => KeyCode::X,
+        'y' => KeyCode::Y,
+        'z' => KeyCode::Z,
+        '0' => KeyCode::Kc0,
+        '1' => KeyCode::Kc1,
+        '2' => KeyCode::Kc2,
+        '3' => KeyCode::Kc3,
+        '4' => KeyCode::Kc4,
+        '5' => KeyCode::Kc5,
+        '6' => KeyCode::Kc6,
+        '7' => KeyCode::Kc7,
+        '8' => KeyCode::Kc8,
+        '9' => KeyCode::Kc9,
+        ';' => KeyCode::Semicolon,
+        ',' => KeyCode::Comma,
+        '.' => KeyCode::Dot,
+        '/' => KeyCode::Slash,
+        '`' => KeyCode::Grave,
+        '\'' => KeyCode::Quote,
+        '-' => KeyCode::Minus,
+        '=' => KeyCode::Equal,
+        '[' => KeyCode::LeftBracket,
+        ']' => KeyCode::RightBracket,
+        '\\' => KeyCode::Backslash,
+        ' ' => KeyCode::Space,
+        _ => KeyCode::No,
+    }
+}
+
+/// 字符鍵值轉換的逆運算
+pub fn 鍵值字符轉換(鍵碼: KeyCode) -> Option<char> {
+    "abcdefghijklmnopqrstuvwxyz0123456789;,./`'-=[]\\ "
+        .chars()
+        .find(|&字符| 字符鍵值轉換(字符) == 鍵碼)
+}
@@ -0,0 +1,206 @@
+//! 導入 Keyboard Layout Editor (keyboard-layout-editor.com) 的配列文件
+//!
+//! 文件是 JSON 數組, 除開頭可有的說明對象外, 每項是一行鍵.
+//! 行中的字符串是一個鍵的刻印, 對象則設定其後各鍵的位置、大小及轉角.
+//! 按刻印認出鍵碼, 認不出的鍵照畫, 不作輸入.
+
+use serde_json::Value;
+
+use crate::gear::geometry::{配列幾何, 鍵位幾何};
+use crate::gear::layout::配列;
+use crate::key_code::{字符鍵值轉換, KeyCode};
+
+#[derive(Debug)]
+pub enum 配列文件錯誤 {
+    格式有誤(serde_json::Error),
+    未知內容(String),
+    未有按鍵,
+}
+
+impl std::fmt::Display for 配列文件錯誤 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::格式有誤(錯誤) => write!(f, "配列文件格式有誤: {錯誤}"),
+            Self::未知內容(內容) => write!(f, "配列文件中有不能識別的內容: {內容}"),
+            Self::未有按鍵 => write!(f, "配列文件中未有按鍵"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for 配列文件錯誤 {
+    fn from(錯誤: serde_json::Error) -> Self {
+        Self::格式有誤(錯誤)
+    }
+}
+
+/// 上檔字符及其所在的鍵在美式鍵盤上的下檔字符
+const 上檔字符: &[(char, char)] = &[
+    ('~', '`'),
+    ('!', '1'),
+    ('@', '2'),
+    ('#', '3'),
+    ('$', '4'),
+    ('%', '5'),
+    ('^', '6'),
+    ('&', '7'),
+    ('*', '8'),
+    ('(', '9'),
+    (')', '0'),
+    ('_', '-'),
+    ('+', '='),
+    ('{', '['),
+    ('}', ']'),
+    ('|', '\\'),
+    (':', ';'),
+    ('"', '\''),
+    ('<', ','),
+    ('>', '.'),
+    ('?', '/'),
+];
+
+/// 日式鍵盤特有的鍵. 其刻印中的 `|`、`_` 在美式鍵盤上另有所在, 故須先認這些刻印.
+fn 日式刻印鍵碼(刻印: &str) -> Option<KeyCode> {
+    match 刻印 {
+        "¥" => Some(KeyCode::International3),
+        "ろ" => Some(KeyCode::International1),
+        _ => None,
+    }
+}
+
+fn 單個刻印鍵碼(刻印: &str) -> KeyCode {
+    match 刻印.to_lowercase().as_str() {
+        "esc" | "escape" => return KeyCode::Escape,
+        "tab" | "⇥" | "↹" => return KeyCode::Tab,
+        "backspace" | "back space" | "bksp" | "⌫" => return KeyCode::Backspace,
+        "enter" | "return" | "⏎" | "↵" | "⌤" => return KeyCode::Enter,
+        "space" | "spacebar" | "␣" => return KeyCode::Space,
+        _ => {}
+    }
+    let mut 衆字符 = 刻印.chars();
+    match (衆字符.next(), 衆字符.next()) {
+        (Some(字符), None) => {
+            let 下檔 = 上檔字符
+                .iter()
+                .find(|&&(上檔, _)| 上檔 == 字符)
+                .map_or(字符, |&(_, 下檔)| 下檔);
+            字符鍵值轉換(下檔.to_ascii_lowercase())
+        }
+        _ => KeyCode::No,
+    }
+}
+
+/// 按刻印認出鍵碼. 刻印以換行分隔各處的文字, 有日式鍵盤特有的刻印則以之爲準,
+/// 否則取第一個認得的. 無刻印的長鍵當作空格.
+pub fn 刻印鍵碼(刻印: &str, 寬: f64) -> KeyCode {
+    let 衆文字 = 刻印
+        .split('\n')
+        .map(str::trim)
+        .filter(|文字| !文字.is_empty());
+    let 鍵碼 = 衆文字
+        .clone()
+        .find_map(日式刻印鍵碼)
+        .or_else(|| 衆文字.map(單個刻印鍵碼).find(|&鍵碼| 鍵碼 != KeyCode::No));
+    match 鍵碼 {
+        Some(鍵碼) => 鍵碼,
+        None if 刻印.trim().is_empty() && 寬 >= 3.0 => KeyCode::Space,
+        None => KeyCode::No,
+    }
+}
+
+/// 逐鍵推算位置時的當前狀態, 規則同 Keyboard Layout Editor
+struct 游標 {
+    橫: f64,
+    縱: f64,
+    寬: f64,
+    高: f64,
+    轉角: f64,
+    旋轉中心: (f64, f64),
+    /// 裝飾用的鍵, 不畫
+    裝飾: bool,
+}
+
+impl Default for 游標 {
+    fn default() -> Self {
+        Self {
+            橫: 0.0,
+            縱: 0.0,
+            寬: 1.0,
+            高: 1.0,
+            轉角: 0.0,
+            旋轉中心: (0.0, 0.0),
+            裝飾: false,
+        }
+    }
+}
+
+fn 讀數值(屬性: &serde_json::Map<String, Value>, 名稱: &str) -> Option<f64> {
+    屬性.get(名稱).and_then(Value::as_f64)
+}
+
+pub fn 載入配列文件(文本: &str) -> Result<配列幾何, 配列文件錯誤> {
+    let 文件 = serde_json::from_str::<Vec<Value>>(文本)?;
+    let mut 游標 = 游標::default();
+    let mut 衆鍵位 = Vec::new();
+    for 行 in 文件 {
+        // 開頭的說明對象
+        let Value::Array(行) = 行 else {
+            continue;
+        };
+        for 項 in 行 {
+            match 項 {
+                Value::String(刻印) => {
+                    if !游標.裝飾 {
+                        let 鍵碼 = 刻印鍵碼(&刻印, 游標.寬);
+                        衆鍵位.push(鍵位幾何 {
+                            鍵碼,
+                            刻印座標: 配列::主鍵盤區.鍵碼刻印座標(鍵碼),
+                            左: 游標.橫,
+                            上: 游標.縱,
+                            寬: 游標.寬,
+                            高: 游標.高,
+                            轉角: 游標.轉角,
+                            旋轉中心: 游標.旋轉中心,
+                            拇指鍵: 鍵碼 == KeyCode::Space,
+                        });
+                    }
+                    游標.橫 += 游標.寬;
+                    游標.寬 = 1.0;
+                    游標.高 = 1.0;
+                    游標.裝飾 = false;
+                }
+                Value::Object(屬性) => {
+                    if let Some(轉角) = 讀數值(&屬性, "r") {
+                        游標.轉角 = 轉角;
+                    }
+                    // 換了旋轉中心, 座標從旋轉中心算起
+                    if let Some(中心橫) = 讀數值(&屬性, "rx") {
+                        游標.旋轉中心.0 = 中心橫;
+                        (游標.橫, 游標.縱) = 游標.旋轉中心;
+                    }
+                    if let Some(中心縱) = 讀數值(&屬性, "ry") {
+                        游標.旋轉中心.1 = 中心縱;
+                        (游標.橫, 游標.縱) = 游標.旋轉中心;
+                    }
+                    游標.橫 += 讀數值(&屬性, "x").unwrap_or_default();
+                    游標.縱 += 讀數值(&屬性, "y").unwrap_or_default();
+                    if let Some(寬) = 讀數值(&屬性, "w") {
+                        游標.寬 = 寬;
+                    }
+                    if let Some(高) = 讀數值(&屬性, "h") {
+                        游標.高 = 高;
+                    }
+                    if let Some(裝飾) = 屬性.get("d").and_then(Value::as_bool) {
+                        游標.裝飾 = 裝飾;
+                    }
+                }
+                其他 => return Err(配列文件錯誤::未知內容(其他.to_string())),
+            }
+        }
+        游標.縱 += 1.0;
+        游標.橫 = 游標.旋轉中心.0;
+    }
+    if 衆鍵位.is_empty() {
+        return Err(配列文件錯誤::未有按鍵);
+    }
+    Ok(配列幾何::new(衆鍵位))
+}
@@ -0,0 +1,20 @@
+#![feature(type_alias_impl_trait)]
+#![allow(confusable_idents)]
+#![allow(uncommon_codepoints)]
+
+pub mod action;
+pub mod app;
+pub mod app_state;
+pub mod definition;
+pub mod drill;
+pub mod engine;
+pub mod gear;
+pub mod headless;
+pub mod key_code;
+pub mod kle;
+pub mod rime_schema;
+pub mod spelling_algebra;
+pub mod theory;
+pub mod theory_audit;
+pub mod theory_file;
+pub mod view;
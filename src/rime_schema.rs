@@ -0,0 +1,285 @@
+//! Rime 並擊方案導入導出
+//!
+//! 讀取 Rime 方案文件 `*.schema.yaml` 中的 `chord_composer` 設定, 生成以並擊指法輸入的方案.
+//! - alphabet :: 並擊字母表, 按字母表次序排列的按鍵構成字根表
+//! - algebra :: 並擊碼轉換爲拼式的運算
+//! - output_format :: 對運算結果的格式化, 結尾的音節分隔符將被去除
+//! - prompt_format :: 僅用於 Rime 輸入提示, 打字機另有顯示法, 不予採用
+//!
+//! Rime 只定義由並擊碼求拼式的運算, 沒有反向拆分拼式的規則,
+//! 因此導入的方案無法反查並擊碼及提示鍵位, 練習時僅比對並擊所得的拼式.
+//!
+//! 反之, 以打字機中的並擊方案爲準, 可導出 Rime 輸入法所用的設定.
+
+use lazy_regex::{regex, Regex};
+use maybe_owned::MaybeOwned;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use strum::IntoEnumIterator;
+
+use crate::definition::{
+    碼表格式, 觸鍵方式, 輸入方案定義, 轉寫法定義, 邊界判定規則, 鍵位定義
+};
+use crate::gear::layout::{
+    刻印說明, 基本盤面, 盤面定義, 盤面選擇碼, 矩陣座標, 配列, 鍵盤佈局, 鍵面刻印,
+};
+use crate::key_code::{字符鍵值轉換, 鍵值字符轉換, KeyCode};
+use crate::spelling_algebra::{拼寫運算, 解析拼寫運算};
+use crate::theory_file::{常駐, 常駐文字, 方案文件錯誤};
+
+#[derive(Deserialize, Serialize)]
+struct Rime方案文件 {
+    #[serde(rename = "schema", default)]
+    方案信息: Rime方案信息,
+    #[serde(rename = "chord_composer")]
+    並擊設定: Rime並擊設定,
+    #[serde(rename = "speller", default)]
+    拼寫設定: Rime拼寫設定,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+struct Rime方案信息 {
+    #[serde(rename = "schema_id")]
+    方案代號: String,
+    #[serde(rename = "name")]
+    名稱: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Rime並擊設定 {
+    #[serde(rename = "alphabet")]
+    字母表: String,
+    #[serde(rename = "algebra", default)]
+    拼寫運算: Vec<String>,
+    #[serde(rename = "output_format", default)]
+    輸出格式: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+struct Rime拼寫設定 {
+    #[serde(rename = "delimiter")]
+    分隔符: String,
+}
+
+impl Default for Rime拼寫設定 {
+    fn default() -> Self {
+        // Rime 默認的音節分隔符
+        Rime拼寫設定 {
+            分隔符: " '".to_owned(),
+        }
+    }
+}
+
+pub fn 是否含並擊設定(文件: &serde_yaml::Value) -> bool {
+    文件.get("chord_composer").is_some()
+}
+
+pub fn 導入並擊方案(
+    文件: serde_yaml::Value,
+) -> Result<輸入方案定義<'static>, 方案文件錯誤> {
+    serde_yaml::from_value::<Rime方案文件>(文件)?.生成方案定義()
+}
+
+impl Rime方案文件 {
+    fn 生成方案定義(self) -> Result<輸入方案定義<'static>, 方案文件錯誤> {
+        let mut 衆運算 = self
+            .並擊設定
+            .拼寫運算
+            .iter()
+            .chain(&self.並擊設定.輸出格式)
+            .map(|規則| 解析拼寫運算(規則))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 轉寫運算逐字作用, 起首的轉寫運算可先施於字母表, 使字根碼顯示爲轉寫後的形式
+        let mut 衆字根 = self
+            .並擊設定
+            .字母表
+            .chars()
+            .map(|字符| (字符, 字符))
+            .collect::<Vec<_>>();
+        while let Some(拼寫運算::轉寫 { 字符映射 }) = 衆運算.first() {
+            for (_, 字根) in 衆字根.iter_mut() {
+                if let Some(&轉寫字符) = 字符映射.get(字根) {
+                    *字根 = 轉寫字符;
+                }
+            }
+            衆運算.remove(0);
+        }
+
+        if !self.拼寫設定.分隔符.is_empty() {
+            let 模式 = format!("[{}]+$", regex::escape(&self.拼寫設定.分隔符));
+            衆運算.push(拼寫運算::變換 {
+                模式: MaybeOwned::Owned(Regex::new(&模式)?),
+                替換文字: Cow::Borrowed(""),
+            });
+        }
+
+        let 字根表 = 常駐(
+            衆字根
+                .into_iter()
+                .map(|(字符, 字根)| {
+                    let 鍵碼 = 字符鍵值轉換(字符);
+                    if 鍵碼 == KeyCode::No {
+                        return Err(方案文件錯誤::未知按鍵(字符));
+                    }
+                    Ok(鍵位定義 {
+                        輸入碼: 常駐文字(字根.to_string()),
+                        盤面: 盤面選擇碼(0),
+                        鍵碼,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        );
+
+        let 佈局 = Box::leak(Box::new(鍵盤佈局 {
+            盤面: 常駐(vec![基本盤面, 生成並擊盤面(字根表)]),
+            默認盤面: 盤面選擇碼(1),
+            首選配列: 配列::主鍵盤區,
+        }));
+
+        let 名稱 = [self.方案信息.名稱, self.方案信息.方案代號]
+            .into_iter()
+            .find(|名稱| !名稱.is_empty())
+            .unwrap_or_else(|| "Rime 並擊方案".to_owned());
+
+        Ok(輸入方案定義 {
+            名稱: 常駐文字(名稱),
+            佈局,
+            指法: 觸鍵方式::並擊,
+            編碼法: 碼表格式::並擊,
+            字根表,
+            轉寫法: 轉寫法定義 {
+                輸入碼表示: &[],
+                輸入碼鍵位: &[],
+                拼式轉寫規則: 常駐(衆運算),
+                字根拆分規則: &[],
+                拼式驗證規則: &[],
+                邊界判定: 邊界判定規則 {
+                    分隔鍵: &[],
+                    起始鍵: &[],
+                    終止鍵: &[],
+                },
+            },
+            動態切換: &[],
+        })
+    }
+}
+
+/// 在各種配列的矩陣中標出字根所在的鍵位, 字母表以外的鍵位留空
+fn 生成並擊盤面(字根表: &'static [鍵位定義<'static>]) -> 盤面定義<'static> {
+    let mut 衆行 = 基本盤面
+        .iter()
+        .map(|此行| vec![鍵面刻印::無刻; 此行.len()])
+        .collect::<Vec<_>>();
+    for 配列 in 配列::iter() {
+        for (行, 此行) in 配列.矩陣().iter().enumerate() {
+            for (列, 鍵碼) in 此行.iter().enumerate() {
+                let 字根 = 字根表.iter().find(|字根| 字根.鍵碼 == *鍵碼);
+                let 鍵面 = 配列
+                    .刻印座標(行, 列)
+                    .and_then(|矩陣座標(行, 列)| 衆行.get_mut(行)?.get_mut(列));
+                if let (Some(字根), Some(鍵面)) = (字根, 鍵面) {
+                    *鍵面 = 鍵面刻印::有刻(刻印說明::居中(字根.輸入碼));
+                }
+            }
+        }
+    }
+    常駐(衆行.into_iter().map(常駐).collect())
+}
+
+/// 導出 Rime 方案文件中的 `chord_composer` 及 `speller` 設定
+///
+/// 字根表中的鍵位按次序構成並擊字母表, 再將字母轉寫爲字根;
+/// 輸入碼表示、拼式轉寫規則依次寫成 algebra, 音節結尾補上分隔符.
+pub fn 導出並擊方案(方案: &輸入方案定義, 方案代號: &str) -> String {
+    let mut 衆鍵位 = Vec::<(char, &str)>::new();
+    for 鍵位 in 方案.字根表 {
+        if let Some(字符) = 鍵值字符轉換(鍵位.鍵碼) {
+            if !衆鍵位.iter().any(|&(已有字符, _)| 已有字符 == 字符) {
+                衆鍵位.push((字符, 鍵位.輸入碼));
+            }
+        }
+    }
+    let 字母表 = 衆鍵位.iter().map(|&(字符, _)| 字符).collect::<String>();
+
+    let mut 衆運算式 = Vec::new();
+    if 衆鍵位.iter().any(|&(字符, 字根)| 字根 != 字符.to_string()) {
+        if 衆鍵位.iter().all(|&(_, 字根)| 字根.chars().count() == 1) {
+            let 字根表 = 衆鍵位.iter().map(|&(_, 字根)| 字根).collect::<String>();
+            衆運算式.push(寫成運算式("xlit", &[&字母表, &字根表]));
+        } else {
+            // 多字符的字根無法逐字轉寫
+            衆運算式.extend(衆鍵位.iter().map(|&(字符, 字根)| {
+                寫成運算式("xform", &[&regex::escape(&字符.to_string()), 字根])
+            }));
+        }
+    }
+    衆運算式.extend(
+        方案
+            .轉寫法
+            .輸入碼表示
+            .iter()
+            .chain(方案.轉寫法.拼式轉寫規則)
+            .map(導出拼寫運算),
+    );
+
+    let 方案文件 = Rime方案文件 {
+        方案信息: Rime方案信息 {
+            方案代號: 方案代號.to_owned(),
+            名稱: 方案.名稱.to_owned(),
+        },
+        並擊設定: Rime並擊設定 {
+            字母表,
+            拼寫運算: 衆運算式,
+            輸出格式: vec![寫成運算式("xform", &["^(.+)$", "${1}'"])],
+        },
+        拼寫設定: Rime拼寫設定::default(),
+    };
+    serde_yaml::to_string(&方案文件).unwrap_or_default()
+}
+
+fn 導出拼寫運算(運算: &拼寫運算) -> String {
+    match 運算 {
+        拼寫運算::變換 {
+            模式, 替換文字
+        } => 寫成運算式("xform", &[模式.as_str(), 替換文字]),
+        拼寫運算::轉寫 { 字符映射 } => {
+            let mut 衆映射 = 字符映射.iter().collect::<Vec<_>>();
+            衆映射.sort();
+            let 左字表 = 衆映射.iter().map(|&(左, _)| 左).collect::<String>();
+            let 右字表 = 衆映射.iter().map(|&(_, 右)| 右).collect::<String>();
+            寫成運算式("xlit", &[&左字表, &右字表])
+        }
+        // Rime 以模式匹配整個拼式, 而本品只要匹配拼式的一部分就消除
+        拼寫運算::消除 { 模式 } => {
+            let 模式 = 模式.as_str();
+            if 模式.starts_with('^') && 模式.ends_with('$') && !模式.ends_with("\\$") {
+                寫成運算式("erase", &[模式])
+            } else {
+                寫成運算式("erase", &[&format!(".*(?:{模式}).*")])
+            }
+        }
+        拼寫運算::派生 {
+            類別,
+            模式,
+            替換文字,
+        } => 寫成運算式(類別.運算名(), &[模式.as_str(), 替換文字]),
+    }
+}
+
+/// 選用參數中未出現的字符爲分隔符
+fn 寫成運算式(運算名: &str, 衆參數: &[&str]) -> String {
+    let 分隔符 = ['/', '|', '#', '%', '@', '!', '~', ',']
+        .into_iter()
+        .find(|&分隔符| 衆參數.iter().all(|參數| !參數.contains(分隔符)))
+        .unwrap_or('/');
+    let mut 運算式 = 運算名.to_owned();
+    for 參數 in 衆參數 {
+        運算式.push(分隔符);
+        運算式.push_str(參數);
+    }
+    運算式.push(分隔符);
+    運算式
+}
@@ -1,11 +1,12 @@
 use lazy_regex::Regex;
 use maybe_owned::MaybeOwned;
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 pub enum 拼寫運算<'a> {
     變換 {
         模式: MaybeOwned<'a, Regex>,
-        替換文字: &'a str,
+        替換文字: Cow<'a, str>,
     },
     轉寫 {
         字符映射: HashMap<char, char>,
@@ -13,6 +14,67 @@ pub enum 拼寫運算<'a> {
     消除 {
         模式: MaybeOwned<'a, Regex>,
     },
+    /// 保留原有拼式, 另增變換所得的拼式, 僅在多值運算中生效
+    派生 {
+        類別: 派生類別,
+        模式: MaybeOwned<'a, Regex>,
+        替換文字: Cow<'a, str>,
+    },
+}
+
+/// 對應 Rime 的 `derive`, `fuzz`, `abbrev` 三種多值運算.
+/// 派生所得沿用原拼式的類別; 模糊、縮寫所得分別標作 [`拼式類別::模糊`]、[`拼式類別::縮寫`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum 派生類別 {
+    派生,
+    模糊,
+    縮寫,
+}
+
+impl 派生類別 {
+    pub fn 運算名(self) -> &'static str {
+        match self {
+            Self::派生 => "derive",
+            Self::模糊 => "fuzz",
+            Self::縮寫 => "abbrev",
+        }
+    }
+
+    pub fn 取自運算名(運算名: &str) -> Option<Self> {
+        match 運算名 {
+            "derive" => Some(Self::派生),
+            "fuzz" => Some(Self::模糊),
+            "abbrev" => Some(Self::縮寫),
+            _ => None,
+        }
+    }
+
+    /// 由 `原類別` 的拼式派生所得的拼式類別
+    fn 所得類別(self, 原類別: 拼式類別) -> 拼式類別 {
+        let 標記 = match self {
+            Self::派生 => 拼式類別::正規,
+            Self::模糊 => 拼式類別::模糊,
+            Self::縮寫 => 拼式類別::縮寫,
+        };
+        原類別.max(標記)
+    }
+}
+
+/// 多值運算所得拼式的來歷, 越靠前越正. 同一拼式有多種來歷時, 取最正的一種
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum 拼式類別 {
+    /// 原拼式, 及經 `derive` 所得
+    正規,
+    /// 經 `fuzz` 所得, 容錯之用, 不作正規拼式
+    模糊,
+    /// 經 `abbrev` 所得, 只作簡拼
+    縮寫,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct 分類拼式 {
+    pub 拼式: String,
+    pub 類別: 拼式類別,
 }
 
 #[macro_export]
@@ -20,7 +82,7 @@ macro_rules! 變換 {
     ($模式:literal, $替換文字:literal) => {
         拼寫運算::變換 {
             模式: regex!($模式).deref().into(),
-            替換文字: $替換文字,
+            替換文字: std::borrow::Cow::Borrowed($替換文字),
         }
     };
 }
@@ -43,6 +105,22 @@ macro_rules! 消除 {
     };
 }
 
+/// 缺省類別爲派生, 亦可指明, 如 `派生!(模糊, "^([zcs])h", "$1")`
+#[macro_export]
+macro_rules! 派生 {
+    ($模式:literal, $替換文字:literal) => {
+        $crate::派生!(派生, $模式, $替換文字)
+    };
+    ($類別:ident, $模式:literal, $替換文字:literal) => {
+        拼寫運算::派生 {
+            類別: $crate::spelling_algebra::派生類別::$類別,
+            模式: regex!($模式).deref().into(),
+            替換文字: std::borrow::Cow::Borrowed($替換文字),
+        }
+    };
+}
+
+/// 單值運算, 求得唯一的正規拼式. 派生、模糊、縮寫不改變拼式.
 pub fn 施展拼寫運算(原形: &str, 運算規則: &[拼寫運算]) -> Option<String> {
     if 原形.is_empty() {
         return None;
@@ -53,7 +131,7 @@ pub fn 施展拼寫運算(原形: &str, 運算規則: &[拼寫運算]) -> Option
             拼寫運算::變換 {
                 ref 模式, 替換文字
             } => {
-                運算結果 = 模式.replace_all(&運算結果, *替換文字).to_string();
+                運算結果 = 模式.replace_all(&運算結果, 替換文字.as_ref()).to_string();
             }
             拼寫運算::轉寫 { ref 字符映射 } => {
                 運算結果 = 運算結果
@@ -66,7 +144,184 @@ pub fn 施展拼寫運算(原形: &str, 運算規則: &[拼寫運算]) -> Option
                     return None;
                 }
             }
+            拼寫運算::派生 { .. } => {}
         };
     }
     (!運算結果.is_empty()).then_some(運算結果)
 }
+
+/// 多值運算, 求得一組拼式並標明類別. 正規拼式若未被消除, 則居首位;
+/// 其餘按派生次序排列, 不含重複.
+pub fn 施展多值拼寫運算(原形: &str, 運算規則: &[拼寫運算]) -> Vec<分類拼式> {
+    if 原形.is_empty() {
+        return vec![];
+    }
+    let mut 運算結果 = vec![分類拼式 {
+        拼式: 原形.to_owned(),
+        類別: 拼式類別::正規,
+    }];
+    let 逐一變換 = |衆拼式: Vec<分類拼式>, 變換: &dyn Fn(&str) -> String| {
+        去重(衆拼式.into_iter().map(|原拼式| 分類拼式 {
+            拼式: 變換(&原拼式.拼式),
+            類別: 原拼式.類別,
+        }))
+    };
+    for 運算 in 運算規則 {
+        match 運算 {
+            拼寫運算::變換 {
+                ref 模式, 替換文字
+            } => {
+                運算結果 = 逐一變換(運算結果, &|拼式| {
+                    模式.replace_all(拼式, 替換文字.as_ref()).to_string()
+                });
+            }
+            拼寫運算::轉寫 { ref 字符映射 } => {
+                運算結果 = 逐一變換(運算結果, &|拼式| {
+                    拼式
+                        .chars()
+                        .map(|字符| 字符映射.get(&字符).copied().unwrap_or(字符))
+                        .collect::<String>()
+                });
+            }
+            拼寫運算::消除 { ref 模式 } => {
+                運算結果.retain(|拼式| !模式.is_match(&拼式.拼式));
+            }
+            拼寫運算::派生 {
+                類別,
+                ref 模式,
+                替換文字,
+            } => {
+                let 派生拼式 = 運算結果
+                    .iter()
+                    .filter(|原拼式| 模式.is_match(&原拼式.拼式))
+                    .map(|原拼式| 分類拼式 {
+                        拼式: 模式
+                            .replace_all(&原拼式.拼式, 替換文字.as_ref())
+                            .to_string(),
+                        類別: 類別.所得類別(原拼式.類別),
+                    })
+                    .collect::<Vec<_>>();
+                運算結果 = 去重(運算結果.into_iter().chain(派生拼式));
+            }
+        };
+    }
+    運算結果.retain(|拼式| !拼式.拼式.is_empty());
+    運算結果
+}
+
+/// 重複的拼式留在首次出現處, 類別取最正者
+fn 去重(衆拼式: impl Iterator<Item = 分類拼式>) -> Vec<分類拼式> {
+    let mut 結果 = Vec::<分類拼式>::new();
+    for 拼式 in 衆拼式 {
+        match 結果.iter_mut().find(|已有| 已有.拼式 == 拼式.拼式) {
+            Some(已有) => 已有.類別 = 已有.類別.min(拼式.類別),
+            None => 結果.push(拼式),
+        }
+    }
+    結果
+}
+
+#[derive(Debug)]
+pub enum 拼寫運算錯誤 {
+    未知運算(String),
+    格式有誤(String),
+    字表長度不等(String),
+    正則表達式有誤(lazy_regex::regex::Error),
+}
+
+impl std::fmt::Display for 拼寫運算錯誤 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::未知運算(規則) => write!(f, "未知拼寫運算: {規則}"),
+            Self::格式有誤(規則) => write!(f, "拼寫運算格式有誤: {規則}"),
+            Self::字表長度不等(規則) => write!(f, "轉寫字表長度不等: {規則}"),
+            Self::正則表達式有誤(錯誤) => write!(f, "正則表達式有誤: {錯誤}"),
+        }
+    }
+}
+
+impl From<lazy_regex::regex::Error> for 拼寫運算錯誤 {
+    fn from(錯誤: lazy_regex::regex::Error) -> Self {
+        Self::正則表達式有誤(錯誤)
+    }
+}
+
+/// 解析 Rime 拼寫運算式, 如 `xform/^([zcs])h/$1/`, `xlit/abc/xyz/`, `erase/^x$/`
+/// 以及多值運算 `derive`, `fuzz`, `abbrev`.
+///
+/// 運算名之後的首個字符爲分隔符, 末尾的分隔符可省略.
+/// 整套規則可逐條解析, 收集爲 `Result<Vec<拼寫運算>, 拼寫運算錯誤>`.
+pub fn 解析拼寫運算(規則: &str) -> Result<拼寫運算<'static>, 拼寫運算錯誤> {
+    let 運算名長度 = 規則
+        .find(|字符: char| !字符.is_ascii_alphabetic())
+        .unwrap_or(規則.len());
+    let (運算名, 參數) = 規則.split_at(運算名長度);
+    let 分隔符 = 參數
+        .chars()
+        .next()
+        .ok_or_else(|| 拼寫運算錯誤::格式有誤(規則.to_owned()))?;
+    let 參數 = &參數[分隔符.len_utf8()..];
+    let 參數 = 參數.strip_suffix(分隔符).unwrap_or(參數);
+    let 衆參數 = 參數.split(分隔符).collect::<Vec<_>>();
+    match (運算名, 衆參數.as_slice()) {
+        ("xform", [模式, 替換文字]) => Ok(拼寫運算::變換 {
+            模式: MaybeOwned::Owned(Regex::new(模式)?),
+            替換文字: Cow::Owned(轉換替換文字(替換文字)),
+        }),
+        ("derive" | "fuzz" | "abbrev", [模式, 替換文字]) => Ok(拼寫運算::派生 {
+            類別: 派生類別::取自運算名(運算名).unwrap(),
+            模式: MaybeOwned::Owned(Regex::new(模式)?),
+            替換文字: Cow::Owned(轉換替換文字(替換文字)),
+        }),
+        ("xlit", [左字表, 右字表]) => {
+            if 左字表.chars().count() != 右字表.chars().count() {
+                return Err(拼寫運算錯誤::字表長度不等(規則.to_owned()));
+            }
+            Ok(拼寫運算::轉寫 {
+                字符映射: std::iter::zip(左字表.chars(), 右字表.chars()).collect(),
+            })
+        }
+        // Rime 以模式匹配整個拼式
+        ("erase", [模式]) => Ok(拼寫運算::消除 {
+            模式: MaybeOwned::Owned(Regex::new(&format!("^(?:{模式})$"))?),
+        }),
+        ("xform" | "xlit" | "erase" | "derive" | "fuzz" | "abbrev", _) => {
+            Err(拼寫運算錯誤::格式有誤(規則.to_owned()))
+        }
+        _ => Err(拼寫運算錯誤::未知運算(規則.to_owned())),
+    }
+}
+
+/// Rime 的替換文字以 `$1` 或 `${1}` 引用分組, 前者其後緊接字母時須改寫作 `${1}` 方能爲 regex 識別;
+/// `$&` 即整個匹配, `$$` 及其餘 `$` 皆爲字面.
+fn 轉換替換文字(替換文字: &str) -> String {
+    let mut 轉換結果 = String::with_capacity(替換文字.len());
+    let mut 字符序列 = 替換文字.chars().peekable();
+    while let Some(字符) = 字符序列.next() {
+        if 字符 != '$' {
+            轉換結果.push(字符);
+            continue;
+        }
+        match 字符序列.peek() {
+            Some('&') => {
+                字符序列.next();
+                轉換結果.push_str("${0}");
+            }
+            Some(數字) if 數字.is_ascii_digit() => {
+                let mut 序號 = String::new();
+                while let Some(數字) = 字符序列.next_if(char::is_ascii_digit) {
+                    序號.push(數字);
+                }
+                轉換結果.push_str(&format!("${{{序號}}}"));
+            }
+            Some('$') => {
+                字符序列.next();
+                轉換結果.push_str("$$");
+            }
+            Some('{') => 轉換結果.push('$'),
+            // 其餘 `$` 照錄
+            _ => 轉換結果.push_str("$$"),
+        }
+    }
+    轉換結果
+}
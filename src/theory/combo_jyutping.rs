@@ -224,8 +224,7 @@ const 宮保粵拼佈局: 鍵盤佈局 = 鍵盤佈局 {
 };
 
 pub fn 宮保粵拼輸入方案(環境: 輸入方案環境) -> 輸入方案定義<'static> {
-    let 已選配列 = (環境.已選配列)();
-    let 是否縱版 = matches!(已選配列, Some(配列::縱向錯列分體));
+    let 是否縱版 = matches!(環境.已選配列, Some(配列::縱向錯列分體));
     輸入方案定義 {
         名稱: "宮保粵拼",
         佈局: &宮保粵拼佈局,
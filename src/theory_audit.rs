@@ -0,0 +1,243 @@
+//! 檢查並擊方案的拼寫規則是否自洽
+//!
+//! 拼式經 `字根拆分規則` 拆成字根碼, 按出對應的鍵位, 再經 `拼式轉寫規則` 轉寫回來,
+//! 應當得到原來的拼式. 不然便是有音節打不出, 或打出來變了樣.
+//!
+//! 反過來, 遍歷字根表中諸鍵的組合, 可查看每種並擊轉寫出甚麼, 哪些音節重碼, 哪些並擊被消除.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::definition::{觸鍵方式, 輸入方案定義, 鍵組};
+use crate::gear::assignment::{對照輸入碼, 碼表定義, 解析碼表};
+use crate::gear::layout::盤面選擇碼;
+use crate::key_code::KeyCode;
+use crate::spelling_algebra::施展拼寫運算;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum 往返問題 {
+    /// 拼式不合驗證規則
+    不合拼式,
+    /// 拼式拆分不出字根碼
+    無從拆分,
+    /// 字根碼中有字根表未收的成分, 按不出來
+    缺少字根 { 字根碼: String, 缺少: String },
+    /// 按出的並擊碼轉寫不出拼式
+    無從轉寫 {
+        字根碼: String, 並擊碼: String
+    },
+    /// 轉寫回來的拼式與原拼式不同.
+    /// 按出的並擊碼與拆分所得相符, 或原拼式在派生、模糊、縮寫所得的拼式之中, 仍算擊中
+    拼式走樣 {
+        字根碼: String,
+        並擊碼: String,
+        轉寫所得: String,
+        仍可擊中: bool,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct 往返覈對條目 {
+    pub 拼式: String,
+    pub 問題: 往返問題,
+}
+
+/// 逐個拼式走一遍「拆分—按鍵—轉寫」, 返回有問題的拼式
+pub fn 覈對往返<T: AsRef<str>>(
+    方案: &輸入方案定義,
+    音節表: impl IntoIterator<Item = T>,
+) -> Vec<往返覈對條目> {
+    音節表
+        .into_iter()
+        .filter_map(|拼式| {
+            let 拼式 = 拼式.as_ref();
+            覈對一個拼式(方案, 拼式).map(|問題| 往返覈對條目 {
+                拼式: 拼式.to_owned(),
+                問題,
+            })
+        })
+        .collect()
+}
+
+fn 覈對一個拼式(方案: &輸入方案定義, 拼式: &str) -> Option<往返問題> {
+    let 轉寫法 = &方案.轉寫法;
+    // 未定義驗證規則的方案不做驗證
+    if !轉寫法.拼式驗證規則.is_empty() && !轉寫法.驗證拼式(拼式) {
+        return Some(往返問題::不合拼式);
+    }
+    // 與作業中反查並擊碼的做法相同
+    let 對照碼 = 對照輸入碼 {
+        字根碼原文: None,
+        轉寫碼原文: Some(拼式.to_owned()),
+    };
+    let Some(字根碼) = 對照碼
+        .反查字根碼(轉寫法)
+        .filter(|字根碼| !字根碼.is_empty())
+    else {
+        return Some(往返問題::無從拆分);
+    };
+    let 缺少 = 字根表未收成分(方案, &字根碼);
+    if !缺少.is_empty() {
+        return Some(往返問題::缺少字根 { 字根碼, 缺少 });
+    }
+    // 連擊方案逐鍵錄入字根碼, 無須經鍵位往返
+    let 並擊碼 = match 方案.指法 {
+        觸鍵方式::並擊 => 方案.寫成字根碼(&方案.讀出鍵位(&字根碼, &盤面選擇碼(0))),
+        觸鍵方式::連擊 => 字根碼.clone(),
+    };
+    let Some(轉寫所得) = 轉寫法.字根碼轉寫爲拼式(&並擊碼) else {
+        return Some(往返問題::無從轉寫 {
+            字根碼, 並擊碼
+        });
+    };
+    if 轉寫所得 == 拼式 {
+        return None;
+    }
+    // 同並擊判定: 並擊碼相符, 或派生、模糊、縮寫所得的拼式相符
+    let 仍可擊中 = 對照碼.反查衆字根碼(轉寫法).contains(&並擊碼)
+        || 轉寫法
+            .字根碼轉寫爲衆拼式(&並擊碼)
+            .iter()
+            .any(|衆拼式之一| 衆拼式之一.拼式 == 拼式);
+    Some(往返問題::拼式走樣 {
+        字根碼,
+        並擊碼,
+        轉寫所得,
+        仍可擊中,
+    })
+}
+
+/// 字根碼換成按鍵序列後, 去掉字根表中的各字根, 剩下的便是按不出的成分
+fn 字根表未收成分(方案: &輸入方案定義, 字根碼: &str) -> String {
+    let mut 餘下 = 施展拼寫運算(字根碼, 方案.轉寫法.輸入碼鍵位).unwrap_or(字根碼.to_owned());
+    let mut 衆字根 = 方案
+        .字根表
+        .iter()
+        .map(|鍵| 鍵.輸入碼)
+        .filter(|輸入碼| !輸入碼.is_empty())
+        .collect::<Vec<_>>();
+    // 先去長的, 免得多字符的字根被拆散
+    衆字根.sort_by_key(|輸入碼| std::cmp::Reverse(輸入碼.chars().count()));
+    for 輸入碼 in 衆字根 {
+        餘下 = 餘下.replace(輸入碼, "");
+    }
+    餘下.retain(|字符| !字符.is_whitespace());
+    餘下
+}
+
+/// 從碼表中收集有待覈對的拼式. 已註明並擊碼的字根、韻母等不算音節, 不收
+pub fn 碼表中的音節(方案: &輸入方案定義, 碼表: &碼表定義) -> BTreeSet<String> {
+    解析碼表(碼表, 方案)
+        .iter()
+        .filter(|對照碼| 對照碼.字根碼原文.is_none())
+        .filter_map(|對照碼| 對照碼.轉寫碼原文.clone())
+        .filter(|拼式| 方案.轉寫法.驗證拼式(拼式))
+        .collect()
+}
+
+impl 往返問題 {
+    /// 照此拼式練習時能否擊中
+    pub fn 能否擊中(&self) -> bool {
+        matches!(
+            self,
+            往返問題::拼式走樣 {
+                仍可擊中: true, ..
+            }
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct 並擊轉寫 {
+    pub 鍵位: 鍵組,
+    pub 並擊碼: String,
+    pub 拼式: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct 並擊空間報告 {
+    pub 並擊總數: usize,
+    /// 轉寫所得合乎驗證規則的並擊
+    pub 有效並擊: Vec<並擊轉寫>,
+    /// 轉寫出拼式, 但不合驗證規則
+    pub 不合拼式: Vec<並擊轉寫>,
+    /// 被消除規則剔除, 轉寫不出拼式的並擊
+    pub 被消除: Vec<並擊轉寫>,
+}
+
+impl 並擊空間報告 {
+    /// 可由多種並擊得到的拼式. 只計正規拼式, 不計派生、模糊、縮寫所得
+    pub fn 重碼(&self) -> BTreeMap<&str, Vec<&並擊轉寫>> {
+        let mut 按拼式歸類 = BTreeMap::<&str, Vec<&並擊轉寫>>::new();
+        for 條目 in &self.有效並擊 {
+            按拼式歸類.entry(&條目.拼式).or_default().push(條目);
+        }
+        按拼式歸類.retain(|_, 衆並擊| 衆並擊.len() > 1);
+        按拼式歸類
+    }
+}
+
+/// 遍歷字根表諸鍵中, 不多於 `鍵數上限` 個鍵的所有組合, 逐一寫成並擊碼並轉寫爲拼式
+pub fn 遍歷並擊空間(方案: &輸入方案定義, 鍵數上限: usize) -> 並擊空間報告 {
+    let 衆鍵 = 方案
+        .字根表
+        .iter()
+        .map(|鍵| 鍵.鍵碼)
+        .filter(|&鍵碼| 鍵碼 != KeyCode::No)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    遍歷諸鍵並擊(方案, &衆鍵, 鍵數上限)
+}
+
+/// 同 [`遍歷並擊空間`], 只取 `衆鍵` 中的鍵
+pub fn 遍歷諸鍵並擊(
+    方案: &輸入方案定義,
+    衆鍵: &[KeyCode],
+    鍵數上限: usize,
+) -> 並擊空間報告 {
+    let 轉寫法 = &方案.轉寫法;
+    let mut 報告 = 並擊空間報告::default();
+    let mut 選中 = vec![];
+    組合諸鍵(衆鍵, 鍵數上限, &mut 選中, &mut |選中| {
+        let 鍵位 = 鍵組(選中.iter().copied().collect());
+        let 並擊碼 = 方案.寫成字根碼(&鍵位);
+        報告.並擊總數 += 1;
+        match 轉寫法.字根碼轉寫爲拼式(&並擊碼) {
+            None => 報告.被消除.push(並擊轉寫 {
+                鍵位,
+                並擊碼,
+                拼式: String::new(),
+            }),
+            Some(拼式) => {
+                let 合乎拼式 = 轉寫法.拼式驗證規則.is_empty() || 轉寫法.驗證拼式(&拼式);
+                let 條目 = 並擊轉寫 {
+                    鍵位, 並擊碼, 拼式
+                };
+                if 合乎拼式 {
+                    報告.有效並擊.push(條目);
+                } else {
+                    報告.不合拼式.push(條目);
+                }
+            }
+        }
+    });
+    報告
+}
+
+/// 從 `衆鍵` 中取出一至 `餘量` 個鍵的各種組合
+fn 組合諸鍵(
+    衆鍵: &[KeyCode],
+    餘量: usize,
+    選中: &mut Vec<KeyCode>,
+    處理: &mut impl FnMut(&[KeyCode]),
+) {
+    if 餘量 == 0 {
+        return;
+    }
+    for (次序, &鍵) in 衆鍵.iter().enumerate() {
+        選中.push(鍵);
+        處理(選中);
+        組合諸鍵(&衆鍵[次序 + 1..], 餘量 - 1, 選中, 處理);
+        選中.pop();
+    }
+}
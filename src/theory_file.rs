@@ -0,0 +1,406 @@
+//! 輸入方案數據文件
+//!
+//! 用 YAML 描述字根表、盤面、轉寫法等方案要素, 運行時求取並生成輸入方案定義,
+//! 調整方案不必重新編譯.
+
+use lazy_regex::{regex, Regex};
+use maybe_owned::MaybeOwned;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::definition::{
+    動態切換規則, 碼表格式, 觸鍵方式, 輸入方案定義, 轉寫法定義, 邊界判定規則, 鍵位定義,
+};
+use crate::gear::layout::{
+    上檔盤面, 刻印說明, 基本盤面, 大寫字母盤面, 盤面定義, 盤面選擇碼, 配列, 鍵盤佈局, 鍵面刻印,
+};
+use crate::key_code::KeyCode;
+use crate::rime_schema::{導入並擊方案, 是否含並擊設定};
+use crate::spelling_algebra::{拼寫運算, 拼寫運算錯誤, 派生類別, 解析拼寫運算};
+
+#[derive(Deserialize)]
+pub struct 方案文件 {
+    pub 名稱: String,
+    pub 指法: 觸鍵方式,
+    pub 編碼法: 碼表格式,
+    pub 佈局: 佈局文件,
+    pub 字根表: Vec<鍵位文件>,
+    #[serde(default)]
+    pub 轉寫法: 轉寫法文件,
+    #[serde(default)]
+    pub 動態切換: Vec<動態切換文件>,
+}
+
+#[derive(Deserialize)]
+pub struct 佈局文件 {
+    pub 盤面: Vec<盤面文件>,
+    /// 默認選用的盤面號. 0 號盤面總是可選, 不必列出
+    #[serde(default)]
+    pub 默認盤面: Vec<usize>,
+    #[serde(default)]
+    pub 首選配列: 配列,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum 盤面文件 {
+    /// 引用內建盤面, 如 `基本盤面`
+    內建(String),
+    /// 自 E 行至 A 行逐行列出鍵面刻印
+    自訂(Vec<行文件>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum 行文件 {
+    /// 以空白分隔的一行刻印, 寫法同 `盤面!` 宏: `_` 爲透明, `空` 爲無刻
+    簡寫(String),
+    逐鍵(Vec<鍵面文件>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum 鍵面文件 {
+    居中(String),
+    詳細 {
+        中: Option<String>,
+        上: Option<String>,
+        下: Option<String>,
+        左: Option<String>,
+        右: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct 鍵位文件 {
+    pub 輸入碼: String,
+    pub 鍵碼: KeyCode,
+    /// 字根所在的盤面號. 不寫則屬於 0 號盤面
+    #[serde(default)]
+    pub 盤面: Vec<usize>,
+}
+
+/// 拼寫運算寫作單鍵映射, 如 `- 變換: [模式, 替換文字]`, 或 Rime 拼寫運算式, 如 `- xlit/abc/xyz/`
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct 轉寫法文件 {
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub 輸入碼表示: Vec<運算文件>,
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub 輸入碼鍵位: Vec<運算文件>,
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub 拼式轉寫規則: Vec<運算文件>,
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub 字根拆分規則: Vec<運算文件>,
+    pub 拼式驗證規則: Vec<String>,
+    pub 邊界判定: 邊界判定文件,
+}
+
+/// 邊界判定引用字根表中的輸入碼
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct 邊界判定文件 {
+    pub 分隔鍵: Vec<String>,
+    pub 起始鍵: Vec<String>,
+    pub 終止鍵: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum 運算文件 {
+    /// Rime 拼寫運算式, 如 `xform/^([zcs])h/$1/`
+    運算式(String),
+    逐項(運算項文件),
+}
+
+#[derive(Deserialize)]
+pub enum 運算項文件 {
+    變換(String, String),
+    轉寫(String, String),
+    消除(String),
+    派生(String, String),
+    模糊(String, String),
+    縮寫(String, String),
+}
+
+#[derive(Deserialize)]
+pub struct 動態切換文件 {
+    pub 匹配輸入: String,
+    pub 目標盤面: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub enum 方案文件錯誤 {
+    格式有誤(serde_yaml::Error),
+    正則表達式有誤(regex::Error),
+    拼寫運算有誤(拼寫運算錯誤),
+    未知盤面(String),
+    未知字根(String),
+    未知按鍵(char),
+}
+
+impl std::fmt::Display for 方案文件錯誤 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::格式有誤(錯誤) => write!(f, "方案文件格式有誤: {錯誤}"),
+            Self::正則表達式有誤(錯誤) => write!(f, "正則表達式有誤: {錯誤}"),
+            Self::拼寫運算有誤(錯誤) => write!(f, "{錯誤}"),
+            Self::未知盤面(名稱) => write!(f, "未知盤面: {名稱}"),
+            Self::未知字根(輸入碼) => write!(f, "字根表中未有: {輸入碼}"),
+            Self::未知按鍵(字符) => write!(f, "無從對應按鍵的字符: {字符:?}"),
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for 方案文件錯誤 {
+    fn from(錯誤: serde_yaml::Error) -> Self {
+        Self::格式有誤(錯誤)
+    }
+}
+
+impl From<regex::Error> for 方案文件錯誤 {
+    fn from(錯誤: regex::Error) -> Self {
+        Self::正則表達式有誤(錯誤)
+    }
+}
+
+impl From<拼寫運算錯誤> for 方案文件錯誤 {
+    fn from(錯誤: 拼寫運算錯誤) -> Self {
+        Self::拼寫運算有誤(錯誤)
+    }
+}
+
+thread_local! {
+    /// 以文件原文爲鍵, 已生成的方案定義
+    static 已載入方案: RefCell<HashMap<String, 輸入方案定義<'static>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// 亦可載入含 `chord_composer` 設定的 Rime 方案文件.
+/// 同一文本只生成一次方案定義, 再次載入時取用前次的結果.
+pub fn 載入方案文件(文本: &str) -> Result<輸入方案定義<'static>, 方案文件錯誤> {
+    if let Some(方案) = 已載入方案.with_borrow(|衆方案| 衆方案.get(文本).copied()) {
+        return Ok(方案);
+    }
+    let 文件 = serde_yaml::from_str::<serde_yaml::Value>(文本)?;
+    let 方案 = if 是否含並擊設定(&文件) {
+        導入並擊方案(文件)?
+    } else {
+        serde_yaml::from_value::<方案文件>(文件)?.生成方案定義()?
+    };
+    已載入方案.with_borrow_mut(|衆方案| 衆方案.insert(文本.to_owned(), 方案));
+    Ok(方案)
+}
+
+/// 輸入方案定義所引用的數據須與應用同壽.
+/// 載入的方案與內建方案的靜態數據一樣常駐內存; 經 [`載入方案文件`] 載入的,
+/// 每種文本至多常駐一份, 來回切換方案不會再佔內存, 但改過的文件算作另一種.
+pub fn 常駐<T>(數據: Vec<T>) -> &'static [T] {
+    數據.leak()
+}
+
+pub fn 常駐文字(文字: String) -> &'static str {
+    文字.leak()
+}
+
+impl 方案文件 {
+    pub fn 生成方案定義(self) -> Result<輸入方案定義<'static>, 方案文件錯誤> {
+        let 字根表 = 常駐(self.字根表.into_iter().map(生成鍵位).collect());
+        let 佈局 = Box::leak(Box::new(self.佈局.生成鍵盤佈局()?));
+        let 轉寫法 = self.轉寫法.生成轉寫法(字根表)?;
+        let 動態切換 = 常駐(
+            self.動態切換
+                .into_iter()
+                .map(|規則| {
+                    Ok(動態切換規則 {
+                        匹配輸入: Box::leak(Box::new(Regex::new(&規則.匹配輸入)?)),
+                        目標盤面: 選擇盤面(&規則.目標盤面),
+                    })
+                })
+                .collect::<Result<_, 方案文件錯誤>>()?,
+        );
+        Ok(輸入方案定義 {
+            名稱: 常駐文字(self.名稱),
+            佈局,
+            指法: self.指法,
+            編碼法: self.編碼法,
+            字根表,
+            轉寫法,
+            動態切換,
+        })
+    }
+}
+
+impl 佈局文件 {
+    fn 生成鍵盤佈局(self) -> Result<鍵盤佈局, 方案文件錯誤> {
+        Ok(鍵盤佈局 {
+            盤面: 常駐(
+                self.盤面
+                    .into_iter()
+                    .map(生成盤面)
+                    .collect::<Result<_, _>>()?,
+            ),
+            默認盤面: 選擇盤面(&self.默認盤面),
+            首選配列: self.首選配列,
+        })
+    }
+}
+
+impl 轉寫法文件 {
+    fn 生成轉寫法(
+        self,
+        字根表: &'static [鍵位定義<'static>],
+    ) -> Result<轉寫法定義<'static>, 方案文件錯誤> {
+        let 拼式驗證規則 = self
+            .拼式驗證規則
+            .iter()
+            .map(|模式| Ok(&*Box::leak(Box::new(Regex::new(模式)?))))
+            .collect::<Result<Vec<_>, 方案文件錯誤>>()?;
+        Ok(轉寫法定義 {
+            輸入碼表示: 生成運算規則(self.輸入碼表示)?,
+            輸入碼鍵位: 生成運算規則(self.輸入碼鍵位)?,
+            拼式轉寫規則: 生成運算規則(self.拼式轉寫規則)?,
+            字根拆分規則: 生成運算規則(self.字根拆分規則)?,
+            拼式驗證規則: 常駐(拼式驗證規則),
+            邊界判定: 邊界判定規則 {
+                分隔鍵: 查找字根(字根表, &self.邊界判定.分隔鍵)?,
+                起始鍵: 查找字根(字根表, &self.邊界判定.起始鍵)?,
+                終止鍵: 查找字根(字根表, &self.邊界判定.終止鍵)?,
+            },
+        })
+    }
+}
+
+/// 盤面號轉換爲盤面選擇碼, 如 `[2]` 即 `盤面選擇碼(1 << 1)`
+fn 選擇盤面(衆盤面號: &[usize]) -> 盤面選擇碼 {
+    盤面選擇碼(
+        衆盤面號
+            .iter()
+            .filter(|&&盤面號| (1..=64).contains(&盤面號))
+            .fold(0, |選擇碼, &盤面號| 選擇碼 | (1 << (盤面號 - 1))),
+    )
+}
+
+fn 生成鍵位(鍵位: 鍵位文件) -> 鍵位定義<'static> {
+    鍵位定義 {
+        輸入碼: 常駐文字(鍵位.輸入碼),
+        盤面: 選擇盤面(&鍵位.盤面),
+        鍵碼: 鍵位.鍵碼,
+    }
+}
+
+fn 查找字根(
+    字根表: &'static [鍵位定義<'static>],
+    衆輸入碼: &[String],
+) -> Result<&'static [鍵位定義<'static>], 方案文件錯誤> {
+    let mut 查得字根 = Vec::new();
+    for 輸入碼 in 衆輸入碼 {
+        let 字根 = 字根表.iter().filter(|鍵位| 鍵位.輸入碼 == 輸入碼);
+        let 起始數目 = 查得字根.len();
+        查得字根.extend(字根.cloned());
+        if 查得字根.len() == 起始數目 {
+            return Err(方案文件錯誤::未知字根(輸入碼.to_owned()));
+        }
+    }
+    Ok(常駐(查得字根))
+}
+
+fn 生成運算規則(
+    衆規則: Vec<運算文件>,
+) -> Result<&'static [拼寫運算<'static>], 方案文件錯誤> {
+    let 運算規則 = 衆規則
+        .into_iter()
+        .map(|規則| {
+            Ok(match 規則 {
+                運算文件::運算式(運算式) => 解析拼寫運算(&運算式)?,
+                運算文件::逐項(運算項文件::變換(模式, 替換文字)) => {
+                    拼寫運算::變換 {
+                        模式: MaybeOwned::Owned(Regex::new(&模式)?),
+                        替換文字: Cow::Owned(替換文字),
+                    }
+                }
+                運算文件::逐項(運算項文件::轉寫(左字表, 右字表)) => {
+                    if 左字表.chars().count() != 右字表.chars().count() {
+                        return Err(拼寫運算錯誤::字表長度不等(
+                            format!("{左字表} / {右字表}"),
+                        )
+                        .into());
+                    }
+                    拼寫運算::轉寫 {
+                        字符映射: std::iter::zip(左字表.chars(), 右字表.chars()).collect(),
+                    }
+                }
+                運算文件::逐項(運算項文件::消除(模式)) => 拼寫運算::消除 {
+                    模式: MaybeOwned::Owned(Regex::new(&模式)?),
+                },
+                運算文件::逐項(運算項文件::派生(模式, 替換文字)) => {
+                    生成派生運算(派生類別::派生, &模式, 替換文字)?
+                }
+                運算文件::逐項(運算項文件::模糊(模式, 替換文字)) => {
+                    生成派生運算(派生類別::模糊, &模式, 替換文字)?
+                }
+                運算文件::逐項(運算項文件::縮寫(模式, 替換文字)) => {
+                    生成派生運算(派生類別::縮寫, &模式, 替換文字)?
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, 方案文件錯誤>>()?;
+    Ok(常駐(運算規則))
+}
+
+fn 生成派生運算(
+    類別: 派生類別,
+    模式: &str,
+    替換文字: String,
+) -> Result<拼寫運算<'static>, 方案文件錯誤> {
+    Ok(拼寫運算::派生 {
+        類別,
+        模式: MaybeOwned::Owned(Regex::new(模式)?),
+        替換文字: Cow::Owned(替換文字),
+    })
+}
+
+fn 生成盤面(盤面: 盤面文件) -> Result<盤面定義<'static>, 方案文件錯誤> {
+    match 盤面 {
+        盤面文件::內建(名稱) => match 名稱.as_str() {
+            "基本盤面" => Ok(基本盤面),
+            "上檔盤面" => Ok(上檔盤面),
+            "大寫字母盤面" => Ok(大寫字母盤面),
+            _ => Err(方案文件錯誤::未知盤面(名稱)),
+        },
+        盤面文件::自訂(衆行) => Ok(常駐(衆行.into_iter().map(生成盤面行).collect())),
+    }
+}
+
+fn 生成盤面行(行: 行文件) -> &'static [鍵面刻印] {
+    match 行 {
+        行文件::簡寫(刻印) => 常駐(
+            刻印
+                .split_whitespace()
+                .map(|記號| 生成刻印(鍵面文件::居中(記號.to_owned())))
+                .collect(),
+        ),
+        行文件::逐鍵(衆鍵面) => 常駐(衆鍵面.into_iter().map(生成刻印).collect()),
+    }
+}
+
+fn 生成刻印(鍵面: 鍵面文件) -> 鍵面刻印 {
+    match 鍵面 {
+        鍵面文件::居中(記號) => match 記號.as_str() {
+            "_" => 鍵面刻印::透明,
+            "空" => 鍵面刻印::無刻,
+            _ => 鍵面刻印::有刻(刻印說明::居中(常駐文字(記號))),
+        },
+        鍵面文件::詳細 {
+            中, 上, 下, 左, 右
+        } => 鍵面刻印::有刻(刻印說明 {
+            中: 中.map(常駐文字),
+            上: 上.map(常駐文字),
+            下: 下.map(常駐文字),
+            左: 左.map(常駐文字),
+            右: 右.map(常駐文字),
+        }),
+    }
+}
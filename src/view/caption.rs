@@ -1,22 +1,38 @@
 use leptos::prelude::*;
 
-use crate::gear::caption::字幕表示;
+use crate::gear::caption::{字幕表示, 影子表示};
 
 #[component]
 pub fn Rime字幕屏(
     是否顯示光標: Signal<bool>,
     按進度顯示字幕: Signal<Option<字幕表示>>,
+    /// 影子光標在本段時纔有
+    影子: Signal<Option<影子表示>>,
 ) -> impl IntoView {
     view! {
         <div class="text-box">
             <div class="caption">
             {
                 move || 按進度顯示字幕().map(|字幕| view! {
-                    <span class="accepted">{字幕.已完成}</span>
+                    {
+                        字幕.已完成分截.into_iter().map(|(文字, 擊錯)| view! {
+                            <span class="accepted" class:missed={擊錯}>{文字}</span>
+                        }).collect_view()
+                    }
                     <span class="highlight" class:cursor={是否顯示光標}>{字幕.指標文字}</span>
                     <span>{字幕.未完成}</span>
                 })
             }
+            {
+                // 與字幕重疊的一層, 僅顯出影子所在之字
+                move || 影子().map(|影子| view! {
+                    <div class="ghost-layer">
+                        {影子.之前}
+                        <span class="ghost">{影子.指標文字}</span>
+                        {影子.之後}
+                    </div>
+                })
+            }
             </div>
         </div>
     }
@@ -0,0 +1,32 @@
+use leptos::prelude::*;
+
+#[component]
+pub fn Rime解鎖進度(
+    已解鎖鍵數: Signal<usize>,
+    全部鍵數: Signal<usize>,
+    /// 最後解鎖的鍵的字根
+    新鍵字根: Signal<Option<String>>,
+    /// 一課結束後的評定, 如「達標」或未達標的原因
+    評定: Signal<Option<String>>,
+) -> impl IntoView {
+    view! {
+        <div class="session-summary">
+            <span class="summary-item">
+                <span class="summary-label">"已解鎖"</span>
+                <span class="summary-value">{move || format!("{}/{}", 已解鎖鍵數(), 全部鍵數())}</span>
+                <span class="summary-label">"鍵"</span>
+            </span>
+            {move || 新鍵字根().map(|字根| view! {
+                <span class="summary-item">
+                    <span class="summary-label">"新鍵"</span>
+                    <span class="summary-value">{字根}</span>
+                </span>
+            })}
+            {move || 評定().map(|評定| view! {
+                <span class="summary-item">
+                    <span class="summary-value">{評定}</span>
+                </span>
+            })}
+        </div>
+    }
+}
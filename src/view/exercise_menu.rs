@@ -4,19 +4,35 @@ use leptos::prelude::*;
 
 use crate::action::動作給一參數;
 use crate::drill::練習題;
+use crate::gear::{adaptive::加練標題, curriculum::解鎖標題};
+
+/// 列在預設練習題之後, 即時生成的練習題: 選項值、標題、說明
+const 生成題選項: &[(&str, &str, &str)] = &[
+    ("adaptive", 加練標題, "按練習紀錄抽取易錯、偏慢的片段"),
+    ("unlock", 解鎖標題, "從幾個常用鍵練起, 達標後逐個解鎖新鍵"),
+];
 
 #[component]
 pub fn Rime練習題選單(
     預設練習題: Signal<&'static [練習題<'static>]>,
     當選題號: Signal<Option<usize>>,
     選中題號: impl 動作給一參數<usize>,
+    /// 現行作業若是生成的練習題, 其標題
+    當選生成題: Signal<Option<&'static str>>,
+    選中生成題: impl 動作給一參數<&'static str>,
 ) -> impl IntoView {
     let 練習題選單的引用 = NodeRef::<html::Select>::new();
     let _ = Effect::new(move |_| {
         if let Some(輸入欄) = 練習題選單的引用.get() {
-            let 選項序號 = 當選題號()
-                .and_then(|題號| 題號.try_into().ok())
-                .unwrap_or(-1);
+            let 選項序號 = match 當選生成題() {
+                Some(標題) => 生成題選項
+                    .iter()
+                    .position(|&(_, 生成題標題, _)| 生成題標題 == 標題)
+                    .map(|序號| 預設練習題().len() + 序號),
+                None => 當選題號(),
+            }
+            .and_then(|序號| 序號.try_into().ok())
+            .unwrap_or(-1);
             輸入欄.set_selected_index(選項序號);
             let _ = 輸入欄.focus();
         }
@@ -28,7 +44,9 @@ pub fn Rime練習題選單(
             on:change=move |ev| {
                 let 題號 = event_target_value(&ev);
                 log!("題號: {}", 題號);
-                if let Ok(題號) = 題號.parse::<usize>() {
+                if let Some(&(_, 標題, _)) = 生成題選項.iter().find(|(選項值, _, _)| *選項值 == 題號) {
+                    選中生成題(標題);
+                } else if let Ok(題號) = 題號.parse::<usize>() {
                     if 題號 < 預設練習題().len() {
                         選中題號(題號);
                     }
@@ -40,6 +58,11 @@ pub fn Rime練習題選單(
                 <option value={題號}>{題.標題}</option>
             }).collect_view()
         }
+        {
+            生成題選項.iter().map(|&(選項值, 標題, 說明)| view! {
+                <option value={選項值} title={說明}>{標題}</option>
+            }).collect_view()
+        }
         </select>
     }
 }
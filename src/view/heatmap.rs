@@ -0,0 +1,54 @@
+use leptos::prelude::*;
+
+use crate::gear::{heatmap::熱圖選項, history::片段成績};
+
+#[component]
+pub fn Rime最差片段表(
+    選項: Signal<熱圖選項>,
+    衆片段: Signal<Vec<片段成績>>,
+    /// 片段的量詞, 如「音節」、「字」
+    片段單位: Signal<&'static str>,
+) -> impl IntoView {
+    let 各行 = move || {
+        let 指標 = 選項.read().指標;
+        衆片段
+            .read()
+            .iter()
+            .map(|片段| {
+                view! {
+                    <tr>
+                        <td>{片段.輸入碼.clone()}</td>
+                        <td class="history-number">{片段.次數}</td>
+                        <td class="history-number">{片段.擊錯次數}</td>
+                        <td class="history-number">{指標.表示(指標.取值(片段))}</td>
+                    </tr>
+                }
+            })
+            .collect_view()
+    };
+    view! {
+        <div class="worst-segments">
+            <div class="history-header">
+                <span class="summary-label">
+                    {move || format!("{}最差{}", 選項.read().來源, 片段單位())}
+                </span>
+            </div>
+            <Show
+                when=move || !衆片段.read().is_empty()
+                fallback=|| view! { <p class="summary-label">"尚無可比較的成績"</p> }
+            >
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{片段單位}</th>
+                            <th>"次數"</th>
+                            <th>"擊錯"</th>
+                            <th>{move || 選項.read().指標.to_string()}</th>
+                        </tr>
+                    </thead>
+                    <tbody>{各行}</tbody>
+                </table>
+            </Show>
+        </div>
+    }
+}
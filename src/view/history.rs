@@ -0,0 +1,92 @@
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsValue;
+
+use crate::action::動作;
+use crate::gear::{history::練習成績, layout::配列};
+
+/// 每條紀錄列出擊錯最多的片段數
+const 易錯片段數: usize = 3;
+
+fn 日期表示(日期: f64) -> String {
+    let 日期 = js_sys::Date::new(&JsValue::from_f64(日期));
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        日期.get_full_year(),
+        日期.get_month() + 1,
+        日期.get_date(),
+        日期.get_hours(),
+        日期.get_minutes()
+    )
+}
+
+fn 配列名稱(代號: &str) -> String {
+    配列::from_slug(代號).map_or_else(|| 代號.to_owned(), |配列| 配列.to_string())
+}
+
+#[component]
+pub fn Rime練習紀錄(
+    /// 按完成先後排列, 顯示時新者在前
+    衆成績: Signal<Vec<練習成績>>,
+    片段單位: Signal<&'static str>,
+    清除紀錄: impl 動作,
+    關閉: impl 動作,
+) -> impl IntoView {
+    let 各行 = move || {
+        衆成績
+            .read()
+            .iter()
+            .rev()
+            .map(|成績| {
+                let 易錯片段 = 成績
+                    .易錯片段(易錯片段數)
+                    .iter()
+                    .map(|片段| format!("{}×{}", 片段.輸入碼, 片段.擊錯次數))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                view! {
+                    <tr>
+                        <td>{日期表示(成績.日期)}</td>
+                        <td>{成績.標題.clone()}</td>
+                        <td>{配列名稱(&成績.配列)}</td>
+                        <td class="history-number">{format!("{:.0}", 成績.摘要.每分鐘片段數())}</td>
+                        <td class="history-number">{format!("{:.0}", 成績.摘要.每分鐘擊數())}</td>
+                        <td class="history-number">{format!("{:.0}%", 成績.摘要.首擊正確率() * 100.0)}</td>
+                        <td>{易錯片段}</td>
+                    </tr>
+                }
+            })
+            .collect_view()
+    };
+    view! {
+        <div class="practice-history">
+            <div class="history-header">
+                <span class="summary-label">"練習紀錄"</span>
+                <span class="status-item" on:click=move |_| 清除紀錄() title="清除本方案的練習紀錄">
+                    <span class="status-label">"清除"</span>
+                </span>
+                <span class="status-item" on:click=move |_| 關閉() title="收起">
+                    <span class="status-label">"✕"</span>
+                </span>
+            </div>
+            <Show
+                when=move || !衆成績.read().is_empty()
+                fallback=|| view! { <p class="summary-label">"本方案尚無練習紀錄"</p> }
+            >
+                <table>
+                    <thead>
+                        <tr>
+                            <th>"日期"</th>
+                            <th>"練習題"</th>
+                            <th>"佈局"</th>
+                            <th>{move || format!("{}/分", 片段單位())}</th>
+                            <th>"擊/分"</th>
+                            <th>"首擊正確"</th>
+                            <th>"易錯"</th>
+                        </tr>
+                    </thead>
+                    <tbody>{各行}</tbody>
+                </table>
+            </Show>
+        </div>
+    }
+}
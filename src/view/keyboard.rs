@@ -2,8 +2,10 @@ use leptos::prelude::*;
 use leptos::wasm_bindgen::JsCast;
 use leptos::web_sys;
 
+use crate::gear::geometry::{配列幾何, 鍵位幾何};
+use crate::gear::host_layout::系統佈局;
 use crate::gear::layout::{
-    功能鍵::衆功能鍵, 盤面選擇碼, 矩陣座標, 配列, 鍵盤佈局, 鍵面刻印, 鍵面映射,
+    功能鍵::衆功能鍵, 替代鍵位表, 盤面選擇碼, 矩陣座標, 配列, 鍵盤佈局, 鍵面刻印, 鍵面映射,
 };
 use crate::key_code::KeyCode;
 
@@ -24,6 +26,10 @@ pub trait 鍵面動態着色法 {
     fn 鍵位提示(&self, 鍵: KeyCode) -> bool;
     fn 是否落鍵(&self, 鍵: KeyCode) -> bool;
     fn 是否擊中(&self, 鍵: KeyCode) -> bool;
+    /// 熱圖着色, 0 至 1. 爲空則不着色.
+    fn 熱度(&self, _鍵: KeyCode) -> Option<f64> {
+        None
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -31,12 +37,12 @@ struct 選擇鍵面 {
     佈局: 鍵盤佈局,
     鍵碼: KeyCode,
     目標盤面: 盤面選擇碼,
-    座標: 矩陣座標,
+    座標: Option<矩陣座標>,
 }
 
 impl 選擇鍵面 {
     fn new(
-        佈局: 鍵盤佈局, 鍵碼: KeyCode, 目標盤面: 盤面選擇碼, 座標: 矩陣座標
+        佈局: 鍵盤佈局, 鍵碼: KeyCode, 目標盤面: 盤面選擇碼, 座標: Option<矩陣座標>
     ) -> Self {
         Self {
             佈局,
@@ -47,7 +53,7 @@ impl 選擇鍵面 {
     }
 
     fn 有效盤面(&self) -> Option<(usize, 鍵面刻印)> {
-        self.佈局.選擇盤面(self.目標盤面, self.座標)
+        self.佈局.選擇盤面(self.目標盤面, self.座標?)
     }
 }
 
@@ -57,12 +63,18 @@ impl 鍵面標註法 for 選擇鍵面 {
     }
     fn 刻印(&self) -> 鍵面刻印 {
         self.有效盤面()
-            .map_or(鍵面刻印::透明, move |(_, 刻印)| 刻印)
+            .map(|(_, 刻印)| 刻印)
+            // 拇指區的功能鍵
+            .or_else(|| {
+                衆功能鍵
+                    .iter()
+                    .find(|功能鍵| 功能鍵.鍵碼 == self.鍵碼)
+                    .map(|功能鍵| 功能鍵.刻印)
+            })
+            .unwrap_or(鍵面刻印::透明)
     }
     fn 是否空鍵(&self) -> bool {
-        !self
-            .有效盤面()
-            .is_some_and(|(_, 刻印)| matches!(刻印, 鍵面刻印::有刻 { .. }))
+        !matches!(self.刻印(), 鍵面刻印::有刻 { .. })
     }
     fn 是否後備盤面(&self) -> bool {
         self.有效盤面()
@@ -100,12 +112,16 @@ where
     let (已落鍵, 落鍵) = signal(false);
     view! {
         <div
-            style="touch-action: none; user-select: none;"
+            style=move || match 着色法.熱度(鍵) {
+                Some(熱度) => format!("touch-action: none; user-select: none; --heat: {熱度:.3};"),
+                None => String::from("touch-action: none; user-select: none;"),
+            }
             class="key horizontal-box"
             class:empty={move || 標註法.read().是否空鍵()}
             class:fallback={move || 標註法.read().是否後備盤面()}
             class:function={move || 標註法.read().是否功能鍵()}
             class:space={move || 標註法.read().是否空格()}
+            class:heat={move || 着色法.熱度(鍵).is_some()}
             class:hint={move || 着色法.鍵位提示(鍵)}
             class:keydown={move || 着色法.是否落鍵(鍵)}
             class:pressed={move || 着色法.是否擊中(鍵)}
@@ -157,11 +173,34 @@ where
     }
 }
 
+fn 鍵位樣式(鍵位: &鍵位幾何) -> String {
+    let 鍵位幾何 {
+        左,
+        上,
+        寬,
+        高,
+        轉角,
+        旋轉中心: (中心橫, 中心縱),
+        ..
+    } = 鍵位;
+    format!(
+        "left: calc(var(--pitch) * {左}); top: calc(var(--pitch) * {上}); \
+         width: calc(var(--pitch) * {寬} - var(--gap)); height: calc(var(--pitch) * {高} - var(--gap)); \
+         transform-origin: calc(var(--pitch) * {}) calc(var(--pitch) * {}); transform: rotate({轉角}deg);",
+        中心橫 - 左,
+        中心縱 - 上,
+    )
+}
+
 #[component]
 pub fn Rime鍵盤圖<T>(
-    配列: Signal<配列>,
+    配列幾何: Signal<配列幾何>,
+    /// 各物理位置按系統佈局換成方案所認的鍵, 刻印隨之重排
+    系統佈局: Signal<系統佈局>,
+    /// 配列所缺的鍵改由哪些鍵代替
+    替代鍵位: Signal<替代鍵位表>,
     鍵盤佈局: Signal<鍵盤佈局>,
-    目標盤面: ReadSignal<盤面選擇碼>,
+    目標盤面: Signal<盤面選擇碼>,
     着色法: T,
     #[prop(into)] 落鍵動作: Callback<KeyCode>,
     #[prop(into)] 抬鍵動作: Callback<KeyCode>,
@@ -172,24 +211,32 @@ where
     view! {
         <div
             class="board"
-            class=("size-60", move || 配列.read().規格() == 60)
-            class=("size-30", move || 配列.read().規格() == 30)
-            class:ortholinear={move || 配列.read().直列()}
-            class:staggered={move || 配列.read().橫向交錯()}
-            class:columnar={move || 配列.read().縱向交錯()}
-            class:split={move || 配列.read().分體()}
+            style=move || {
+                let (寬, 高) = 配列幾何.read().尺寸();
+                format!("--board-width: {寬}; --board-height: {高};")
+            }
         >
-        { move || 配列.read().矩陣().iter().enumerate().map(|(行座標, 行)| view! {
-            <div class="row">
-            { 行.iter().enumerate()
-              .filter(|(_, &鍵)| 鍵 != KeyCode::No)
-              .map(|(列座標, &鍵)| {
-                let 標註法 = Signal::derive(move || 選擇鍵面::new(鍵盤佈局(), 鍵, 目標盤面(), 矩陣座標(行座標, 列座標)));
-                view! {
+        { move || 配列幾何.read().衆鍵位.iter().map(|鍵位| {
+            let 物理位置 = 鍵位.鍵碼;
+            let 鍵 = 替代鍵位.read().轉換(系統佈局().轉換(物理位置));
+            let 座標 = if 鍵 == 物理位置 {
+                鍵位.刻印座標
+            } else {
+                // 無對應之鍵不取刻印
+                配列::主鍵盤區.鍵碼刻印座標(鍵)
+            };
+            let 標註法 = Signal::derive(move || 選擇鍵面::new(鍵盤佈局(), 鍵, 目標盤面(), 座標));
+            view! {
+                <div
+                    class="key-slot"
+                    class:thumb={鍵位.拇指鍵}
+                    class:wide={鍵位.寬 > 1.5}
+                    class:homing={鍵位.定位鍵()}
+                    style={鍵位樣式(鍵位)}
+                >
                     <Rime鍵圖 鍵={鍵} 標註法={標註法} 着色法={着色法} 落鍵動作={落鍵動作} 抬鍵動作={抬鍵動作}/>
-                }
-            }).collect_view() }
-            </div>
+                </div>
+            }
         }).collect_view() }
         </div>
     }
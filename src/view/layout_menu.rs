@@ -1,16 +1,37 @@
 use leptos::html;
 use leptos::logging::log;
 use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::web_sys;
 use strum::IntoEnumIterator;
 
-use crate::action::動作給一參數;
+use crate::action::{動作給一參數, 動作給一參數得一結果};
 use crate::gear::layout::配列;
 
 #[component]
 pub fn Rime配列選單(
     已選配列: Signal<配列>,
     選中配列: impl 動作給一參數<配列>,
+    /// 導入 Keyboard Layout Editor 的 JSON 文件
+    導入配列: impl 動作給一參數得一結果<String>,
 ) -> impl IntoView {
+    let (導入有誤, 設置導入有誤) = signal(false);
+    let 選取文件 = move |ev: leptos::ev::Event| {
+        let 文件 = event_target::<web_sys::HtmlInputElement>(&ev)
+            .files()
+            .and_then(|衆文件| 衆文件.get(0));
+        let Some(文件) = 文件 else {
+            return;
+        };
+        spawn_local(async move {
+            let 文本 = wasm_bindgen_futures::JsFuture::from(文件.text())
+                .await
+                .ok()
+                .and_then(|文本| 文本.as_string());
+            設置導入有誤(文本.is_none_or(|文本| 導入配列(文本).is_err()));
+        });
+    };
+
     let 配列選單的引用 = NodeRef::<html::Select>::new();
     let _ = Effect::new(move |_| {
         if let Some(輸入欄) = 配列選單的引用.get() {
@@ -41,5 +62,9 @@ pub fn Rime配列選單(
             }).collect_view()
         }
         </select>
+        <label class="status-item layout-import" title="導入 Keyboard Layout Editor 的 JSON 文件">
+            <span class="status-label">{move || if 導入有誤() { "KLE 有誤" } else { "導入 KLE" }}</span>
+            <input type="file" accept=".json,application/json" style="display: none;" on:change=選取文件/>
+        </label>
     }
 }
@@ -1,7 +1,12 @@
 pub mod caption;
+pub mod curriculum;
 pub mod exercise_menu;
+pub mod heatmap;
+pub mod history;
 pub mod input_code;
 pub mod keyboard;
 pub mod layout_menu;
+pub mod replay;
+pub mod statistics;
 pub mod status_bar;
 pub mod theory_menu;
@@ -0,0 +1,96 @@
+use leptos::prelude::*;
+
+use crate::action::{動作, 動作給一參數得一結果};
+use crate::view::status_bar::百分號編碼;
+
+#[component]
+pub fn Rime錄放面板(
+    錄製中: Signal<bool>,
+    放映中: Signal<bool>,
+    /// 擊鍵數與時長, 尚無錄像則爲空
+    錄像摘要: Signal<Option<String>>,
+    /// 錄像的 JSON 文本
+    導出文本: Signal<Option<String>>,
+    重放倍速: Signal<f64>,
+    開始錄製: impl 動作,
+    停止錄製: impl 動作,
+    開始重放: impl 動作,
+    停止重放: impl 動作,
+    切換重放倍速: impl 動作,
+    導入錄像: impl 動作給一參數得一結果<String>,
+    關閉: impl 動作,
+) -> impl IntoView {
+    let (導入文本, 設置導入文本) = signal(String::new());
+    let (導入有誤, 設置導入有誤) = signal(false);
+    let 點擊錄製 = move || {
+        if 錄製中() {
+            停止錄製();
+        } else {
+            開始錄製();
+        }
+    };
+    let 點擊重放 = move || {
+        if 放映中() {
+            停止重放();
+        } else {
+            開始重放();
+        }
+    };
+    let 點擊導入 = move || {
+        let 結果 = 導入錄像(導入文本.get_untracked());
+        設置導入有誤(結果.is_err());
+        if 結果.is_ok() {
+            設置導入文本(String::new());
+        }
+    };
+    let 下載網址 = move || {
+        導出文本().map(|文本| format!("data:application/json;charset=utf-8,{}", 百分號編碼(&文本)))
+    };
+    view! {
+        <div class="practice-history replay-panel">
+            <div class="history-header">
+                <span class="summary-label">"擊鍵錄像"</span>
+                <span class="status-item" on:click=move |_| 點擊錄製() title="從頭錄製本題的擊鍵, 打完即停">
+                    <span class="status-label">{move || if 錄製中() { "停錄" } else { "錄製" }}</span>
+                </span>
+                <span class="status-item" on:click=move |_| 點擊重放() title="將錄像中的擊鍵送回打字機">
+                    <span class="status-label">{move || if 放映中() { "停放" } else { "重放" }}</span>
+                </span>
+                <span class="status-item" on:click=move |_| 切換重放倍速() title="切換重放速度">
+                    <span class="status-value">{move || format!("×{}", 重放倍速())}</span>
+                </span>
+                <a
+                    class="status-item"
+                    href={下載網址}
+                    download="typewriter-recording.json"
+                    title="導出錄像"
+                    style:display=move || if 導出文本.read().is_none() { "none" } else { "inline" }
+                >
+                    <span class="status-label">"導出"</span>
+                </a>
+                <span class="status-item" on:click=move |_| 關閉() title="收起">
+                    <span class="status-label">"✕"</span>
+                </span>
+            </div>
+            <p class="summary-label">
+                {move || 錄像摘要().unwrap_or_else(|| String::from("尚無錄像"))}
+            </p>
+            <div class="replay-import">
+                <textarea
+                    placeholder="貼上導出的錄像"
+                    prop:value=導入文本
+                    on:input=move |ev| 設置導入文本(event_target_value(&ev))
+                    // 在此打字不作練習
+                    on:keydown=|ev| ev.stop_propagation()
+                    on:keyup=|ev| ev.stop_propagation()
+                />
+                <span class="status-item" on:click=move |_| 點擊導入() title="讀入貼上的錄像">
+                    <span class="status-label">"導入"</span>
+                    <span class="status-value" style:display=move || if 導入有誤() { "inline" } else { "none" }>
+                        "格式有誤"
+                    </span>
+                </span>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,78 @@
+use leptos::prelude::*;
+
+use crate::action::動作;
+use crate::gear::statistics::{片段紀錄, 統計摘要};
+
+/// 列出用時最長的片段數
+const 最慢片段數: usize = 3;
+
+#[component]
+pub fn Rime練習小結(
+    摘要: Signal<統計摘要>,
+    衆片段: Signal<Vec<片段紀錄>>,
+    /// 片段的量詞, 如「音節」、「字」
+    片段單位: Signal<&'static str>,
+    /// 比影子慢幾毫秒, 負數爲快; 未與影子競速則爲空
+    影子時差: Signal<Option<f64>>,
+    /// 提議重練時爲待重練的片段數
+    提議重練: Signal<Option<usize>>,
+    點擊重練: impl 動作,
+) -> impl IntoView {
+    let 最慢片段 = move || {
+        let mut 衆片段 = 衆片段();
+        衆片段.sort_by(|甲, 乙| 乙.用時.total_cmp(&甲.用時));
+        衆片段
+            .into_iter()
+            .take(最慢片段數)
+            .map(|片段| format!("{} {:.1}秒", 片段.輸入碼, 片段.用時 / 1000.0))
+            .collect::<Vec<_>>()
+            .join("、")
+    };
+    view! {
+        <div class="session-summary">
+            <span class="summary-item">
+                <span class="summary-value">{move || format!("{:.0}", 摘要.read().每分鐘片段數())}</span>
+                <span class="summary-label">{move || format!("{}/分", 片段單位())}</span>
+            </span>
+            <span class="summary-item">
+                <span class="summary-value">{move || format!("{:.0}", 摘要.read().每分鐘擊數())}</span>
+                <span class="summary-label">"擊/分"</span>
+            </span>
+            <span class="summary-item">
+                <span class="summary-value">{move || format!("{:.0}%", 摘要.read().首擊正確率() * 100.0)}</span>
+                <span class="summary-label">"首擊正確"</span>
+            </span>
+            <span class="summary-item">
+                <span class="summary-value">{move || format!("{:.2}", 摘要.read().平均遲疑 / 1000.0)}</span>
+                <span class="summary-label">"秒遲疑"</span>
+            </span>
+            <Show when={move || 摘要.read().非正規並擊數 > 0}>
+                <span class="summary-item" title="拼式相符, 但未按正規並擊的片段">
+                    <span class="summary-value">{move || 摘要.read().非正規並擊數}</span>
+                    <span class="summary-label">"非正規"</span>
+                </span>
+            </Show>
+            <Show when={move || 影子時差.read().is_some()}>
+                <span class="summary-item" title="與影子相比">
+                    <span class="summary-label">{move || if 影子時差().unwrap_or_default() > 0.0 { "比影子慢" } else { "比影子快" }}</span>
+                    <span class="summary-value">{move || format!("{:.2}", 影子時差().unwrap_or_default().abs() / 1000.0)}</span>
+                    <span class="summary-label">"秒"</span>
+                </span>
+            </Show>
+            <span class="summary-item" title="用時最長的片段">
+                <span class="summary-label">"最慢"</span>
+                <span class="summary-value">{最慢片段}</span>
+            </span>
+            <span
+                class="summary-item retry-offer"
+                style:display=move || if 提議重練.read().is_some() { "flex" } else { "none" }
+                on:click=move |_| 點擊重練()
+                title="只練擊錯或偏慢的片段"
+            >
+                <span class="summary-label">"擊字鍵重練"</span>
+                <span class="summary-value">{move || 提議重練().unwrap_or_default()}</span>
+                <span class="summary-label">{move || format!("{}, Esc 略過", 片段單位())}</span>
+            </span>
+        </div>
+    }
+}
@@ -7,11 +7,52 @@ use crate::gear::{layout::配列, theory::方案選項};
 pub fn Rime狀態欄(
     現行方案: Signal<方案選項>,
     已選配列: Signal<配列>,
+    系統佈局名稱: Signal<String>,
+    /// 方案用到而配列中沒有的鍵, 無則不顯示
+    缺鍵提示: Signal<Option<String>>,
+    導出方案文件: Signal<Option<String>>,
+    紀錄條數: Signal<usize>,
+    熱圖名稱: Signal<String>,
+    /// 並擊方案纔有
+    並擊判定名稱: Signal<Option<String>>,
+    推進方式名稱: Signal<String>,
+    /// 擊錯或偏慢的片段數, 有則可重練
+    重練片段數: Signal<usize>,
+    /// 錄製中、重放中, 或空閒
+    錄像狀態: Signal<&'static str>,
+    影子名稱: Signal<String>,
     點擊方案: impl 動作,
     點擊配列: impl 動作,
+    點擊系統佈局: impl 動作,
+    點擊缺鍵: impl 動作,
+    點擊紀錄: impl 動作,
+    點擊熱圖: impl 動作,
+    點擊並擊判定: impl 動作,
+    點擊推進方式: impl 動作,
+    點擊重練: impl 動作,
+    點擊錄像: impl 動作,
+    點擊影子: impl 動作,
 ) -> impl IntoView {
     let 方案名稱 = move || 現行方案.read().to_string();
     let 佈局名稱 = move || 已選配列.read().to_string();
+    let 導出連結 = move || {
+        導出方案文件().map(|方案文件| {
+            let 文件名 = format!("{}.schema.yaml", 現行方案.read().slug());
+            let 網址 = format!("data:text/yaml;charset=utf-8,{}", 百分號編碼(&方案文件));
+            view! {
+                <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+                <a class="status-item" href={網址} download={文件名} title="導出 Rime 並擊方案">
+                    <span class="status-label">導出</span>
+                    <span class="status-value">Rime</span>
+                </a>
+            }
+        })
+    };
+    let 並擊判定名稱表示 = move || 並擊判定名稱().unwrap_or_default();
+    let 隱藏並擊判定 = move || 並擊判定名稱.read().is_none();
+    let 隱藏重練 = move || 重練片段數() == 0;
+    let 隱藏缺鍵 = move || 缺鍵提示.read().is_none();
+    let 缺鍵提示表示 = move || 缺鍵提示().unwrap_or_default();
     view! {
         <div class="status-bar">
             <div class="status-item" on:click=move |_| 點擊方案() title="切換輸入方案">
@@ -23,6 +64,70 @@ pub fn Rime狀態欄(
                 <span class="status-label">佈局</span>
                 <span class="status-value">{佈局名稱}</span>
             </div>
+            <span style:display=move || if 隱藏缺鍵() { "none" } else { "contents" }>
+                <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+                <div class="status-item warning" on:click=move |_| 點擊缺鍵() title="方案用到而配列中沒有的鍵. 點擊以配列中閒置的鍵代替, 再點擊取消">
+                    <span class="status-label">缺鍵</span>
+                    <span class="status-value">{缺鍵提示表示}</span>
+                </div>
+            </span>
+            <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+            <div class="status-item" on:click=move |_| 點擊系統佈局() title="操作系統的鍵盤佈局, 按鍵依此換成方案所認的鍵">
+                <span class="status-label">系統</span>
+                <span class="status-value">{系統佈局名稱}</span>
+            </div>
+            <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+            <div class="status-item" on:click=move |_| 點擊紀錄() title="查看本方案的練習紀錄">
+                <span class="status-label">紀錄</span>
+                <span class="status-value">{紀錄條數}</span>
+            </div>
+            <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+            <div class="status-item" on:click=move |_| 點擊熱圖() title="按擊錯率或用時爲鍵位着色">
+                <span class="status-label">熱圖</span>
+                <span class="status-value">{熱圖名稱}</span>
+            </div>
+            <span style:display=move || if 隱藏並擊判定() { "none" } else { "contents" }>
+                <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+                <div class="status-item" on:click=move |_| 點擊並擊判定() title="並擊與目標拼式相同而鍵位不同時, 是否算擊中">
+                    <span class="status-label">判定</span>
+                    <span class="status-value">{並擊判定名稱表示}</span>
+                </div>
+            </span>
+            <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+            <div class="status-item" on:click=move |_| 點擊推進方式() title="擊錯時須重擊, 或記下錯處繼續推進">
+                <span class="status-label">推進</span>
+                <span class="status-value">{推進方式名稱}</span>
+            </div>
+            <span style:display=move || if 隱藏重練() { "none" } else { "contents" }>
+                <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+                <div class="status-item" on:click=move |_| 點擊重練() title="只練擊錯或偏慢的片段">
+                    <span class="status-label">重練</span>
+                    <span class="status-value">{重練片段數}</span>
+                </div>
+            </span>
+            <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+            <div class="status-item" on:click=move |_| 點擊錄像() title="錄製擊鍵, 或重放、導出錄像">
+                <span class="status-label">錄像</span>
+                <span class="status-value">{錄像狀態}</span>
+            </div>
+            <span style="color: var(--secondary-fg-color); opacity: 0.2">"|"</span>
+            <div class="status-item" on:click=move |_| 點擊影子() title="與本題的個人最佳或錄像競速">
+                <span class="status-label">影子</span>
+                <span class="status-value">{影子名稱}</span>
+            </div>
+            {導出連結}
         </div>
     }
 }
+
+pub fn 百分號編碼(文本: &str) -> String {
+    文本
+        .bytes()
+        .map(|字節| match 字節 {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (字節 as char).to_string()
+            }
+            _ => format!("%{字節:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::adaptive::{片段庫, 生成加練};
+use typewriter::gear::assignment::解析碼表;
+use typewriter::gear::history::{片段成績, 練習成績};
+use typewriter::gear::statistics::統計摘要;
+use typewriter::gear::theory::{
+    方案選單, 方案選項, 生成方案定義, 輸入方案環境
+};
+
+/// 線性同餘法, 使抽取結果可重現
+fn 偽隨機數() -> impl FnMut() -> f64 {
+    let mut 狀態: u64 = 2024;
+    move || {
+        狀態 = 狀態
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (狀態 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[test]
+fn 寫出的碼表解析後得到原片段() {
+    for (選項, 生成方案) in 方案選單.iter() {
+        let 方案 = 生成方案(輸入方案環境::default());
+        let Some(庫) = 片段庫::收集(選項.配套練習題().unwrap_or(&[]), &方案) else {
+            continue;
+        };
+        let 全部 = 庫.衆片段.iter().collect::<Vec<_>>();
+        let 解析所得 = 解析碼表(&庫.寫成碼表(&全部), &方案);
+        assert_eq!(*解析所得, *庫.衆片段, "[{}]", 選項.slug());
+    }
+}
+
+#[test]
+fn 易錯片段多出現() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let 庫 = 片段庫::收集(方案選項::宮保拼音.配套練習題().unwrap(), &方案).unwrap();
+    // 練過庫中全部片段, 唯有一個屢屢擊錯
+    let 易錯 = 庫.衆片段[0].顯示輸入碼().unwrap().to_owned();
+    let 紀錄 = 練習成績 {
+        方案: "combo_pinyin".to_owned(),
+        題號: None,
+        標題: String::new(),
+        配列: String::new(),
+        日期: 0.0,
+        摘要: 統計摘要::default(),
+        衆片段: 庫
+            .衆片段
+            .iter()
+            .map(|片段| {
+                let 輸入碼 = 片段.顯示輸入碼().unwrap().to_owned();
+                片段成績 {
+                    擊錯次數: if 輸入碼 == 易錯 { 5 } else { 0 },
+                    輸入碼,
+                    次數: 1,
+                    用時: 1000.0,
+                }
+            })
+            .collect(),
+    };
+    let 題量 = 庫.衆片段.len() * 4;
+    let 碼表 = 生成加練(&庫, &方案, &[紀錄], 0.0, 題量, 偽隨機數());
+    let 衆片段 = 解析碼表(&碼表, &方案);
+    assert_eq!(衆片段.len(), 題量);
+    let 出現次數 = 衆片段
+        .iter()
+        .filter(|片段| 片段.顯示輸入碼() == Some(易錯.as_str()))
+        .count();
+    assert!(出現次數 > 4 * 2, "{易錯} 出現 {出現次數} 次");
+}
@@ -0,0 +1,70 @@
+//! 按容錯並擊擊中同一拼式時, 各種並擊判定的結果
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+mod common;
+
+use common::並擊;
+use typewriter::definition::碼表格式;
+use typewriter::gear::assignment::{作業內容, 碼表定義};
+use typewriter::gear::caption::字幕格式;
+use typewriter::gear::chord::並擊判定;
+use typewriter::gear::statistics::{作答位置, 擊鍵紀錄, 練習統計};
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::headless::引擎核心;
+use typewriter::theory_audit::遍歷並擊空間;
+
+#[test]
+fn 容錯並擊按判定擊中或標記() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let 報告 = 遍歷並擊空間(&方案, 3);
+    let 重碼 = 報告.重碼();
+    let mut 核心 = 引擎核心::new(方案);
+
+    // 找一個有正規並擊之外的並擊可得的拼式
+    let (拼式, 容錯鍵位) = 重碼
+        .iter()
+        .find_map(|(拼式, 衆並擊)| {
+            核心.佈置作業(Some(作業內容 {
+                碼表: 碼表定義::生成(碼表格式::並擊, format!("<{拼式}> <{拼式}>")),
+                字幕: 字幕格式::自動生成,
+            }));
+            let 正規鍵位 = 核心.反查鍵位()?;
+            衆並擊
+                .iter()
+                .find(|並擊| 並擊.鍵位 != 正規鍵位)
+                .map(|並擊| (拼式.to_string(), 並擊.鍵位.clone()))
+        })
+        .expect("宮保拼音應有容錯並擊");
+
+    核心.設置並擊判定(並擊判定::同拼式);
+    let 反饋 = 並擊(&mut 核心, &容錯鍵位);
+    assert!(反饋.作業推進 && !反饋.非正規並擊, "{拼式}");
+
+    核心.重置作業進度();
+    核心.設置並擊判定(並擊判定::正規並擊);
+    let 反饋 = 並擊(&mut 核心, &容錯鍵位);
+    assert!(!反饋.作業推進 && 反饋.擊鍵有誤, "{拼式}");
+    核心.重置並擊狀態();
+    let 正規鍵位 = 核心.反查鍵位().unwrap();
+    assert!(並擊(&mut 核心, &正規鍵位).作業推進, "{拼式}");
+
+    核心.重置作業進度();
+    核心.設置並擊判定(並擊判定::標記非正規);
+    let mut 統計 = 練習統計::default();
+    let 作答 = 作答位置::取自(&核心);
+    let 反饋 = 並擊(&mut 核心, &容錯鍵位);
+    assert!(反饋.作業推進 && 反饋.非正規並擊, "{拼式}");
+    統計.記錄(
+        0.0,
+        &擊鍵紀錄 {
+            落鍵: false,
+            作答,
+            反饋,
+        },
+    );
+    assert_eq!(統計.摘要().非正規並擊數, 1);
+}
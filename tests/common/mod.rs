@@ -0,0 +1,71 @@
+//! 各測試共用的輔助函數
+
+#![allow(dead_code)]
+
+use typewriter::definition::鍵組;
+use typewriter::drill::題目來源;
+use typewriter::gear::assignment::作業內容;
+use typewriter::gear::statistics::{作答位置, 擊鍵紀錄, 練習統計};
+use typewriter::gear::theory::方案選項;
+use typewriter::headless::{引擎核心, 擊鍵反饋};
+
+/// 依次按下各鍵, 再依次抬起, 返回末次抬鍵的反饋
+pub fn 並擊(核心: &mut 引擎核心, 鍵位: &鍵組) -> 擊鍵反饋 {
+    for &鍵 in &鍵位.0 {
+        核心.落鍵(鍵);
+    }
+    let mut 反饋 = 擊鍵反饋::default();
+    for &鍵 in &鍵位.0 {
+        反饋 = 核心.抬鍵(鍵);
+    }
+    反饋
+}
+
+/// 並擊一次並記入統計, 各鍵相隔 10 毫秒
+pub fn 計時並擊(
+    核心: &mut 引擎核心,
+    統計: &mut 練習統計,
+    鍵位: &鍵組,
+    時刻: &mut f64,
+) -> 擊鍵反饋 {
+    let mut 反饋 = 擊鍵反饋::default();
+    for (落鍵, &鍵) in 鍵位
+        .0
+        .iter()
+        .map(|鍵| (true, 鍵))
+        .chain(鍵位.0.iter().map(|鍵| (false, 鍵)))
+    {
+        let 作答 = 作答位置::取自(核心);
+        反饋 = if 落鍵 {
+            核心.落鍵(鍵)
+        } else {
+            核心.抬鍵(鍵)
+        };
+        *時刻 += 10.0;
+        統計.記錄(
+            *時刻,
+            &擊鍵紀錄 {
+                落鍵, 作答, 反饋
+            },
+        );
+    }
+    反饋
+}
+
+/// 佈置方案配套練習題中首道分段的內建練習題, 返回題號
+pub fn 佈置分段練習題(核心: &mut 引擎核心, 方案: 方案選項) -> Option<usize> {
+    方案
+        .配套練習題()?
+        .iter()
+        .enumerate()
+        .find_map(|(題號, 題)| match &題.題目 {
+            題目來源::內建 { 編碼, 字幕 } => {
+                核心.佈置作業(Some(作業內容 {
+                    碼表: 編碼.clone(),
+                    字幕: 字幕.clone(),
+                }));
+                (核心.分段字幕().len() > 1).then_some(題號)
+            }
+            題目來源::求取 { .. } => None,
+        })
+}
@@ -0,0 +1,41 @@
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::assignment::解析碼表;
+use typewriter::gear::curriculum::{生成課程練習, 編排課程, 解鎖次序};
+use typewriter::gear::heatmap::片段鍵位;
+use typewriter::gear::theory::{方案選單, 輸入方案環境};
+
+#[test]
+fn 課程只出已解鎖的鍵打得出的片段() {
+    for (選項, 生成方案) in 方案選單.iter() {
+        let 方案 = 生成方案(輸入方案環境::default());
+        let 練習題 = 選項.配套練習題().unwrap_or(&[]);
+        let 次序 = 解鎖次序(&方案, 練習題);
+        let 課程 = 編排課程(&方案, 練習題, &次序, 0);
+        assert!(!課程.片段庫.衆片段.is_empty(), "[{}]", 選項.slug());
+        let mut 計數 = 0;
+        let 碼表 = 生成課程練習(&方案, &課程, 20, || {
+            計數 += 1;
+            (計數 as f64 * 0.37).fract()
+        });
+        for 片段 in 解析碼表(&碼表, &方案).iter() {
+            let 鍵位 = match &片段.字根碼原文 {
+                Some(字根碼) => 方案
+                    .讀出鍵位(字根碼, &Default::default())
+                    .0
+                    .into_iter()
+                    .collect(),
+                None => 片段鍵位(&方案, 片段.顯示輸入碼().unwrap_or_default()),
+            };
+            assert!(
+                鍵位.iter().all(|鍵| 課程.已解鎖.contains(鍵)),
+                "[{}] {:?}",
+                選項.slug(),
+                片段
+            );
+        }
+    }
+}
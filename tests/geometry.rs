@@ -0,0 +1,56 @@
+//! 配列幾何: 內建配列按矩陣排鍵, 拇指區可容多個鍵
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use strum::IntoEnumIterator;
+use typewriter::gear::layout::{矩陣座標, 配列};
+use typewriter::key_code::KeyCode;
+
+#[test]
+fn 內建配列諸鍵各有位置且不重疊() {
+    for 配列 in 配列::iter() {
+        let 幾何 = 配列.幾何();
+        let 鍵數 = 配列
+            .矩陣()
+            .iter()
+            .flat_map(|此行| 此行.iter())
+            .filter(|&&鍵| 鍵 != KeyCode::No)
+            .count();
+        assert_eq!(幾何.衆鍵位.len(), 鍵數, "{配列}");
+        let (寬, 高) = 幾何.尺寸();
+        assert!(寬 > 0.0 && 高 > 0.0);
+        for (序, 甲) in 幾何.衆鍵位.iter().enumerate() {
+            assert!(甲.左 >= -1e-9 && 甲.上 >= -1e-9, "{配列}: {:?}", 甲.鍵碼);
+            for 乙 in &幾何.衆鍵位[序 + 1..] {
+                let 重疊 = 甲.左 < 乙.左 + 乙.寬 - 1e-9
+                    && 乙.左 < 甲.左 + 甲.寬 - 1e-9
+                    && 甲.上 < 乙.上 + 乙.高 - 1e-9
+                    && 乙.上 < 甲.上 + 甲.高 - 1e-9;
+                assert!(!重疊, "{配列}: {:?} {:?}", 甲.鍵碼, 乙.鍵碼);
+            }
+        }
+    }
+    // 錯位: B 行首鍵在 E 行首鍵右方
+    let 幾何 = 配列::主鍵盤區.幾何();
+    let 左 = |鍵| 幾何.鍵位(鍵).unwrap().左;
+    assert_eq!(左(KeyCode::Z) - 左(KeyCode::Kc1), 1.25);
+}
+
+#[test]
+fn 拇指簇配列每手三鍵() {
+    let 幾何 = 配列::拇指簇分體.幾何();
+    let 拇指鍵 = 幾何
+        .衆鍵位
+        .iter()
+        .filter(|鍵位| 鍵位.拇指鍵)
+        .collect::<Vec<_>>();
+    assert_eq!(拇指鍵.len(), 6);
+    assert!(拇指鍵.iter().all(|鍵位| 鍵位.轉角 != 0.0));
+    // 功能鍵不取盤面上的刻印
+    let 刻印座標 = |鍵| 幾何.鍵位(鍵).unwrap().刻印座標;
+    assert_eq!(刻印座標(KeyCode::Tab), None);
+    assert_eq!(刻印座標(KeyCode::Space), Some(矩陣座標(4, 1)));
+}
@@ -0,0 +1,112 @@
+//! 影子競速: 按錄像推算影子步調, 只留用時最短的一輪
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use std::borrow::Cow;
+
+use typewriter::definition::碼表格式;
+use typewriter::gear::assignment::{作業內容, 碼表定義};
+use typewriter::gear::caption::字幕格式;
+use typewriter::gear::ghost::{影子軌跡, 更新最佳紀錄};
+use typewriter::gear::input::{擊鍵事件, 擊鍵來源};
+use typewriter::gear::replay::{擊鍵錄像, 錄像作業};
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::headless::引擎核心;
+
+fn 錄一輪(間隔: f64) -> (引擎核心, 擊鍵錄像) {
+    錄製("<ba> <ma>", 字幕格式::自動生成, 間隔)
+}
+
+/// 逐個音節並擊, 直至打完一題
+fn 錄製(
+    編碼: &str, 字幕: 字幕格式<'static>, 間隔: f64
+) -> (引擎核心, 擊鍵錄像) {
+    let mut 核心 =
+        引擎核心::new(生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap());
+    核心.佈置作業(Some(作業內容 {
+        碼表: 碼表定義::生成(碼表格式::並擊, 編碼.to_owned()),
+        字幕,
+    }));
+    let mut 衆事件 = vec![];
+    let mut 時刻 = 0.0;
+    let mut 打完 = false;
+    while !打完 {
+        let 鍵位 = 核心.反查鍵位().unwrap();
+        for 落鍵 in [true, false] {
+            for &鍵碼 in &鍵位.0 {
+                if 落鍵 {
+                    核心.落鍵(鍵碼);
+                } else {
+                    打完 |= 核心.抬鍵(鍵碼).打完一題;
+                }
+                衆事件.push(擊鍵事件 {
+                    時刻,
+                    鍵碼,
+                    落鍵,
+                    來源: 擊鍵來源::實體鍵盤,
+                    上檔: false,
+                });
+            }
+            時刻 += 間隔;
+        }
+    }
+    核心.重置作業進度();
+    let 錄像 = 擊鍵錄像 {
+        方案: 方案選項::宮保拼音.slug().to_owned(),
+        作業: 錄像作業 {
+            題號: Some(0),
+            ..Default::default()
+        },
+        推進方式: Default::default(),
+        並擊判定: Default::default(),
+        方案文件: None,
+        日期: 間隔,
+        衆事件,
+    };
+    (核心, 錄像)
+}
+
+#[test]
+fn 影子按錄像步調前進() {
+    let (核心, 錄像) = 錄一輪(100.0);
+    let 軌跡 = 影子軌跡::推算(*核心.方案(), 核心.作業內容().unwrap().clone(), &錄像);
+    // 每個音節落鍵、抬鍵各隔 100 毫秒, 抬鍵時推進
+    assert_eq!(軌跡.進度(0.0), 0);
+    assert_eq!(軌跡.進度(100.0), 1);
+    assert_eq!(軌跡.進度(250.0), 1);
+    assert_eq!(軌跡.進度(300.0), 2);
+    assert_eq!(軌跡.完成時刻, Some(300.0));
+
+    let 影子字幕 = 核心.當前段落().unwrap().影子表示(1).unwrap();
+    assert_eq!(
+        (影子字幕.之前.as_str(), 影子字幕.指標文字.as_str()),
+        ("ba ", "ma ")
+    );
+}
+
+#[test]
+fn 分段的作業迴轉前記下完成時刻() {
+    let (核心, 錄像) = 錄製(
+        "<ba> <ma> <da>",
+        字幕格式::自訂(Cow::Borrowed("八媽 大")),
+        100.0,
+    );
+    // 打完即迴轉到開頭, 影子仍停在末尾
+    let 軌跡 = 影子軌跡::推算(*核心.方案(), 核心.作業內容().unwrap().clone(), &錄像);
+    assert_eq!(軌跡.完成時刻, Some(500.0));
+    assert_eq!(軌跡.進度(450.0), 2);
+    assert_eq!(軌跡.進度(500.0), 3);
+}
+
+#[test]
+fn 只留最快的一輪() {
+    let mut 衆紀錄 = vec![];
+    assert!(更新最佳紀錄(&mut 衆紀錄, 錄一輪(100.0).1));
+    assert!(!更新最佳紀錄(&mut 衆紀錄, 錄一輪(150.0).1));
+    assert!(更新最佳紀錄(&mut 衆紀錄, 錄一輪(80.0).1));
+    assert_eq!(衆紀錄.len(), 1);
+    assert_eq!(衆紀錄[0].時長(), 80.0 * 3.0);
+}
@@ -0,0 +1,293 @@
+//! 無頭引擎: 並擊與連擊錄入、作業推進、退格及段落跳轉
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+mod common;
+
+use std::borrow::Cow;
+
+use common::{並擊, 佈置分段練習題, 計時並擊};
+use typewriter::definition::{碼表格式, 鍵組};
+use typewriter::gear::assignment::{作業內容, 步進法, 碼表定義};
+use typewriter::gear::caption::字幕格式;
+use typewriter::gear::statistics::練習統計;
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::headless::{功能鍵反饋, 引擎核心, 擊鍵反饋};
+use typewriter::key_code::KeyCode;
+
+fn 佈置(
+    方案: 方案選項, 格式: 碼表格式, 編碼: &str, 字幕: 字幕格式<'static>
+) -> 引擎核心 {
+    let mut 核心 = 引擎核心::new(生成方案定義(方案, 輸入方案環境::default()).unwrap());
+    核心.佈置作業(Some(作業內容 {
+        碼表: 碼表定義::生成(格式, 編碼.to_owned()),
+        字幕,
+    }));
+    核心
+}
+
+/// 按提示擊一個字根, 返回落鍵的反饋
+fn 連擊一鍵(核心: &mut 引擎核心) -> 擊鍵反饋 {
+    let 鍵 = *核心.逐鍵提示().unwrap().0.first().unwrap();
+    let 反饋 = 核心.落鍵(鍵);
+    核心.抬鍵(鍵);
+    反饋
+}
+
+/// 偷看第 `進度` 個片段的並擊鍵位
+fn 片段鍵位(核心: &mut 引擎核心, 進度: usize) -> 鍵組 {
+    let 原進度 = 核心.作業進度();
+    let 跳至 = |核心: &mut 引擎核心, 目標| {
+        核心.重置作業進度();
+        核心
+            .作業推進(步進法 {
+                目標: Some(目標),
+                迴轉: false,
+            })
+            .ok();
+    };
+    跳至(核心, 進度);
+    let 鍵位 = 核心.反查鍵位().unwrap();
+    跳至(核心, 原進度);
+    鍵位
+}
+
+#[test]
+fn 並擊擊中方可推進() {
+    let mut 核心 = 佈置(
+        方案選項::宮保拼音,
+        碼表格式::並擊,
+        "<ba> <ma>",
+        字幕格式::自動生成,
+    );
+    let ba = 片段鍵位(&mut 核心, 0);
+    let ma = 片段鍵位(&mut 核心, 1);
+
+    let 反饋 = 並擊(&mut 核心, &ma);
+    assert!(反饋.擊發 && 反饋.擊鍵有誤 && !反饋.作業推進);
+    assert_eq!(核心.作業進度(), 0);
+    assert!(核心.擊錯片段().is_empty());
+
+    let 反饋 = 並擊(&mut 核心, &ba);
+    assert!(反饋.擊發 && !反饋.擊鍵有誤 && 反饋.作業推進);
+    assert_eq!(核心.作業進度(), 1);
+    // 推進後清除上一題的並擊
+    assert!(核心.實況並擊碼().is_empty());
+
+    let 反饋 = 並擊(&mut 核心, &ma);
+    assert!(反饋.作業推進);
+    assert!(核心.作業進度完成());
+    // 全文完成後停下顯示結果
+    assert_eq!(核心.並擊所得拼音().as_deref(), Some("ma"));
+
+    let 反饋 = 核心.落鍵(*ba.0.first().unwrap());
+    assert!(反饋.轉入自習);
+    assert!(!核心.有無作業());
+}
+
+#[test]
+fn 連擊逐個字根推進() {
+    let mut 核心 = 佈置(
+        方案選項::動態能力注音,
+        碼表格式::連擊,
+        "ㄅㄚˉ ㄇㄚˉ",
+        字幕格式::自動生成,
+    );
+    let 反饋 = 連擊一鍵(&mut 核心);
+    assert!(反饋.擊發 && !反饋.擊鍵有誤 && !反饋.作業推進);
+    assert_eq!(核心.已錄入字根碼(), "ㄅ");
+    連擊一鍵(&mut 核心);
+    assert_eq!(核心.已錄入字根碼(), "ㄅㄚ");
+    assert_eq!(核心.作業進度(), 0);
+    let 反饋 = 連擊一鍵(&mut 核心);
+    assert!(反饋.作業推進);
+    assert_eq!(核心.作業進度(), 1);
+    assert!(核心.連擊片段完成());
+
+    // 下一音節起首擊錯
+    let ㄉ = 核心
+        .方案()
+        .讀出鍵位("ㄉ", &核心.當選盤面())
+        .0
+        .into_iter()
+        .next()
+        .unwrap();
+    let 反饋 = 核心.落鍵(ㄉ);
+    核心.抬鍵(ㄉ);
+    assert!(反饋.擊發 && 反饋.擊鍵有誤 && !反饋.作業推進);
+    assert_eq!(核心.作業進度(), 1);
+
+    // 清除擊錯的字根, 盤面隨之復位
+    核心.重置輸入狀態();
+    assert_eq!(核心.已錄入字根碼(), "");
+    for _ in 0..3 {
+        連擊一鍵(&mut 核心);
+    }
+    assert!(核心.作業進度完成());
+}
+
+#[test]
+fn 連擊退格() {
+    let mut 核心 = 佈置(
+        方案選項::動態能力注音,
+        碼表格式::連擊,
+        "ㄅㄚˉ ㄇㄚˉ",
+        字幕格式::自動生成,
+    );
+    連擊一鍵(&mut 核心);
+    連擊一鍵(&mut 核心);
+    核心.退格();
+    assert_eq!(核心.已錄入字根碼(), "ㄅ");
+    核心.退格();
+    assert_eq!(核心.已錄入字根碼(), "");
+    // 無可刪之字根碼, 亦無上一題
+    核心.退格();
+    assert_eq!(核心.作業進度(), 0);
+
+    for _ in 0..3 {
+        連擊一鍵(&mut 核心);
+    }
+    assert_eq!(核心.作業進度(), 1);
+    // 剛完成一字, 退格即退回上一題
+    核心.退格();
+    assert_eq!(核心.作業進度(), 0);
+    assert_eq!(核心.已錄入字根碼(), "");
+}
+
+#[test]
+fn 並擊退格() {
+    let mut 核心 = 佈置(
+        方案選項::宮保拼音,
+        碼表格式::並擊,
+        "<ba> <ma>",
+        字幕格式::自動生成,
+    );
+    let ba = 片段鍵位(&mut 核心, 0);
+    let ma = 片段鍵位(&mut 核心, 1);
+    並擊(&mut 核心, &ma);
+    // 擊錯的並擊留在原地, 退格清除之
+    assert!(核心.並擊完成());
+    核心.退格();
+    assert!(!核心.並擊完成());
+    assert_eq!(核心.作業進度(), 0);
+
+    並擊(&mut 核心, &ba);
+    assert_eq!(核心.作業進度(), 1);
+    核心.退格();
+    assert_eq!(核心.作業進度(), 0);
+    // 已在開頭, 不再回退
+    核心.退格();
+    assert_eq!(核心.作業進度(), 0);
+}
+
+#[test]
+fn 段落跳轉首尾相接() {
+    let mut 核心 = 佈置(
+        方案選項::宮保拼音,
+        碼表格式::並擊,
+        "<ba> <ma> <da>",
+        字幕格式::自訂(Cow::Borrowed("八媽 大")),
+    );
+    assert_eq!(核心.分段字幕().len(), 2);
+
+    assert!(核心.跳轉段落(false).is_ok());
+    assert_eq!(核心.作業進度(), 2);
+    assert!(核心.跳轉段落(false).is_ok());
+    assert_eq!(核心.作業進度(), 0);
+
+    // 自首段回到前段, 即末段
+    assert!(核心.跳轉段落(true).is_ok());
+    assert_eq!(核心.作業進度(), 2);
+    assert!(核心.跳轉段落(true).is_ok());
+    assert_eq!(核心.作業進度(), 0);
+
+    // 跳轉時清除未完成的並擊
+    let ma = 片段鍵位(&mut 核心, 1);
+    並擊(&mut 核心, &ma);
+    assert!(核心.並擊完成());
+    assert!(核心.跳轉段落(false).is_ok());
+    assert!(!核心.並擊完成());
+}
+
+#[test]
+fn 錄入時的功能鍵() {
+    let mut 核心 = 佈置(
+        方案選項::宮保拼音,
+        碼表格式::並擊,
+        "<ba> <ma> <da>",
+        字幕格式::自訂(Cow::Borrowed("八媽 大")),
+    );
+    assert_eq!(核心.功能鍵(KeyCode::Tab, false), 功能鍵反饋::已處理);
+    assert_eq!(核心.作業進度(), 2);
+    assert_eq!(核心.功能鍵(KeyCode::Backspace, false), 功能鍵反饋::已處理);
+    assert_eq!(核心.作業進度(), 1);
+    // 先回到開頭, 在開頭再按纔開啓選單
+    assert_eq!(核心.功能鍵(KeyCode::Escape, false), 功能鍵反饋::已處理);
+    assert_eq!(核心.作業進度(), 0);
+    assert_eq!(
+        核心.功能鍵(KeyCode::Escape, false),
+        功能鍵反饋::開啓練習題選單
+    );
+    assert_eq!(核心.功能鍵(KeyCode::Enter, false), 功能鍵反饋::開啓反查輸入);
+    assert_eq!(核心.功能鍵(KeyCode::Grave, false), 功能鍵反饋::開啓方案選單);
+    assert_eq!(核心.功能鍵(KeyCode::A, false), 功能鍵反饋::非功能鍵);
+    assert_eq!(核心.作業進度(), 0);
+}
+
+/// 內建的分段練習題打到末尾迴轉到開頭, 仍應報告打完一題, 並保留本輪統計
+#[test]
+fn 打完分段的內建練習題() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let mut 核心 = 引擎核心::new(方案);
+    assert!(佈置分段練習題(&mut 核心, 方案選項::宮保拼音).is_some());
+    let 全文長度 = 核心.目標輸入碼序列().len();
+
+    let mut 統計 = 練習統計::default();
+    let mut 時刻 = 0.0;
+    let mut 末次反饋 = 擊鍵反饋::default();
+    for _ in 0..全文長度 {
+        assert!(!核心.一題打完());
+        let 鍵位 = 核心.反查鍵位().expect("每個片段都應能反查鍵位");
+        末次反饋 = 計時並擊(&mut 核心, &mut 統計, &鍵位, &mut 時刻);
+        assert!(末次反饋.作業推進);
+    }
+    assert!(末次反饋.打完一題);
+    assert!(核心.一題打完());
+    // 迴轉到開頭, 而非停在全文末尾
+    assert_eq!(核心.作業進度(), 0);
+    assert!(!核心.作業進度完成());
+
+    let 摘要 = 統計.摘要();
+    assert_eq!(摘要.片段數, 全文長度);
+    assert_eq!(摘要.首擊正確數, 全文長度);
+    assert_eq!(摘要.擊錯數, 0);
+    assert!(摘要.用時 > 0.0);
+
+    // 再擊鍵即開始新的一輪, 統計從頭算起
+    let 鍵位 = 核心.反查鍵位().unwrap();
+    let 反饋 = 計時並擊(&mut 核心, &mut 統計, &鍵位, &mut 時刻);
+    assert!(反饋.作業推進 && !反饋.打完一題 && !反饋.轉入自習);
+    assert!(!核心.一題打完());
+    assert_eq!(統計.摘要().片段數, 1);
+}
+
+#[test]
+fn 不分段的作業打完即停() {
+    let mut 核心 = 佈置(
+        方案選項::宮保拼音,
+        碼表格式::並擊,
+        "<ba> <ma>",
+        字幕格式::自動生成,
+    );
+    for _ in 0..2 {
+        let 鍵位 = 核心.反查鍵位().unwrap();
+        並擊(&mut 核心, &鍵位);
+    }
+    assert!(核心.一題打完() && 核心.作業進度完成());
+    // 跳轉或退格亦算離開了打完的狀態
+    核心.退格();
+    assert!(!核心.一題打完());
+}
@@ -0,0 +1,36 @@
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::heatmap::{按鍵位彙總, 熱圖指標, 熱度表, 片段鍵位};
+use typewriter::gear::history::片段成績;
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::key_code::KeyCode;
+
+fn 成績(輸入碼: &str, 次數: usize, 擊錯次數: usize) -> 片段成績 {
+    片段成績 {
+        輸入碼: 輸入碼.to_owned(),
+        次數,
+        擊錯次數,
+        用時: 0.0,
+    }
+}
+
+#[test]
+fn 片段成績計入所用各鍵() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let 鍵位表 = 按鍵位彙總(&方案, &[成績("ba", 2, 2), 成績("bi", 1, 0)]);
+    let 共用鍵 = 片段鍵位(&方案, "ba")
+        .into_iter()
+        .filter(|鍵| 片段鍵位(&方案, "bi").contains(鍵))
+        .collect::<Vec<_>>();
+    assert!(!共用鍵.is_empty());
+    for 鍵 in &共用鍵 {
+        assert_eq!((鍵位表[鍵].次數, 鍵位表[鍵].擊錯次數), (3, 2));
+    }
+    let 熱度 = 熱度表(&鍵位表, 熱圖指標::擊錯率);
+    let 最熱 = 熱度.values().copied().fold(0.0, f64::max);
+    assert_eq!(最熱, 1.0);
+    assert!(!熱度.contains_key(&KeyCode::Escape));
+}
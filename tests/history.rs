@@ -0,0 +1,112 @@
+//! 練習紀錄: 片段合併、存儲, 及打完一題時記下成績
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+mod common;
+
+use common::{佈置分段練習題, 計時並擊};
+use typewriter::gear::{
+    assignment::作業,
+    history::{內存存儲, 成績存儲, 練習成績},
+    layout::配列,
+    statistics::{片段紀錄, 統計摘要, 練習統計},
+    theory::{方案選項, 生成方案定義, 輸入方案環境},
+};
+use typewriter::headless::引擎核心;
+
+fn 片段(輸入碼: &str, 擊錯次數: usize, 用時: f64) -> 片段紀錄 {
+    片段紀錄 {
+        進度: 0,
+        輸入碼: 輸入碼.to_owned(),
+        遲疑: 0.0,
+        用時,
+        擊錯次數,
+    }
+}
+
+fn 成績(日期: f64) -> 練習成績 {
+    練習成績 {
+        方案: "combo_pinyin".to_owned(),
+        題號: Some(0),
+        標題: "聲母".to_owned(),
+        配列: "ortho".to_owned(),
+        日期,
+        摘要: 統計摘要::default(),
+        衆片段: 練習成績::合併片段(&[
+            片段("zh", 2, 800.0),
+            片段("b", 0, 300.0),
+            片段("zh", 1, 500.0),
+        ]),
+    }
+}
+
+#[test]
+fn 同一輸入碼的片段合併計算() {
+    let 成績 = 成績(0.0);
+    assert_eq!(
+        成績
+            .衆片段
+            .iter()
+            .map(|片段| (片段.輸入碼.as_str(), 片段.次數, 片段.擊錯次數, 片段.用時))
+            .collect::<Vec<_>>(),
+        [("zh", 2, 3, 1300.0), ("b", 1, 0, 300.0)]
+    );
+    assert_eq!(
+        成績
+            .易錯片段(3)
+            .iter()
+            .map(|片段| 片段.輸入碼.as_str())
+            .collect::<Vec<_>>(),
+        ["zh"]
+    );
+}
+
+#[test]
+fn 存儲追加紀錄() {
+    let 存儲 = 內存存儲::default();
+    for 日期 in 0..1005 {
+        assert!(存儲.追加(成績(日期 as f64)).is_ok());
+    }
+    let 衆成績 = 存儲.讀取();
+    assert_eq!(衆成績.len(), 1000);
+    assert_eq!(衆成績.first().map(|成績| 成績.日期), Some(5.0));
+    assert_eq!(衆成績.last(), Some(&成績(1004.0)));
+}
+
+/// 分段的練習題打完即迴轉到開頭, 成績仍應在打完一題時記下
+#[test]
+fn 打完分段練習題記下成績() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let mut 核心 = 引擎核心::new(方案);
+    let 題號 = 佈置分段練習題(&mut 核心, 方案選項::宮保拼音).unwrap();
+    let 全文長度 = 核心.目標輸入碼序列().len();
+    let 作業 = 作業::練習題(方案選項::宮保拼音, 題號);
+
+    let mut 統計 = 練習統計::default();
+    let mut 時刻 = 0.0;
+    assert!(練習成績::記下(&作業, 配列::主鍵盤區, 時刻, &統計).is_none());
+    while !核心.一題打完() {
+        let 鍵位 = 核心.反查鍵位().unwrap();
+        計時並擊(&mut 核心, &mut 統計, &鍵位, &mut 時刻);
+    }
+    assert_eq!(核心.作業進度(), 0);
+
+    let 成績 = 練習成績::記下(&作業, 配列::主鍵盤區, 時刻, &統計).unwrap();
+    assert_eq!(成績.方案, "combo_pinyin");
+    assert_eq!(成績.題號, Some(題號));
+    assert_eq!(
+        成績.標題,
+        方案選項::宮保拼音.配套練習題().unwrap()[題號].標題
+    );
+    assert_eq!(成績.摘要.片段數, 全文長度);
+    assert_eq!(
+        成績.衆片段.iter().map(|片段| 片段.次數).sum::<usize>(),
+        全文長度
+    );
+
+    let 存儲 = 內存存儲::default();
+    assert_eq!(存儲.追加(成績.clone()).ok(), Some(vec![成績]));
+}
@@ -0,0 +1,356 @@
+//! 系統佈局: 物理位置換成方案所認的鍵, 並按各位置輸入的字符認出佈局
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::host_layout::{系統佈局, 辨認系統佈局};
+use typewriter::key_code::KeyCode;
+
+#[test]
+fn 按系統佈局換鍵() {
+    assert_eq!(系統佈局::美式.轉換(KeyCode::S), KeyCode::S);
+    assert_eq!(系統佈局::德沃夏克.轉換(KeyCode::S), KeyCode::O);
+    assert_eq!(系統佈局::科爾馬克.轉換(KeyCode::K), KeyCode::E);
+    assert_eq!(系統佈局::法式.轉換(KeyCode::Q), KeyCode::A);
+    assert_eq!(
+        系統佈局::日式.轉換(KeyCode::Backslash),
+        KeyCode::RightBracket
+    );
+}
+
+// 以下各表仿瀏覽器 `navigator.keyboard.getLayoutMap()` 的報告, 列出各系統佈局主鍵盤區的字符
+
+/// Windows 美式鍵盤
+const 美式佈局表: &[(&str, &str)] = &[
+    ("Backquote", "`"),
+    ("Digit1", "1"),
+    ("Digit2", "2"),
+    ("Digit3", "3"),
+    ("Digit4", "4"),
+    ("Digit5", "5"),
+    ("Digit6", "6"),
+    ("Digit7", "7"),
+    ("Digit8", "8"),
+    ("Digit9", "9"),
+    ("Digit0", "0"),
+    ("Minus", "-"),
+    ("Equal", "="),
+    ("KeyQ", "q"),
+    ("KeyW", "w"),
+    ("KeyE", "e"),
+    ("KeyR", "r"),
+    ("KeyT", "t"),
+    ("KeyY", "y"),
+    ("KeyU", "u"),
+    ("KeyI", "i"),
+    ("KeyO", "o"),
+    ("KeyP", "p"),
+    ("BracketLeft", "["),
+    ("BracketRight", "]"),
+    ("KeyA", "a"),
+    ("KeyS", "s"),
+    ("KeyD", "d"),
+    ("KeyF", "f"),
+    ("KeyG", "g"),
+    ("KeyH", "h"),
+    ("KeyJ", "j"),
+    ("KeyK", "k"),
+    ("KeyL", "l"),
+    ("Semicolon", ";"),
+    ("Quote", "'"),
+    ("Backslash", "\\"),
+    ("KeyZ", "z"),
+    ("KeyX", "x"),
+    ("KeyC", "c"),
+    ("KeyV", "v"),
+    ("KeyB", "b"),
+    ("KeyN", "n"),
+    ("KeyM", "m"),
+    ("Comma", ","),
+    ("Period", "."),
+    ("Slash", "/"),
+];
+
+/// Windows 美式 Dvorak
+const 德沃夏克佈局表: &[(&str, &str)] = &[
+    ("Backquote", "`"),
+    ("Digit1", "1"),
+    ("Digit2", "2"),
+    ("Digit3", "3"),
+    ("Digit4", "4"),
+    ("Digit5", "5"),
+    ("Digit6", "6"),
+    ("Digit7", "7"),
+    ("Digit8", "8"),
+    ("Digit9", "9"),
+    ("Digit0", "0"),
+    ("Minus", "["),
+    ("Equal", "]"),
+    ("KeyQ", "'"),
+    ("KeyW", ","),
+    ("KeyE", "."),
+    ("KeyR", "p"),
+    ("KeyT", "y"),
+    ("KeyY", "f"),
+    ("KeyU", "g"),
+    ("KeyI", "c"),
+    ("KeyO", "r"),
+    ("KeyP", "l"),
+    ("BracketLeft", "/"),
+    ("BracketRight", "="),
+    ("KeyA", "a"),
+    ("KeyS", "o"),
+    ("KeyD", "e"),
+    ("KeyF", "u"),
+    ("KeyG", "i"),
+    ("KeyH", "d"),
+    ("KeyJ", "h"),
+    ("KeyK", "t"),
+    ("KeyL", "n"),
+    ("Semicolon", "s"),
+    ("Quote", "-"),
+    ("Backslash", "\\"),
+    ("KeyZ", ";"),
+    ("KeyX", "q"),
+    ("KeyC", "j"),
+    ("KeyV", "k"),
+    ("KeyB", "x"),
+    ("KeyN", "b"),
+    ("KeyM", "m"),
+    ("Comma", "w"),
+    ("Period", "v"),
+    ("Slash", "z"),
+];
+
+/// Colemak
+const 科爾馬克佈局表: &[(&str, &str)] = &[
+    ("Backquote", "`"),
+    ("Digit1", "1"),
+    ("Digit2", "2"),
+    ("Digit3", "3"),
+    ("Digit4", "4"),
+    ("Digit5", "5"),
+    ("Digit6", "6"),
+    ("Digit7", "7"),
+    ("Digit8", "8"),
+    ("Digit9", "9"),
+    ("Digit0", "0"),
+    ("Minus", "-"),
+    ("Equal", "="),
+    ("KeyQ", "q"),
+    ("KeyW", "w"),
+    ("KeyE", "f"),
+    ("KeyR", "p"),
+    ("KeyT", "g"),
+    ("KeyY", "j"),
+    ("KeyU", "l"),
+    ("KeyI", "u"),
+    ("KeyO", "y"),
+    ("KeyP", ";"),
+    ("BracketLeft", "["),
+    ("BracketRight", "]"),
+    ("KeyA", "a"),
+    ("KeyS", "r"),
+    ("KeyD", "s"),
+    ("KeyF", "t"),
+    ("KeyG", "d"),
+    ("KeyH", "h"),
+    ("KeyJ", "n"),
+    ("KeyK", "e"),
+    ("KeyL", "i"),
+    ("Semicolon", "o"),
+    ("Quote", "'"),
+    ("Backslash", "\\"),
+    ("KeyZ", "z"),
+    ("KeyX", "x"),
+    ("KeyC", "c"),
+    ("KeyV", "v"),
+    ("KeyB", "b"),
+    ("KeyN", "k"),
+    ("KeyM", "m"),
+    ("Comma", ","),
+    ("Period", "."),
+    ("Slash", "/"),
+];
+
+/// Windows 法語 AZERTY, 數字行不按上檔輸入的是符號
+const 法式佈局表: &[(&str, &str)] = &[
+    ("Backquote", "²"),
+    ("Digit1", "&"),
+    ("Digit2", "é"),
+    ("Digit3", "\""),
+    ("Digit4", "'"),
+    ("Digit5", "("),
+    ("Digit6", "-"),
+    ("Digit7", "è"),
+    ("Digit8", "_"),
+    ("Digit9", "ç"),
+    ("Digit0", "à"),
+    ("Minus", ")"),
+    ("Equal", "="),
+    ("KeyQ", "a"),
+    ("KeyW", "z"),
+    ("KeyE", "e"),
+    ("KeyR", "r"),
+    ("KeyT", "t"),
+    ("KeyY", "y"),
+    ("KeyU", "u"),
+    ("KeyI", "i"),
+    ("KeyO", "o"),
+    ("KeyP", "p"),
+    ("BracketLeft", "^"),
+    ("BracketRight", "$"),
+    ("KeyA", "q"),
+    ("KeyS", "s"),
+    ("KeyD", "d"),
+    ("KeyF", "f"),
+    ("KeyG", "g"),
+    ("KeyH", "h"),
+    ("KeyJ", "j"),
+    ("KeyK", "k"),
+    ("KeyL", "l"),
+    ("Semicolon", "m"),
+    ("Quote", "ù"),
+    ("Backslash", "*"),
+    ("KeyZ", "w"),
+    ("KeyX", "x"),
+    ("KeyC", "c"),
+    ("KeyV", "v"),
+    ("KeyB", "b"),
+    ("KeyN", "n"),
+    ("KeyM", ","),
+    ("Comma", ";"),
+    ("Period", ":"),
+    ("Slash", "!"),
+    ("IntlBackslash", "<"),
+];
+
+/// Windows 日語 JIS, `半角/全角` 鍵不在表中
+const 日式佈局表: &[(&str, &str)] = &[
+    ("Digit1", "1"),
+    ("Digit2", "2"),
+    ("Digit3", "3"),
+    ("Digit4", "4"),
+    ("Digit5", "5"),
+    ("Digit6", "6"),
+    ("Digit7", "7"),
+    ("Digit8", "8"),
+    ("Digit9", "9"),
+    ("Digit0", "0"),
+    ("Minus", "-"),
+    ("Equal", "^"),
+    ("KeyQ", "q"),
+    ("KeyW", "w"),
+    ("KeyE", "e"),
+    ("KeyR", "r"),
+    ("KeyT", "t"),
+    ("KeyY", "y"),
+    ("KeyU", "u"),
+    ("KeyI", "i"),
+    ("KeyO", "o"),
+    ("KeyP", "p"),
+    ("BracketLeft", "@"),
+    ("BracketRight", "["),
+    ("KeyA", "a"),
+    ("KeyS", "s"),
+    ("KeyD", "d"),
+    ("KeyF", "f"),
+    ("KeyG", "g"),
+    ("KeyH", "h"),
+    ("KeyJ", "j"),
+    ("KeyK", "k"),
+    ("KeyL", "l"),
+    ("Semicolon", ";"),
+    ("Quote", ":"),
+    ("Backslash", "]"),
+    ("KeyZ", "z"),
+    ("KeyX", "x"),
+    ("KeyC", "c"),
+    ("KeyV", "v"),
+    ("KeyB", "b"),
+    ("KeyN", "n"),
+    ("KeyM", "m"),
+    ("Comma", ","),
+    ("Period", "."),
+    ("Slash", "/"),
+    ("IntlYen", "¥"),
+    ("IntlRo", "\\"),
+];
+
+/// Windows 德語 QWERTZ, 不在所列佈局之中
+const 德式佈局表: &[(&str, &str)] = &[
+    ("Backquote", "^"),
+    ("Digit1", "1"),
+    ("Digit2", "2"),
+    ("Digit3", "3"),
+    ("Digit4", "4"),
+    ("Digit5", "5"),
+    ("Digit6", "6"),
+    ("Digit7", "7"),
+    ("Digit8", "8"),
+    ("Digit9", "9"),
+    ("Digit0", "0"),
+    ("Minus", "ß"),
+    ("Equal", "´"),
+    ("KeyQ", "q"),
+    ("KeyW", "w"),
+    ("KeyE", "e"),
+    ("KeyR", "r"),
+    ("KeyT", "t"),
+    ("KeyY", "z"),
+    ("KeyU", "u"),
+    ("KeyI", "i"),
+    ("KeyO", "o"),
+    ("KeyP", "p"),
+    ("BracketLeft", "ü"),
+    ("BracketRight", "+"),
+    ("KeyA", "a"),
+    ("KeyS", "s"),
+    ("KeyD", "d"),
+    ("KeyF", "f"),
+    ("KeyG", "g"),
+    ("KeyH", "h"),
+    ("KeyJ", "j"),
+    ("KeyK", "k"),
+    ("KeyL", "l"),
+    ("Semicolon", "ö"),
+    ("Quote", "ä"),
+    ("Backslash", "#"),
+    ("KeyZ", "y"),
+    ("KeyX", "x"),
+    ("KeyC", "c"),
+    ("KeyV", "v"),
+    ("KeyB", "b"),
+    ("KeyN", "n"),
+    ("KeyM", "m"),
+    ("Comma", ","),
+    ("Period", "."),
+    ("Slash", "-"),
+    ("IntlBackslash", "<"),
+];
+
+fn 查表(佈局表: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |鍵值| {
+        佈局表
+            .iter()
+            .find(|(位置, _)| *位置 == 鍵值)
+            .map(|(_, 字符)| 字符.to_string())
+    }
+}
+
+#[test]
+fn 按瀏覽器佈局表認出佈局() {
+    assert_eq!(辨認系統佈局(查表(美式佈局表)), Some(系統佈局::美式));
+    assert_eq!(辨認系統佈局(查表(德沃夏克佈局表)), Some(系統佈局::德沃夏克));
+    assert_eq!(辨認系統佈局(查表(科爾馬克佈局表)), Some(系統佈局::科爾馬克));
+    assert_eq!(辨認系統佈局(查表(法式佈局表)), Some(系統佈局::法式));
+    assert_eq!(辨認系統佈局(查表(日式佈局表)), Some(系統佈局::日式));
+}
+
+#[test]
+fn 未列出的佈局認不出() {
+    assert_eq!(辨認系統佈局(查表(德式佈局表)), None);
+    assert_eq!(辨認系統佈局(|_| Some(String::from("ß"))), None);
+}
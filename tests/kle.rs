@@ -0,0 +1,90 @@
+//! 導入 Keyboard Layout Editor 的配列文件
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::layout::矩陣座標;
+use typewriter::key_code::KeyCode;
+use typewriter::kle::{載入配列文件, 配列文件錯誤};
+
+#[test]
+fn 按刻印認鍵並推算位置() {
+    let 幾何 = 載入配列文件(
+        r#"[
+            {"name": "小鍵盤"},
+            ["Esc", "!\n1", "@\n2"],
+            [{"x": 0.5}, "Q", {"d": true}, "", "Shift"],
+            [{"w": 6.25}, ""],
+            [{"r": 15, "rx": 4, "ry": 5}, "Enter", "⌫"]
+        ]"#,
+    )
+    .unwrap();
+    let 衆鍵碼 = 幾何.衆鍵位.iter().map(|鍵位| 鍵位.鍵碼).collect::<Vec<_>>();
+    assert_eq!(
+        衆鍵碼,
+        [
+            KeyCode::Escape,
+            KeyCode::Kc1,
+            KeyCode::Kc2,
+            KeyCode::Q,
+            KeyCode::No,
+            KeyCode::Space,
+            KeyCode::Enter,
+            KeyCode::Backspace,
+        ]
+    );
+    let 鍵位 = |鍵碼| 幾何.鍵位(鍵碼).unwrap();
+    assert_eq!(鍵位(KeyCode::Q).刻印座標, Some(矩陣座標(1, 0)));
+    // 裝飾鍵不畫, 但佔位置
+    assert_eq!(鍵位(KeyCode::No).左 - 鍵位(KeyCode::Q).左, 2.0);
+    assert_eq!(鍵位(KeyCode::Space).寬, 6.25);
+    // 轉動的鍵從旋轉中心起排
+    let 回車 = 鍵位(KeyCode::Enter);
+    assert_eq!(回車.轉角, 15.0);
+    assert_eq!(
+        (回車.左 - 回車.旋轉中心.0, 回車.上 - 回車.旋轉中心.1),
+        (0.0, 0.0)
+    );
+    assert_eq!(鍵位(KeyCode::Backspace).左 - 回車.左, 1.0);
+}
+
+#[test]
+fn 認出日式鍵盤特有的鍵() {
+    // 仿 Keyboard Layout Editor 中 JIS 配列的刻印, 上檔字符在前, 假名在後
+    let 幾何 = 載入配列文件(
+        r#"[
+            [{"x": 13}, "|\n¥\n\n\nー", "BS"],
+            [{"w": 2.25}, "Shift", "Z\n\n\n\nつ", "M\n\n\n\nも", "?\n/\n\n\nめ", "_\n\\\n\n\nろ", {"w": 2.75}, "Shift"]
+        ]"#,
+    )
+    .unwrap();
+    let 衆鍵碼 = 幾何.衆鍵位.iter().map(|鍵位| 鍵位.鍵碼).collect::<Vec<_>>();
+    assert_eq!(
+        衆鍵碼,
+        [
+            KeyCode::International3,
+            KeyCode::No,
+            KeyCode::No,
+            KeyCode::Z,
+            KeyCode::M,
+            KeyCode::Slash,
+            KeyCode::International1,
+            KeyCode::No,
+        ]
+    );
+}
+
+#[test]
+fn 無按鍵或格式有誤則報錯() {
+    assert!(matches!(
+        載入配列文件(r#"[{"name": "空"}]"#),
+        Err(配列文件錯誤::未有按鍵)
+    ));
+    assert!(matches!(
+        載入配列文件("[[1]]"),
+        Err(配列文件錯誤::未知內容(_))
+    ));
+    assert!(matches!(載入配列文件("{"), Err(配列文件錯誤::格式有誤(_))));
+}
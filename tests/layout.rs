@@ -0,0 +1,67 @@
+//! 配列: ISO、JIS 鍵盤多出的鍵位及其刻印
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::layout::{盤面選擇碼, 矩陣座標, 配列};
+use typewriter::key_code::{網頁鍵值轉換, KeyCode};
+use typewriter::theory_file::載入方案文件;
+
+#[test]
+fn 擴展鍵位的鍵碼與座標() {
+    assert_eq!(網頁鍵值轉換("IntlBackslash"), KeyCode::NonusBackslash);
+    assert_eq!(網頁鍵值轉換("IntlRo"), KeyCode::International1);
+    assert_eq!(網頁鍵值轉換("IntlYen"), KeyCode::International3);
+
+    let 歐規 = 配列::歐規主鍵盤區;
+    assert_eq!(歐規.矩陣()[3][0], KeyCode::NonusBackslash);
+    assert_eq!(歐規.刻印座標(3, 0).unwrap().to_string(), "B00");
+    // 其後各鍵的刻印位置與主鍵盤區相同
+    assert_eq!(歐規.刻印座標(3, 1), Some(矩陣座標(3, 0)));
+    assert_eq!(矩陣座標(3, 0).to_string(), "B01");
+
+    let 日規 = 配列::日規主鍵盤區;
+    assert_eq!(日規.刻印座標(0, 13).unwrap().to_string(), "E13");
+    assert_eq!(日規.刻印座標(3, 10).unwrap().to_string(), "B11");
+    // E00 的 ` 鍵取主鍵盤區 E13 的刻印
+    assert_eq!(日規.刻印座標(0, 0), Some(矩陣座標(0, 12)));
+    assert_eq!(日規.刻印座標(0, 1), Some(矩陣座標(0, 0)));
+}
+
+#[test]
+fn 字根表可用擴展鍵位() {
+    let 方案 = 載入方案文件(
+        r#"
+名稱: 歐規測試
+指法: 連擊
+編碼法: 連擊
+佈局:
+  盤面:
+    - - ""
+      - ""
+      - ""
+      - z x c v b n m _ _ _ _ 〇
+      - _ _ _
+  首選配列: 歐規主鍵盤區
+字根表:
+  - { 輸入碼: z, 鍵碼: Z }
+  - { 輸入碼: 〇, 鍵碼: NonusBackslash }
+"#,
+    )
+    .unwrap();
+    assert!(方案
+        .字根表
+        .iter()
+        .any(|鍵位| 鍵位.輸入碼 == "〇" && 鍵位.鍵碼 == KeyCode::NonusBackslash));
+    let 配列 = 方案.佈局.首選配列;
+    let 刻印 = |列| {
+        方案
+            .佈局
+            .選擇盤面(盤面選擇碼(0), 配列.刻印座標(3, 列).unwrap())
+            .and_then(|(_, 刻印)| 刻印.居中刻印文字())
+    };
+    assert_eq!(刻印(0), Some("〇"));
+    assert_eq!(刻印(1), Some("z"));
+}
@@ -0,0 +1,73 @@
+//! 缺鍵: 方案用到而配列中沒有的鍵, 及以閒置的鍵代替
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::geometry::配列幾何;
+use typewriter::gear::layout::{方案所用鍵位, 替代鍵位表, 缺失鍵位, 配列};
+use typewriter::gear::theory::輸入方案環境;
+use typewriter::key_code::KeyCode;
+use typewriter::theory::early_middle_chinese::早期中古漢語輸入方案;
+use typewriter::theory_file::載入方案文件;
+
+#[test]
+fn 字母鍵盤缺少斜線鍵() {
+    let 方案 = 早期中古漢語輸入方案(輸入方案環境::default());
+    let 所用鍵位 = 方案所用鍵位(&方案);
+    assert!(缺失鍵位(&所用鍵位, &方案.佈局.首選配列.幾何()).is_empty());
+
+    let 字母鍵盤 = 配列::字母鍵盤.幾何();
+    let 缺鍵 = 缺失鍵位(&所用鍵位, &字母鍵盤);
+    assert_eq!(缺鍵, [KeyCode::Slash]);
+    // 「去」改由盤面上留空的 `.` 鍵擊出
+    let 替代 = 替代鍵位表::編排(&缺鍵, &所用鍵位, &字母鍵盤);
+    assert_eq!(替代.0, [(KeyCode::Dot, KeyCode::Slash)]);
+    assert_eq!(替代.轉換(KeyCode::Dot), KeyCode::Slash);
+}
+
+#[test]
+fn 以最近的閒置鍵代替() {
+    let 方案 = 載入方案文件(
+        r#"
+名稱: 缺鍵測試
+指法: 連擊
+編碼法: 連擊
+佈局:
+  盤面:
+    - - ""
+      - ""
+      - a s
+      - ""
+      - ""
+  首選配列: 主鍵盤區
+字根表:
+  - { 輸入碼: a, 鍵碼: A }
+  - { 輸入碼: s, 鍵碼: S }
+  - { 輸入碼: 斜, 鍵碼: Slash }
+"#,
+    )
+    .unwrap();
+    let 所用鍵位 = 方案所用鍵位(&方案);
+    assert_eq!(所用鍵位, [KeyCode::A, KeyCode::S, KeyCode::Slash]);
+
+    let 字母鍵盤 = 配列::字母鍵盤.幾何();
+    let 缺鍵 = 缺失鍵位(&所用鍵位, &字母鍵盤);
+    assert_eq!(缺鍵, [KeyCode::Slash]);
+    // 配列所有的鍵不變; 閒置鍵用盡則不再代替
+    let 替代 = 替代鍵位表::編排(&缺鍵, &所用鍵位, &字母鍵盤);
+    assert_eq!(替代.轉換(KeyCode::A), KeyCode::A);
+    let 僅有兩鍵 = 配列幾何::new(
+        字母鍵盤
+            .衆鍵位
+            .iter()
+            .filter(|鍵位| matches!(鍵位.鍵碼, KeyCode::A | KeyCode::S))
+            .copied()
+            .collect(),
+    );
+    assert_eq!(
+        替代鍵位表::編排(&缺鍵, &所用鍵位, &僅有兩鍵),
+        替代鍵位表::default()
+    );
+}
@@ -0,0 +1,116 @@
+//! 記錯推進: 擊錯亦推進, 錯處標於字幕, 可另組重練
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+mod common;
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use common::並擊;
+use typewriter::definition::碼表格式;
+use typewriter::gear::assignment::{作業內容, 推進方式, 碼表定義, 解析碼表};
+use typewriter::gear::caption::字幕格式;
+use typewriter::gear::retry::{待重練片段, 重練內容};
+use typewriter::gear::statistics::片段紀錄;
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::headless::引擎核心;
+
+#[test]
+fn 擊錯亦推進並可重練() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let mut 核心 = 引擎核心::new(方案);
+    核心.佈置作業(Some(作業內容 {
+        碼表: 碼表定義::生成(碼表格式::並擊, "<ba> <ma> <da>".to_owned()),
+        字幕: 字幕格式::自動生成,
+    }));
+    核心.設置推進方式(推進方式::記錯推進);
+
+    // 該打 ba, 卻打了 ma
+    核心.作業推進(Default::default()).ok();
+    let ma = 核心.反查鍵位().unwrap();
+    核心.重置作業進度();
+    let 反饋 = 並擊(&mut 核心, &ma);
+    assert!(反饋.擊鍵有誤 && 反饋.作業推進);
+    assert_eq!(核心.作業進度(), 1);
+
+    // 這回打對了
+    let 反饋 = 並擊(&mut 核心, &ma);
+    assert!(!反饋.擊鍵有誤 && 反饋.作業推進);
+    assert_eq!(核心.擊錯片段().iter().copied().collect::<Vec<_>>(), [0]);
+
+    let 字幕 = 核心.段落表示().unwrap();
+    assert_eq!(
+        字幕.已完成分截,
+        [("ba ".to_owned(), true), ("ma ".to_owned(), false)]
+    );
+
+    let 重練 = 重練內容(&核心, 核心.擊錯片段()).unwrap();
+    let 片段 = 解析碼表(&重練.碼表, 核心.方案());
+    assert_eq!(片段.len(), 1);
+    assert_eq!(片段[0].顯示輸入碼(), Some("ba"));
+}
+
+/// 分段的作業在末一片段擊錯, 迴轉到開頭後仍記得, 待下一輪擊鍵纔清除
+#[test]
+fn 末一片段擊錯亦記下() {
+    let 方案 = 生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap();
+    let mut 核心 = 引擎核心::new(方案);
+    核心.佈置作業(Some(作業內容 {
+        碼表: 碼表定義::生成(碼表格式::並擊, "<ba> <ma> <da>".to_owned()),
+        字幕: 字幕格式::自訂(Cow::Borrowed("八媽 大")),
+    }));
+    核心.設置推進方式(推進方式::記錯推進);
+    assert_eq!(核心.分段字幕().len(), 2);
+
+    let ba = 核心.反查鍵位().unwrap();
+    並擊(&mut 核心, &ba);
+    // 該打 ma, 打了 ba
+    let 反饋 = 並擊(&mut 核心, &ba);
+    assert!(反饋.擊鍵有誤 && 反饋.作業推進 && !反饋.打完一題);
+    // 該打 da, 打了 ba
+    let 反饋 = 並擊(&mut 核心, &ba);
+    assert!(反饋.擊鍵有誤 && 反饋.作業推進 && 反饋.打完一題);
+    assert_eq!(核心.作業進度(), 0);
+    assert!(核心.一題打完());
+    assert_eq!(核心.擊錯片段(), &BTreeSet::from([1, 2]));
+
+    let 重練 = 重練內容(&核心, 核心.擊錯片段()).unwrap();
+    let 片段 = 解析碼表(&重練.碼表, 核心.方案());
+    assert_eq!(
+        片段
+            .iter()
+            .map(|片段| 片段.顯示輸入碼())
+            .collect::<Vec<_>>(),
+        [Some("ma"), Some("da")]
+    );
+
+    // 再擊鍵即開始新的一輪
+    並擊(&mut 核心, &ba);
+    assert!(!核心.一題打完());
+    assert!(核心.擊錯片段().is_empty());
+    assert_eq!(核心.作業進度(), 1);
+}
+
+#[test]
+fn 擊錯或偏慢的片段待重練() {
+    let 片段 = |進度, 用時, 擊錯次數| 片段紀錄 {
+        進度,
+        輸入碼: String::new(),
+        遲疑: 0.0,
+        用時,
+        擊錯次數,
+    };
+    let 衆片段 = [
+        片段(0, 500.0, 0),
+        片段(1, 500.0, 1),
+        片段(2, 2000.0, 0),
+        片段(3, 500.0, 0),
+    ];
+    let 跳過 = BTreeSet::from([4]);
+    assert_eq!(待重練片段(&衆片段, &跳過, true), BTreeSet::from([1, 2, 4]));
+    assert_eq!(待重練片段(&衆片段, &跳過, false), BTreeSet::from([1, 4]));
+}
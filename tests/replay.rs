@@ -0,0 +1,125 @@
+//! 擊鍵錄放: 錄像送回引擎核心, 結果與錄製時一致
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::definition::碼表格式;
+use typewriter::gear::assignment::{作業, 作業內容, 推進方式, 生成題, 碼表定義};
+use typewriter::gear::caption::字幕格式;
+use typewriter::gear::chord::並擊判定;
+use typewriter::gear::input::{擊鍵事件, 擊鍵來源};
+use typewriter::gear::replay::{擊鍵錄像, 重放到核心, 錄像作業};
+use typewriter::gear::retry::重練標題;
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::headless::{引擎核心, 擊鍵反饋};
+use typewriter::key_code::KeyCode;
+
+fn 宮保拼音核心() -> 引擎核心 {
+    let mut 核心 =
+        引擎核心::new(生成方案定義(方案選項::宮保拼音, 輸入方案環境::default()).unwrap());
+    核心.佈置作業(Some(作業內容 {
+        碼表: 碼表定義::生成(碼表格式::並擊, "<ba> <ma> <da>".to_owned()),
+        字幕: 字幕格式::自動生成,
+    }));
+    核心
+}
+
+fn 擊鍵(時刻: f64, 鍵碼: KeyCode, 落鍵: bool) -> 擊鍵事件 {
+    擊鍵事件 {
+        時刻,
+        鍵碼,
+        落鍵,
+        來源: 擊鍵來源::實體鍵盤,
+        上檔: false,
+    }
+}
+
+#[test]
+fn 重放結果與錄製時一致() {
+    // 邊打邊錄
+    let mut 錄製核心 = 宮保拼音核心();
+    let mut 衆事件 = vec![];
+    let mut 錄製反饋 = vec![];
+    let mut 時刻 = 0.0;
+    while !錄製核心.作業進度完成() {
+        let 鍵位 = 錄製核心.反查鍵位().unwrap();
+        for 落鍵 in [true, false] {
+            for &鍵碼 in &鍵位.0 {
+                時刻 += 50.0;
+                let 事件 = 擊鍵(時刻, 鍵碼, 落鍵);
+                錄製反饋.push(if 落鍵 {
+                    錄製核心.落鍵(鍵碼)
+                } else {
+                    錄製核心.抬鍵(鍵碼)
+                });
+                衆事件.push(事件);
+            }
+        }
+    }
+
+    let mut 重放核心 = 宮保拼音核心();
+    let 重放反饋: Vec<擊鍵反饋> = 重放到核心(&mut 重放核心, &衆事件);
+    assert_eq!(重放反饋, 錄製反饋);
+    assert!(重放核心.作業進度完成());
+
+    // 退出鍵從頭再來
+    重放到核心(&mut 重放核心, &[擊鍵(0.0, KeyCode::Escape, true)]);
+    assert_eq!(重放核心.作業進度(), 0);
+}
+
+#[test]
+fn 錄像可導出再導入() {
+    let 生成的作業 = 作業::生成(
+        方案選項::宮保拼音,
+        生成題 {
+            標題: 重練標題,
+            內容: 作業內容 {
+                碼表: 碼表定義::生成(碼表格式::並擊, "<ba>".to_owned()),
+                字幕: 字幕格式::自動生成,
+            },
+        },
+    );
+    let 錄像 = 擊鍵錄像 {
+        方案: 方案選項::宮保拼音.slug().to_owned(),
+        作業: 錄像作業::取自(&生成的作業, 碼表格式::並擊),
+        推進方式: 推進方式::記錯推進,
+        並擊判定: 並擊判定::正規並擊,
+        方案文件: None,
+        日期: 0.0,
+        衆事件: vec![擊鍵(0.0, KeyCode::A, true), 擊鍵(120.0, KeyCode::A, false)],
+    };
+    let 文本 = serde_json::to_string(&錄像).unwrap();
+    let 導入的錄像: 擊鍵錄像 = serde_json::from_str(&文本).unwrap();
+    assert_eq!(導入的錄像, 錄像);
+    assert_eq!(導入的錄像.時長(), 120.0);
+    assert!(導入的錄像.作業.還原(方案選項::宮保拼音) == 生成的作業);
+}
+
+#[test]
+fn 時長從首擊算起() {
+    let 錄像 = 擊鍵錄像 {
+        方案: 方案選項::自訂方案.slug().to_owned(),
+        作業: 錄像作業::default(),
+        推進方式: 推進方式::default(),
+        並擊判定: 並擊判定::default(),
+        方案文件: Some("https://example.com/theory.yaml".to_owned()),
+        日期: 0.0,
+        // 開始錄製後過了兩秒纔擊鍵
+        衆事件: vec![
+            擊鍵(2000.0, KeyCode::A, true),
+            擊鍵(2150.0, KeyCode::A, false),
+        ],
+    };
+    assert_eq!(錄像.首擊時刻(), 2000.0);
+    assert_eq!(錄像.時長(), 150.0);
+}
+
+#[test]
+fn 舊錄像未記方案文件() {
+    let 文本 = r#"{"方案":"combo_pinyin","作業":{"題號":0,"自訂反查碼":null,"生成題":null},"日期":0.0,"衆事件":[]}"#;
+    let 錄像: 擊鍵錄像 = serde_json::from_str(文本).unwrap();
+    assert_eq!(錄像.方案文件, None);
+    assert_eq!(錄像.時長(), 0.0);
+}
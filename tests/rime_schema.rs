@@ -0,0 +1,179 @@
+//! Rime 並擊方案導入導出測試
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use std::collections::BTreeSet;
+use typewriter::definition::{觸鍵方式, 輸入方案定義, 鍵組};
+use typewriter::drill::題目來源;
+use typewriter::gear::assignment::對照輸入碼;
+use typewriter::gear::layout::盤面選擇碼;
+use typewriter::gear::theory::{方案選單, 輸入方案環境};
+use typewriter::key_code::KeyCode;
+use typewriter::rime_schema::導出並擊方案;
+use typewriter::spelling_algebra::拼寫運算錯誤;
+use typewriter::theory_audit::碼表中的音節;
+use typewriter::theory_file::{方案文件錯誤, 載入方案文件};
+
+const 示例方案: &str = r#"
+schema:
+  schema_id: sample_chord
+  name: 示例並擊
+chord_composer:
+  alphabet: "sdfjkl"
+  algebra:
+    - xlit/sdfjkl/SDFJKL/
+    - xform/^SJ$/shi/
+    - xlit/SDFJKL/sdfjkl/
+  output_format:
+    - "xform/^(.+)$/$1'/"
+"#;
+
+fn 並擊所得(方案: &輸入方案定義, 衆鍵: &[KeyCode]) -> Option<String> {
+    let 字根碼 = 方案.寫成字根碼(&鍵組(衆鍵.iter().copied().collect()));
+    方案.轉寫法.字根碼轉寫爲拼式(&字根碼)
+}
+
+#[test]
+fn 導入並擊字母表() {
+    let 方案 = 載入方案文件(示例方案).expect("示例方案應能導入");
+    assert_eq!(方案.名稱, "示例並擊");
+    assert!(matches!(方案.指法, 觸鍵方式::並擊));
+    // 起首的轉寫運算施於字母表
+    assert_eq!(
+        方案.字根表.iter().map(|鍵| 鍵.輸入碼).collect::<Vec<_>>(),
+        ["S", "D", "F", "J", "K", "L"]
+    );
+    assert_eq!(
+        方案.字根表.iter().map(|鍵| 鍵.鍵碼).collect::<Vec<_>>(),
+        [
+            KeyCode::S,
+            KeyCode::D,
+            KeyCode::F,
+            KeyCode::J,
+            KeyCode::K,
+            KeyCode::L
+        ]
+    );
+}
+
+#[test]
+fn 並擊碼經運算得拼式() {
+    let 方案 = 載入方案文件(示例方案).unwrap();
+    // 輸出格式所加的分隔符在結尾被去除
+    assert_eq!(
+        並擊所得(&方案, &[KeyCode::J, KeyCode::S]).as_deref(),
+        Some("shi")
+    );
+    assert_eq!(
+        並擊所得(&方案, &[KeyCode::F, KeyCode::K]).as_deref(),
+        Some("fk")
+    );
+    assert_eq!(並擊所得(&方案, &[]), None);
+}
+
+#[test]
+fn 分隔符爲空則保留輸出格式() {
+    let 文本 = format!("{示例方案}speller:\n  delimiter: \"\"\n");
+    let 方案 = 載入方案文件(&文本).unwrap();
+    assert_eq!(
+        並擊所得(&方案, &[KeyCode::J, KeyCode::S]).as_deref(),
+        Some("shi'")
+    );
+}
+
+#[test]
+fn 未起首轉寫則以字母爲字根() {
+    let 文本 = "chord_composer:\n  alphabet: \"qaz\"\n  algebra:\n    - xform/^qa$/ba/\n";
+    let 方案 = 載入方案文件(文本).unwrap();
+    assert_eq!(
+        方案.字根表.iter().map(|鍵| 鍵.輸入碼).collect::<Vec<_>>(),
+        ["q", "a", "z"]
+    );
+    assert_eq!(
+        並擊所得(&方案, &[KeyCode::A, KeyCode::Q]).as_deref(),
+        Some("ba")
+    );
+}
+
+#[test]
+fn 方案名稱() {
+    let 只有代號 = "schema:\n  schema_id: sample_chord\nchord_composer:\n  alphabet: \"qaz\"\n";
+    assert_eq!(載入方案文件(只有代號).unwrap().名稱, "sample_chord");
+    let 未具名 = "chord_composer:\n  alphabet: \"qaz\"\n";
+    assert_eq!(載入方案文件(未具名).unwrap().名稱, "Rime 並擊方案");
+}
+
+#[test]
+fn 字母表含無從對應按鍵的字符() {
+    let 文本 = "chord_composer:\n  alphabet: \"qa字\"\n";
+    assert!(matches!(
+        載入方案文件(文本),
+        Err(方案文件錯誤::未知按鍵('字'))
+    ));
+}
+
+#[test]
+fn 運算式有誤() {
+    let 文本 = "chord_composer:\n  alphabet: \"qaz\"\n  algebra:\n    - xfrom/q/b/\n";
+    assert!(matches!(
+        載入方案文件(文本),
+        Err(方案文件錯誤::拼寫運算有誤(
+            拼寫運算錯誤::未知運算(_)
+        ))
+    ));
+}
+
+#[test]
+fn 缺少並擊字母表() {
+    let 文本 = "chord_composer:\n  algebra:\n    - xform/q/b/\n";
+    assert!(matches!(載入方案文件(文本), Err(方案文件錯誤::格式有誤(_))));
+}
+
+/// 內建並擊方案導出爲 Rime 方案後再導入, 練習題中各音節的並擊應轉寫出相同的拼式
+#[test]
+fn 內建並擊方案導出後可原樣導入() {
+    let mut 走樣 = Vec::new();
+    let mut 覈對數 = 0;
+    for (選項, 生成方案) in 方案選單.iter() {
+        let 方案 = 生成方案(輸入方案環境::default());
+        if !matches!(方案.指法, 觸鍵方式::並擊) {
+            continue;
+        }
+        let 導出 = 導出並擊方案(&方案, 選項.slug());
+        let 導入 = 載入方案文件(&導出)
+            .unwrap_or_else(|錯誤| panic!("[{}] 導出的方案無法導入: {錯誤}", 選項.slug()));
+        let 音節表 = 選項
+            .配套練習題()
+            .unwrap_or(&[])
+            .iter()
+            .flat_map(|題| match &題.題目 {
+                題目來源::內建 { 編碼, .. } => 碼表中的音節(&方案, 編碼),
+                題目來源::求取 { .. } => Default::default(),
+            })
+            .collect::<BTreeSet<_>>();
+        for 拼式 in 音節表 {
+            let 對照碼 = 對照輸入碼 {
+                字根碼原文: None,
+                轉寫碼原文: Some(拼式.clone()),
+            };
+            let Some(字根碼) = 對照碼.反查字根碼(&方案.轉寫法) else {
+                continue;
+            };
+            覈對數 += 1;
+            let 鍵位 = 方案.讀出鍵位(&字根碼, &盤面選擇碼(0));
+            let 原方案所得 = 方案.轉寫法.字根碼轉寫爲拼式(&方案.寫成字根碼(&鍵位));
+            let 導入所得 = 導入.轉寫法.字根碼轉寫爲拼式(&導入.寫成字根碼(&鍵位));
+            if 原方案所得 != 導入所得 {
+                走樣.push(format!(
+                    "[{}] {拼式}: {原方案所得:?} => {導入所得:?}",
+                    選項.slug()
+                ));
+            }
+        }
+    }
+    assert!(覈對數 > 0);
+    assert!(走樣.is_empty(), "\n{}", 走樣.join("\n"));
+}
@@ -0,0 +1,35 @@
+//! 各方案內建練習題中的音節, 經「拆分—按鍵—轉寫」往返後都應能擊中
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::drill::題目來源;
+use typewriter::gear::theory::{方案選單, 輸入方案環境};
+use typewriter::theory_audit::{碼表中的音節, 覈對往返};
+
+#[test]
+fn 內建練習題的音節皆可擊中() {
+    let 打不出 = 方案選單
+        .iter()
+        .flat_map(|(選項, 生成方案)| {
+            let 方案 = 生成方案(輸入方案環境::default());
+            let 音節表 = 選項
+                .配套練習題()
+                .unwrap_or(&[])
+                .iter()
+                .flat_map(|題| match &題.題目 {
+                    題目來源::內建 { 編碼, .. } => 碼表中的音節(&方案, 編碼),
+                    題目來源::求取 { .. } => Default::default(),
+                })
+                .collect::<Vec<_>>();
+            覈對往返(&方案, &音節表)
+                .into_iter()
+                .filter(|條目| !條目.問題.能否擊中())
+                .map(|條目| format!("[{}] {} {:?}", 選項.slug(), 條目.拼式, 條目.問題))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert!(打不出.is_empty(), "\n{}", 打不出.join("\n"));
+}
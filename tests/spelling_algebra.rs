@@ -0,0 +1,284 @@
+//! Rime 拼寫運算式解析及施展測試
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use lazy_regex::{regex, Regex};
+use std::ops::Deref;
+use typewriter::definition::{轉寫法定義, 邊界判定規則};
+use typewriter::gear::assignment::對照輸入碼;
+use typewriter::spelling_algebra::{
+    拼寫運算, 拼寫運算錯誤, 拼式類別, 施展多值拼寫運算, 施展拼寫運算, 派生類別, 解析拼寫運算,
+};
+use typewriter::{派生, 消除, 轉寫};
+
+fn 解析(衆規則: &[&str]) -> Vec<拼寫運算<'static>> {
+    衆規則
+        .iter()
+        .map(|規則| 解析拼寫運算(規則).expect(規則))
+        .collect()
+}
+
+fn 單值(原形: &str, 衆規則: &[&str]) -> Option<String> {
+    施展拼寫運算(原形, &解析(衆規則))
+}
+
+/// 多值運算所得的衆拼式, 不看類別
+fn 多值(原形: &str, 規則: &[拼寫運算]) -> Vec<String> {
+    施展多值拼寫運算(原形, 規則)
+        .into_iter()
+        .map(|拼式| 拼式.拼式)
+        .collect()
+}
+
+/// 多值運算所得的衆拼式及其類別
+fn 分類(原形: &str, 規則: &[拼寫運算]) -> Vec<(String, 拼式類別)> {
+    施展多值拼寫運算(原形, 規則)
+        .into_iter()
+        .map(|拼式| (拼式.拼式, 拼式.類別))
+        .collect()
+}
+
+#[test]
+fn 解析變換() {
+    assert_eq!(單值("zhi", &["xform/^([zcs])h/$1/"]).as_deref(), Some("zi"));
+    // 末尾分隔符可省略, 分隔符亦可換用他字
+    assert_eq!(單值("zhi", &["xform|^([zcs])h|$1"]).as_deref(), Some("zi"));
+    // 未匹配則原樣保留
+    assert_eq!(單值("ba", &["xform/^([zcs])h/$1/"]).as_deref(), Some("ba"));
+}
+
+#[test]
+fn 改寫分組引用() {
+    // `$1` 緊接字母, 須改寫作 `${1}`, 否則會被當作名爲 `1x` 的分組
+    assert_eq!(單值("ab", &["xform/^(a)b$/$1x/"]).as_deref(), Some("ax"));
+    assert_eq!(單值("ab", &["xform/^(a)(b)$/$2$1/"]).as_deref(), Some("ba"));
+    // `$&` 爲整個匹配
+    assert_eq!(單值("ab", &["xform/b/[$&]/"]).as_deref(), Some("a[b]"));
+    // `$$` 及其餘 `$` 皆爲字面
+    assert_eq!(單值("ab", &["xform/b/$$/"]).as_deref(), Some("a$"));
+    assert_eq!(單值("ab", &["xform/b/$x/"]).as_deref(), Some("a$x"));
+    // 已寫作 `${1}` 者照錄
+    assert_eq!(單值("ab", &["xform/^(a)b$/${1}x/"]).as_deref(), Some("ax"));
+}
+
+#[test]
+fn 解析轉寫() {
+    assert_eq!(單值("abc", &["xlit/abc/xyz/"]).as_deref(), Some("xyz"));
+    assert_eq!(單值("bad", &["xlit/abc/xyz/"]).as_deref(), Some("yxd"));
+    // 按字符而非字節對應
+    assert_eq!(單值("ㄅㄆ", &["xlit/ㄅㄆ/bp/"]).as_deref(), Some("bp"));
+}
+
+#[test]
+fn 解析消除() {
+    // 模式須匹配整個拼式
+    assert_eq!(單值("xa", &["erase/x/"]).as_deref(), Some("xa"));
+    assert_eq!(單值("x", &["erase/x/"]), None);
+    assert_eq!(單值("xa", &["erase/x.*/"]), None);
+    assert_eq!(單值("a", &["erase/a|b/"]), None);
+    assert_eq!(單值("ab", &["erase/a|b/"]).as_deref(), Some("ab"));
+}
+
+#[test]
+fn 解析派生() {
+    let 規則 = 解析(&["derive/^([zcs])h/$1/"]);
+    // 單值運算不受派生影響
+    assert_eq!(施展拼寫運算("zhi", &規則).as_deref(), Some("zhi"));
+    assert_eq!(多值("zhi", &規則), ["zhi", "zi"]);
+    assert_eq!(多值("ba", &規則), ["ba"]);
+}
+
+#[test]
+fn 解析模糊及縮寫() {
+    for (規則, 類別) in [
+        ("derive/a/b/", 派生類別::派生),
+        ("fuzz/a/b/", 派生類別::模糊),
+        ("abbrev/a/b/", 派生類別::縮寫),
+    ] {
+        assert!(
+            matches!(
+                解析拼寫運算(規則),
+                Ok(拼寫運算::派生 { 類別: 所得, .. }) if 所得 == 類別
+            ),
+            "{規則}"
+        );
+    }
+}
+
+#[test]
+fn 多值運算保留正規拼式居首() {
+    let 規則 = [
+        派生!("^([zcs])h", "$1"),
+        派生!(模糊, "n$", "ng"),
+        派生!(縮寫, "^([a-z]).+$", "$1"),
+    ];
+    assert_eq!(多值("zhan", &規則), ["zhan", "zan", "zhang", "zang", "z"]);
+    // 各派生拼式不重複
+    assert_eq!(多值("za", &規則), ["za", "z"]);
+    assert!(多值("", &規則).is_empty());
+}
+
+#[test]
+fn 派生模糊縮寫所得類別不同() {
+    use 拼式類別::*;
+    let 規則 = [
+        派生!("^([zcs])h", "$1"),
+        派生!(模糊, "n$", "ng"),
+        派生!(縮寫, "^([a-z]).+$", "$1"),
+    ];
+    let 類別 = |衆拼式: &[(&str, 拼式類別)]| {
+        衆拼式
+            .iter()
+            .map(|&(拼式, 類別)| (拼式.to_owned(), 類別))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        分類("zhan", &規則),
+        類別(&[
+            ("zhan", 正規),
+            ("zan", 正規),
+            ("zhang", 模糊),
+            ("zang", 模糊),
+            ("z", 縮寫),
+        ])
+    );
+    // 由模糊拼式派生, 仍是模糊拼式
+    let 規則 = [派生!(模糊, "n$", "ng"), 派生!("^zh", "z")];
+    assert_eq!(
+        分類("zhan", &規則),
+        類別(&[
+            ("zhan", 正規),
+            ("zhang", 模糊),
+            ("zan", 正規),
+            ("zang", 模糊),
+        ])
+    );
+    // 同一拼式既可派生亦可模糊得到, 算作正規
+    let 規則 = [派生!(模糊, "^zh", "z"), 派生!("^zh", "z")];
+    assert_eq!(分類("zhi", &規則), 類別(&[("zhi", 正規), ("zi", 正規)]));
+}
+
+#[test]
+fn 多值運算逐一變換衆拼式() {
+    let 規則 = [
+        派生!("^zh", "z"),
+        轉寫!("z", "Z"),
+        // 變換後重複者只留其一
+        解析拼寫運算("xform/h//").unwrap(),
+        消除!("^Zi$"),
+    ];
+    assert_eq!(多值("zhu", &規則), ["Zu"]);
+    assert!(多值("zhi", &規則).is_empty());
+    // 正規拼式被消除後, 派生拼式依舊保留
+    let 規則 = [派生!("^zh", "z"), 消除!("^zh.*$")];
+    assert_eq!(多值("zhi", &規則), ["zi"]);
+    assert_eq!(施展拼寫運算("zhi", &規則), None);
+}
+
+fn 測試轉寫法<'a>(
+    字根拆分規則: &'a [拼寫運算<'a>],
+    拼式驗證規則: &'a [&'a Regex],
+) -> 轉寫法定義<'a> {
+    轉寫法定義 {
+        輸入碼表示: &[],
+        輸入碼鍵位: &[],
+        拼式轉寫規則: &[],
+        字根拆分規則,
+        拼式驗證規則,
+        邊界判定: 邊界判定規則 {
+            分隔鍵: &[],
+            起始鍵: &[],
+            終止鍵: &[],
+        },
+    }
+}
+
+#[test]
+fn 反查衆字根碼() {
+    let 規則 = [派生!("^([zcs])h", "$1"), 派生!(縮寫, "^(.).+$", "$1")];
+    let 驗證規則 = [regex!("^[a-z]+$").deref()];
+    let 轉寫法 = 測試轉寫法(&規則, &驗證規則);
+    let 轉寫碼 = |碼: &str| 對照輸入碼 {
+        字根碼原文: None,
+        轉寫碼原文: Some(碼.to_owned()),
+    };
+    assert_eq!(轉寫碼("zhi").反查衆字根碼(&轉寫法), ["zhi", "zi", "z"]);
+    // 正規字根碼在前, 模糊、縮寫所得在後
+    let 規則 = [派生!(縮寫, "^(.).+$", "$1"), 派生!("^([zcs])h", "$1")];
+    let 轉寫法 = 測試轉寫法(&規則, &驗證規則);
+    assert_eq!(轉寫碼("zhi").反查衆字根碼(&轉寫法), ["zhi", "zi", "z"]);
+    assert_eq!(轉寫碼("zhi").反查字根碼(&轉寫法).as_deref(), Some("zhi"));
+    // 指明字根碼者不再反查
+    let 字根碼 = 對照輸入碼 {
+        字根碼原文: Some("zhi".to_owned()),
+        轉寫碼原文: Some("zhi".to_owned()),
+    };
+    assert_eq!(字根碼.反查衆字根碼(&轉寫法), ["zhi"]);
+    let 空白 = 對照輸入碼 {
+        字根碼原文: None,
+        轉寫碼原文: None,
+    };
+    assert!(空白.反查衆字根碼(&轉寫法).is_empty());
+}
+
+#[test]
+fn 未通過驗證者不反查() {
+    let 規則 = [派生!("^([zcs])h", "$1")];
+    let 驗證規則 = [regex!("^[a-y]+$").deref()];
+    let 轉寫法 = 測試轉寫法(&規則, &驗證規則);
+    let 轉寫碼 = |碼: &str| 對照輸入碼 {
+        字根碼原文: None,
+        轉寫碼原文: Some(碼.to_owned()),
+    };
+    assert!(轉寫碼("zhi").反查衆字根碼(&轉寫法).is_empty());
+    assert_eq!(轉寫碼("shi").反查衆字根碼(&轉寫法), ["shi", "si"]);
+}
+
+#[test]
+fn 未知運算() {
+    for 規則 in ["xfrom/a/b/", "/a/b/"] {
+        assert!(
+            matches!(解析拼寫運算(規則), Err(拼寫運算錯誤::未知運算(_))),
+            "{規則}"
+        );
+    }
+}
+
+#[test]
+fn 格式有誤() {
+    for 規則 in [
+        "xform",
+        "abc",
+        "xform/a/",
+        "xform/a/b/c/",
+        "xlit/ab/",
+        "erase/a/b/",
+        "derive/a/",
+    ] {
+        assert!(
+            matches!(解析拼寫運算(規則), Err(拼寫運算錯誤::格式有誤(_))),
+            "{規則}"
+        );
+    }
+}
+
+#[test]
+fn 字表長度不等() {
+    assert!(matches!(
+        解析拼寫運算("xlit/abc/xy/"),
+        Err(拼寫運算錯誤::字表長度不等(規則)) if 規則 == "xlit/abc/xy/"
+    ));
+}
+
+#[test]
+fn 正則表達式有誤() {
+    for 規則 in ["xform/(a/b/", "erase/[a/", "derive/a)/b/"] {
+        assert!(
+            matches!(解析拼寫運算(規則), Err(拼寫運算錯誤::正則表達式有誤(_))),
+            "{規則}"
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! 各輸入方案拼寫規則的對照測試
+//!
+//! 每個方案一份對照表: 「轉寫」列出字根碼經拼式轉寫規則所得的拼式,
+//! 「拆分」列出拼式經字根拆分規則所得的字根碼,
+//! 「表示」列出按鍵所得的字根碼經輸入碼表示規則所得的形式。
+//! 修改規則後若有音節走樣, 測試會逐條列出。
+//! 新增方案時, 在 `對照表` 中補一項即可; 漏寫則 `內建方案皆有對照` 不能通過。
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use strum::IntoEnumIterator;
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::spelling_algebra::施展拼寫運算;
+
+struct 拼寫對照 {
+    方案: 方案選項,
+    /// (字根碼, 拼式)
+    轉寫: &'static [(&'static str, &'static str)],
+    /// (拼式, 字根碼)
+    拆分: &'static [(&'static str, &'static str)],
+    /// (按鍵所得的字根碼, 經輸入碼表示規則所得的表示形式)
+    表示: &'static [(&'static str, &'static str)],
+}
+
+const 對照表: &[拼寫對照] = &[
+    拼寫對照 {
+        方案: 方案選項::宮保拼音,
+        轉寫: &[
+            ("ZFURO", "zhong"),
+            ("SHUARO", "shuang"),
+            ("CLUARO", "chuang"),
+            ("LDIANE", "niang"),
+            ("HNE", "heng"),
+            ("LÜE", "lüe"),
+            ("LDÜ", "nü"),
+            ("HÜE", "xue"),
+            ("GÜ", "ju"),
+            ("LÜ", "lü"),
+            ("LU", "lu"),
+            ("R", "er"),
+            ("Ü", "yu"),
+            ("IU", "yu"),
+            ("IR", "you"),
+            ("U", "wu"),
+            ("URO", "weng"),
+            ("IRO", "yong"),
+            ("GIRO", "jiong"),
+            ("Z", "zi"),
+            ("ZF", "zhi"),
+            ("HG", "ri"),
+            ("SI", "xi"),
+            ("SGU", "ku"),
+            ("CIE", "qie"),
+            ("DRE", "dei"),
+            ("TE", "te"),
+            ("AE", "a"),
+            ("ANE", "ang"),
+            ("NE", "eng"),
+            ("FB", "me"),
+            ("LD", "ne"),
+        ],
+        拆分: &[
+            ("zhong", "ZFURO"),
+            ("chuang", "CLUARO"),
+            ("niang", "LDIANE"),
+            ("lve", "LÜE"),
+            ("nv", "LDÜ"),
+            ("xue", "HÜE"),
+            ("qu", "KÜ"),
+            ("yu", "Ü"),
+            ("xi", "HI"),
+            ("jiao", "GIAO"),
+            ("ku", "KU"),
+            ("dei", "DR"),
+            ("ri", "HG"),
+            ("a", "AE"),
+        ],
+        表示: &[],
+    },
+    拼寫對照 {
+        方案: 方案選項::拉丁字母,
+        // 無拼式轉寫規則, 只驗拆分
+        轉寫: &[],
+        拆分: &[("hello", "HELLO"), ("a b", "A␣B")],
+        表示: &[],
+    },
+    拼寫對照 {
+        方案: 方案選項::上古漢語,
+        轉寫: &[],
+        拆分: &[],
+        表示: &[
+            ("精匣魚幽職", "莊藥"),
+            ("魚文", "影元"),
+            ("見侯蒸", "見東"),
+            ("並幫脂職", "明質"),
+            ("曉羣見之", "日之"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::早期中古漢語,
+        轉寫: &[],
+        拆分: &[],
+        表示: &[
+            ("精匣果止", "莊蟹"),
+            ("果曾", "影宕"),
+            ("心定流曾", "以通"),
+            ("曉羣臻", "云臻"),
+            ("見入", "見入"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::晚期中古漢語,
+        轉寫: &[],
+        拆分: &[],
+        表示: &[
+            ("幫氣果流", "並效"),
+            ("遇曾", "影江"),
+            ("見氣止曾", "羣梗"),
+            ("心曉見深", "日深"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::近古漢語,
+        轉寫: &[],
+        拆分: &[],
+        表示: &[
+            ("精非幫魚庚", "微東"),
+            ("家真", "影寒"),
+            ("心曉見車侵", "日廉"),
+            ("見家魚", "見蕭"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::現代漢語,
+        轉寫: &[],
+        拆分: &[],
+        表示: &[
+            ("精非", "照支"),
+            ("心曉", "審支"),
+            ("支", "影兒"),
+            ("齊麻痕", "影寒齊"),
+            ("非幫模支波", "明東"),
+            ("見魚歌", "見皆撮"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::粵語,
+        轉寫: &[],
+        拆分: &[],
+        表示: &[
+            ("非幫師修畢", "明先"),
+            ("孤威德", "云東"),
+            ("精非家威", "亞皆"),
+            ("曉見科德", "我角"),
+            ("家", "亞家"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::宮保粵拼,
+        轉寫: &[
+            ("w -i", "wai"),
+            ("f e-i", "fei"),
+            ("gw -u", "gwau"),
+            ("b i-t", "bit"),
+            ("eo-n", "eon"),
+            ("oe-k", "oek"),
+            ("d -u", "dau"),
+            ("z e-k", "zek"),
+        ],
+        拆分: &[
+            ("gwong", "SHGOJQ"),
+            ("kwai", "SHKJ"),
+            ("wai", "SHJ"),
+            ("jyut", "YR"),
+            ("jau", "CLW"),
+            ("ping", "PIJQ"),
+            ("fei", "FIJ"),
+            ("bit", "BIR"),
+            ("hoeng", "HVJQ"),
+            ("eoi", "VJ"),
+            ("zyu", "ZY"),
+            ("aa", "A"),
+            ("m", "FB"),
+            ("ng", "HG"),
+        ],
+        表示: &[
+            ("SHGOJQ", "gw o-ng"),
+            ("SHKJ", "kw -i"),
+            ("YR", "yu-t"),
+            ("CLW", "j -u"),
+            ("FIJ", "f ei"),
+            ("HVJQ", "h oe-ng"),
+            ("VJ", "eo-i"),
+            ("A", "aa"),
+            ("HG", "ng"),
+        ],
+    },
+    拼寫對照 {
+        方案: 方案選項::注音,
+        // 字根碼即拼式, 無拼寫規則可驗
+        轉寫: &[],
+        拆分: &[],
+        表示: &[],
+    },
+    拼寫對照 {
+        方案: 方案選項::動態能力注音,
+        轉寫: &[
+            ("〇ㄚ", "ㄚ"),
+            ("〇ㄦˊ", "ㄦˊ"),
+            ("ㄧㄚ", "ㄧㄚ"),
+            ("ㄅㄚ", "ㄅㄚ"),
+        ],
+        拆分: &[
+            ("ㄚ", "〇ㄚ"),
+            ("ㄦˊ", "〇ㄦˊ"),
+            ("ㄧㄚ", "ㄧㄚ"),
+            ("ㄅㄚ", "ㄅㄚ"),
+        ],
+        表示: &[],
+    },
+    拼寫對照 {
+        方案: 方案選項::宮保注音,
+        轉寫: &[
+            ("ㄓㄨㄥ", "zhong"),
+            ("ㄕㄨㄤ", "shuang"),
+            ("ㄌㄩㄝ", "lüe"),
+            ("ㄋㄩ", "nü"),
+            ("ㄐㄩㄥ", "jiong"),
+            ("ㄩㄥ", "yong"),
+            ("ㄧㄡ", "you"),
+            ("ㄨㄥ", "weng"),
+            ("ㄑㄩ", "qu"),
+            ("ㄦ", "er"),
+            ("ㄩ", "yu"),
+            ("ㄨ", "wu"),
+            ("ㄓ", "zhi"),
+            ("ㄗ", "zi"),
+            ("ㄇ", "me"),
+            ("ㄆ", "pu"),
+            ("ㄗㄏㄨ", "zhu"),
+            ("ㄅㄥ", "beng"),
+        ],
+        拆分: &[
+            ("zhong", "ㄓㄨㄥ"),
+            ("lüe", "ㄌㄩㄝ"),
+            ("yong", "ㄩㄥ"),
+            ("you", "ㄧㄡ"),
+            ("xun", "ㄒㄩㄣ"),
+            ("ju", "ㄐㄩ"),
+            ("zhu", "ㄓㄨ"),
+            ("pu", "ㄆㄨ"),
+            ("bu", "ㄅㄨ"),
+            ("ri", "ㄖ"),
+        ],
+        表示: &[],
+    },
+    拼寫對照 {
+        方案: 方案選項::倉頡,
+        // 字根碼即拼式, 無拼寫規則可驗
+        轉寫: &[],
+        拆分: &[],
+        表示: &[],
+    },
+    拼寫對照 {
+        方案: 方案選項::倉頡七曜並擊,
+        轉寫: &[
+            ("人金·土", "人金土"),
+            ("人·土", "人土"),
+            ("心·土", "心土"),
+            ("月·中", "月中"),
+            ("金", "金"),
+        ],
+        拆分: &[
+            ("人金土", "人·土"),
+            ("火弓卜", "火·卜"),
+            ("月中", "月·中"),
+            ("手手", "手·手"),
+            ("金", "金"),
+        ],
+        表示: &[],
+    },
+];
+
+/// 逐方案逐條比對, 收集所有不符之處, 以便一次看清規則改動的影響
+fn 逐條覈對(
+    比對: impl Fn(&拼寫對照, &typewriter::definition::轉寫法定義) -> Vec<String>
+) {
+    let 不符 = 對照表
+        .iter()
+        .flat_map(|對照| {
+            let 方案 = 生成方案定義(對照.方案, 輸入方案環境::default())
+                .expect("對照表中的方案應爲內建方案");
+            比對(對照, &方案.轉寫法)
+                .into_iter()
+                .map(|說明| format!("[{}] {}", 對照.方案.slug(), 說明))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert!(不符.is_empty(), "\n{}", 不符.join("\n"));
+}
+
+#[test]
+fn 內建方案皆有對照() {
+    let 缺漏 = 方案選項::iter()
+        .filter(|&選項| 生成方案定義(選項, 輸入方案環境::default()).is_some())
+        .filter(|&選項| !對照表.iter().any(|對照| 對照.方案 == 選項))
+        .map(|選項| 選項.slug().to_owned())
+        .collect::<Vec<_>>();
+    assert!(缺漏.is_empty(), "以下方案缺少拼寫對照表: {缺漏:?}");
+}
+
+#[test]
+fn 字根碼轉寫爲拼式() {
+    逐條覈對(|對照, 轉寫法| {
+        對照
+            .轉寫
+            .iter()
+            .filter_map(|&(字根碼, 拼式)| {
+                let 實得 = 轉寫法.字根碼轉寫爲拼式(字根碼);
+                (實得.as_deref() != Some(拼式)).then(|| format!("{字根碼} → {實得:?}, 應爲 {拼式}"))
+            })
+            .collect()
+    });
+}
+
+#[test]
+fn 拼式拆分爲字根碼() {
+    逐條覈對(|對照, 轉寫法| {
+        對照
+            .拆分
+            .iter()
+            .filter_map(|&(拼式, 字根碼)| {
+                let 實得 = 轉寫法.拼式拆分爲字根碼(拼式);
+                (實得.as_deref() != Some(字根碼))
+                    .then(|| format!("{拼式} → {實得:?}, 應爲 {字根碼}"))
+            })
+            .collect()
+    });
+}
+
+#[test]
+fn 字根碼的表示形式() {
+    逐條覈對(|對照, 轉寫法| {
+        對照
+            .表示
+            .iter()
+            .flat_map(|&(字根碼, 表示)| {
+                let 實得 = 施展拼寫運算(字根碼, 轉寫法.輸入碼表示);
+                if 實得.as_deref() != Some(表示) {
+                    return Some(format!("{字根碼} → {實得:?}, 應爲 {表示}"));
+                }
+                // 表示形式換回按鍵序列, 再行表示, 應得原樣
+                let 往返 = 施展拼寫運算(表示, 轉寫法.輸入碼鍵位)
+                    .and_then(|鍵位| 施展拼寫運算(&鍵位, 轉寫法.輸入碼表示));
+                (往返.as_deref() != Some(表示)).then(|| format!("{表示} 往返得 {往返:?}"))
+            })
+            .collect()
+    });
+}
@@ -0,0 +1,120 @@
+//! 方案數據文件載入測試
+
+#![allow(confusable_idents)]
+#![allow(mixed_script_confusables)]
+#![allow(non_snake_case)]
+#![allow(uncommon_codepoints)]
+
+use typewriter::gear::theory::{方案選項, 生成方案定義, 輸入方案環境};
+use typewriter::spelling_algebra::拼寫運算錯誤;
+use typewriter::theory_file::{方案文件錯誤, 載入方案文件};
+
+const 示例方案: &str = include_str!("../assets/theory/detenele.yaml");
+
+/// 在示例方案末尾追加一條拼式轉寫規則
+fn 追加轉寫規則(規則: &str) -> String {
+    示例方案.replacen(
+        "  拼式轉寫規則:\n",
+        &format!("  拼式轉寫規則:\n    - {規則}\n"),
+        1,
+    )
+}
+
+#[test]
+fn 示例方案與內建方案等價() {
+    let 載入 = 載入方案文件(示例方案).expect("示例方案應能載入");
+    let 內建 = 生成方案定義(方案選項::動態能力注音, 輸入方案環境::default()).unwrap();
+    assert_eq!(載入.字根表.len(), 內建.字根表.len());
+    for (甲, 乙) in 載入.字根表.iter().zip(內建.字根表) {
+        assert_eq!(甲.輸入碼, 乙.輸入碼);
+        assert_eq!(甲.鍵碼, 乙.鍵碼);
+        assert_eq!(甲.盤面, 乙.盤面);
+    }
+    for 字根碼 in ["ㄅㄚˉ", "〇ㄢˋ", "ㄓㄨㄤˇ", "〇ㄦˊ"] {
+        assert_eq!(
+            載入.轉寫法.字根碼轉寫爲拼式(字根碼),
+            內建.轉寫法.字根碼轉寫爲拼式(字根碼),
+            "{字根碼}"
+        );
+    }
+    for 拼式 in ["ㄚˉ", "ㄅㄚˉ", "ㄦˊ"] {
+        assert_eq!(
+            載入.轉寫法.拼式拆分爲字根碼(拼式),
+            內建.轉寫法.拼式拆分爲字根碼(拼式),
+            "{拼式}"
+        );
+    }
+}
+
+#[test]
+fn 同一文本只生成一次() {
+    let 甲 = 載入方案文件(示例方案).unwrap();
+    let 乙 = 載入方案文件(示例方案).unwrap();
+    assert!(std::ptr::eq(甲.字根表, 乙.字根表));
+    assert!(std::ptr::eq(甲.佈局, 乙.佈局));
+    let 丙 = 載入方案文件(&追加轉寫規則("xform/ˉ$//")).unwrap();
+    assert!(!std::ptr::eq(甲.字根表, 丙.字根表));
+}
+
+#[test]
+fn 可用拼寫運算式() {
+    let 文本 = 追加轉寫規則("xform/ˉ$//");
+    let 方案 = 載入方案文件(&文本).unwrap();
+    assert_eq!(
+        方案.轉寫法.字根碼轉寫爲拼式("ㄅㄚˉ").as_deref(),
+        Some("ㄅㄚ")
+    );
+}
+
+#[test]
+fn 轉寫字表長度不等() {
+    let 文本 = 追加轉寫規則("轉寫: [ㄅㄆ, b]");
+    assert!(matches!(
+        載入方案文件(&文本),
+        Err(方案文件錯誤::拼寫運算有誤(
+            拼寫運算錯誤::字表長度不等(_)
+        ))
+    ));
+    let 文本 = 追加轉寫規則("xlit/ㄅㄆ/b/");
+    assert!(matches!(
+        載入方案文件(&文本),
+        Err(方案文件錯誤::拼寫運算有誤(
+            拼寫運算錯誤::字表長度不等(_)
+        ))
+    ));
+}
+
+#[test]
+fn 正則表達式有誤() {
+    let 文本 = 追加轉寫規則("變換: [\"(ㄅ\", \"b\"]");
+    assert!(matches!(
+        載入方案文件(&文本),
+        Err(方案文件錯誤::正則表達式有誤(_))
+    ));
+}
+
+#[test]
+fn 未知盤面() {
+    let 文本 = 示例方案.replacen("- 基本盤面", "- 無此盤面", 1);
+    assert!(matches!(
+        載入方案文件(&文本),
+        Err(方案文件錯誤::未知盤面(名稱)) if 名稱 == "無此盤面"
+    ));
+}
+
+#[test]
+fn 未知字根() {
+    let 文本 = 示例方案.replacen("終止鍵: [ˉ,", "終止鍵: [ㄪ, ˉ,", 1);
+    assert!(matches!(
+        載入方案文件(&文本),
+        Err(方案文件錯誤::未知字根(字根)) if 字根 == "ㄪ"
+    ));
+}
+
+#[test]
+fn 格式有誤() {
+    assert!(matches!(
+        載入方案文件("名稱: [未閉合"),
+        Err(方案文件錯誤::格式有誤(_))
+    ));
+}